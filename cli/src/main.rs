@@ -0,0 +1,195 @@
+//! Renders a scenario with some inputs data, as the web interface would, so
+//! that the outputs can be checked in a CI.
+//!
+//! ```text
+//! live-handlebars [--strict] [--template <name>] <scenario> [<data.json>]
+//! ```
+
+use anyhow::{anyhow, bail, Context, Result};
+use live_handlebars_core::{
+    inputs::{
+        clear_hidden_inputs, clear_unavailable_selections, missing_required_paths,
+        update_computed_inputs,
+    },
+    scenario::{self, initial_inputs_data, Format, Scenario, Severity, MAIN_TEMPLATE, MAX_BASES},
+    template_engine::{render_each_element, Engine, TemplateEngine},
+    ArrayMerge, Path,
+};
+use serde_json::Value as JsonValue;
+use std::{fs, path::PathBuf, process};
+
+const USAGE: &str =
+    "Usage: live-handlebars [--strict] [--template <name>] <scenario> [<data.json>]";
+
+#[derive(Debug, Default)]
+struct Args {
+    scenario: PathBuf,
+    data: Option<PathBuf>,
+    template: Option<String>,
+    strict: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args::default();
+        let mut files = vec![];
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--strict" => parsed.strict = true,
+                "--template" => {
+                    parsed.template = Some(args.next().context("Missing the template name.")?)
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                _ if arg.starts_with("--") => bail!("Unknown option {}.", arg),
+                _ => files.push(PathBuf::from(arg)),
+            }
+        }
+        let mut files = files.into_iter();
+        parsed.scenario = files.next().context("Missing the scenario.")?;
+        parsed.data = files.next();
+        if files.next().is_some() {
+            bail!("Too many arguments.");
+        }
+        Ok(parsed)
+    }
+}
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {:#}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(e) = run(&args) {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<()> {
+    let scenario = load_scenario(&args.scenario)?;
+
+    let mut inputs_data = initial_inputs_data(&scenario)?;
+    if let Some(data) = &args.data {
+        let source = fs::read_to_string(data)
+            .with_context(|| format!("Failed to read {}", data.display()))?;
+        let data: JsonValue = serde_json::from_str(&source)
+            .with_context(|| format!("Invalid JSON in {}", data.display()))?;
+        inputs_data.merge_at(&Path::default(), data, ArrayMerge::Replace)?;
+        clear_hidden_inputs(&scenario.inputs, &mut inputs_data);
+        clear_unavailable_selections(&scenario.inputs, &mut inputs_data);
+        update_computed_inputs(&scenario.inputs, &mut inputs_data);
+    }
+
+    let missing = missing_required_paths(&scenario.inputs, &Path::default(), &inputs_data);
+    if !missing.is_empty() {
+        let missing: Vec<_> = missing.iter().map(ToString::to_string).collect();
+        bail!("These required inputs are empty: {}", missing.join(", "));
+    }
+
+    let mut engine = Engine::new_uninit();
+    engine.set_strict_mode(args.strict || scenario.strict_mode);
+    engine
+        .set_template(&scenario)
+        .map_err(|e| anyhow!("Failed to compile the template: {}", e))?;
+    let name = args.template.as_deref().unwrap_or(MAIN_TEMPLATE);
+    if !engine.outputs().iter().any(|output| output == name) {
+        bail!(
+            "Unknown template '{}', expected one of: {}.",
+            name,
+            engine.outputs().join(", ")
+        );
+    }
+    let rendered = match &scenario.render_each {
+        Some(render_each) => render_each_element(name, &inputs_data, render_each, &engine),
+        None => engine.render_named(name, &inputs_data),
+    }
+    .context("Failed to render the data")?;
+
+    for variable in &rendered.unresolved {
+        eprintln!("Warning: '{}' resolved to nothing.", variable);
+    }
+    print!("{}", rendered.output);
+    Ok(())
+}
+
+/// Loads a scenario as `App::load_scenario` does, except that the scenarios
+/// it extends are files, relative to it.
+fn load_scenario(path: &PathBuf) -> Result<Scenario> {
+    let mut json_data = read_scenario(path)?;
+    let mut depth = 0;
+    while let Some(base) = scenario::base_of(&json_data).map(PathBuf::from) {
+        if depth >= MAX_BASES {
+            bail!("Too many scenarios extend each other, maybe one of them extends itself.");
+        }
+        let base = path.parent().map_or(base.clone(), |dir| dir.join(&base));
+        json_data = scenario::extend(read_scenario(&base)?, json_data)?;
+        depth += 1;
+    }
+    if json_data.get("template").is_none() {
+        bail!("The scenario must have a template.");
+    }
+
+    let diagnostics = scenario::validate(&json_data);
+    for diagnostic in &diagnostics {
+        let severity = match diagnostic.severity {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+        };
+        match &diagnostic.path {
+            Some(path) => eprintln!("{} in '{}': {}", severity, path, diagnostic.message),
+            None => eprintln!("{}: {}", severity, diagnostic.message),
+        }
+    }
+    if diagnostics.iter().any(|d| d.is_error()) {
+        bail!("The scenario has errors.");
+    }
+
+    let mut scenario: Scenario =
+        serde_json::from_value(json_data).context("Failed to deserialize the scenario")?;
+    scenario.generate_inputs()?;
+    Ok(scenario)
+}
+
+fn read_scenario(path: &PathBuf) -> Result<JsonValue> {
+    let source =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let name = path.to_str();
+    let json_data = Format::detect(&source, name).parse(&source)?;
+    scenario::migrate(json_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_args() {
+        let args = parse(&[
+            "--template",
+            "values.yaml",
+            "app.yaml",
+            "--strict",
+            "data.json",
+        ])
+        .unwrap();
+        assert_eq!(args.scenario, PathBuf::from("app.yaml"));
+        assert_eq!(args.data, Some(PathBuf::from("data.json")));
+        assert_eq!(args.template.as_deref(), Some("values.yaml"));
+        assert!(args.strict);
+
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["app.yaml", "--template"]).is_err());
+        assert!(parse(&["app.yaml", "--unknown"]).is_err());
+        assert!(parse(&["app.yaml", "a.json", "b.json"]).is_err());
+    }
+}
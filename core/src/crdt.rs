@@ -0,0 +1,493 @@
+//! Merges the edits of inputs data made at the same time by several people,
+//! so that they all end up with the same data without losing what they
+//! typed.
+//!
+//! The texts are merged character by character, like a RGA (Replicated
+//! Growable Array), and the other values are replaced by the latest edit,
+//! ordered by Lamport clocks. The elements of an array can't be merged this
+//! way, so adding or removing some replaces the whole array. The edits of
+//! a text are lost when it is replaced or removed along with its parents.
+
+use crate::{prelude::*, InputsData, PatchOp, Path};
+use std::collections::HashMap;
+
+/// When an edit was made, unique to the replica which made it. The edits
+/// are ordered by their clocks, then by their sites.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash
+)]
+pub struct Stamp {
+    pub clock: u64,
+    pub site: String,
+}
+
+/// Identifies a character of a text, as the `n`th one of an insertion.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CharId(pub Stamp, pub usize);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "kind")]
+pub enum Edit {
+    /// Sets the value at `path`, or removes it if `value` is `None`.
+    Set {
+        stamp: Stamp,
+        path: Path,
+        value: Option<JsonValue>,
+    },
+    /// Deletes characters of the text at `path`, then inserts `insert` after
+    /// the character `after`, or at the start.
+    Text {
+        stamp: Stamp,
+        path: Path,
+        /// The `Set` the text comes from, see `Replica::since`, so that the
+        /// edits of a replaced text are ignored.
+        since: Stamp,
+        after: Option<CharId>,
+        insert: String,
+        delete: Vec<CharId>,
+    },
+}
+
+impl Edit {
+    pub fn path(&self) -> &Path {
+        match self {
+            Edit::Set { path, .. } | Edit::Text { path, .. } => path,
+        }
+    }
+
+    pub fn stamp(&self) -> &Stamp {
+        match self {
+            Edit::Set { stamp, .. } | Edit::Text { stamp, .. } => stamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Char {
+    id: CharId,
+    ch: char,
+    deleted: bool,
+}
+
+/// The characters of a text, including the deleted ones which the edits of
+/// the peers may refer to.
+#[derive(Debug, Clone)]
+struct Text {
+    since: Stamp,
+    chars: Vec<Char>,
+}
+
+impl Text {
+    fn new(since: Stamp, base: &str) -> Self {
+        let chars = base
+            .chars()
+            .enumerate()
+            .map(|(n, ch)| Char {
+                id: CharId(Stamp::default(), n),
+                ch,
+                deleted: false,
+            })
+            .collect();
+        Text { since, chars }
+    }
+
+    fn visible(&self) -> String {
+        self.chars
+            .iter()
+            .filter(|c| !c.deleted)
+            .map(|c| c.ch)
+            .collect()
+    }
+
+    /// Returns what changes `before` into `after`, as one deletion and one
+    /// insertion, and applies it.
+    fn diff(
+        &mut self,
+        stamp: &Stamp,
+        before: &str,
+        after: &str,
+    ) -> (Option<CharId>, String, Vec<CharId>) {
+        let before: Vec<char> = before.chars().collect();
+        let after: Vec<char> = after.chars().collect();
+        let prefix = before
+            .iter()
+            .zip(&after)
+            .take_while(|(b, a)| b == a)
+            .count();
+        let suffix = before[prefix..]
+            .iter()
+            .rev()
+            .zip(after[prefix..].iter().rev())
+            .take_while(|(b, a)| b == a)
+            .count();
+
+        let visible: Vec<&CharId> = self
+            .chars
+            .iter()
+            .filter(|c| !c.deleted)
+            .map(|c| &c.id)
+            .collect();
+        let delete: Vec<CharId> = visible[prefix..before.len() - suffix]
+            .iter()
+            .map(|&id| id.clone())
+            .collect();
+        let after_id = prefix.checked_sub(1).map(|i| visible[i].clone());
+        let insert: String = after[prefix..after.len() - suffix].iter().collect();
+
+        self.apply(stamp, after_id.as_ref(), &insert, &delete);
+        (after_id, insert, delete)
+    }
+
+    fn apply(&mut self, stamp: &Stamp, after: Option<&CharId>, insert: &str, delete: &[CharId]) {
+        for c in self.chars.iter_mut().filter(|c| delete.contains(&c.id)) {
+            c.deleted = true;
+        }
+        let mut pos = match after {
+            Some(after) => match self.chars.iter().position(|c| &c.id == after) {
+                Some(pos) => pos + 1,
+                None => return,
+            },
+            None => 0,
+        };
+        // The concurrent insertions at the same place are ordered by their
+        // stamps, the latest first, and the ones made after them follow them
+        let first = CharId(stamp.clone(), 0);
+        while pos < self.chars.len() && self.chars[pos].id > first {
+            pos += 1;
+        }
+        let inserted = insert.chars().enumerate().map(|(n, ch)| Char {
+            id: CharId(stamp.clone(), n),
+            ch,
+            deleted: false,
+        });
+        self.chars.splice(pos..pos, inserted);
+    }
+}
+
+/// The state of the merge kept by each peer, see the module documentation.
+#[derive(Debug, Clone)]
+pub struct Replica {
+    site: String,
+    clock: u64,
+    /// The latest `Set` of each path, which are kept to be applied again
+    /// after an older `Set` of their parents.
+    sets: HashMap<Path, (Stamp, Option<JsonValue>)>,
+    texts: HashMap<Path, Text>,
+}
+
+impl Replica {
+    /// `site` must be unique among the peers.
+    pub fn new(site: impl Into<String>) -> Self {
+        Replica {
+            site: site.into(),
+            clock: 0,
+            sets: HashMap::new(),
+            texts: HashMap::new(),
+        }
+    }
+
+    pub fn site(&self) -> &str {
+        &self.site
+    }
+
+    fn next_stamp(&mut self) -> Stamp {
+        self.clock += 1;
+        Stamp {
+            clock: self.clock,
+            site: self.site.clone(),
+        }
+    }
+
+    /// The stamp of the latest `Set` of `path` or of its parents, which the
+    /// text at `path` starts from.
+    fn since(&self, path: &Path) -> Stamp {
+        self.sets
+            .iter()
+            .filter(|(set, _)| path.starts_with(set))
+            .map(|(_, (stamp, _))| stamp)
+            .max()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the text at `path`, starting from its value in `data` if it
+    /// wasn't edited since its last `Set`.
+    fn text(&mut self, path: &Path, data: &InputsData) -> Option<&mut Text> {
+        let since = self.since(path);
+        let value = data.get_at(path)?.as_str()?;
+        let text = self
+            .texts
+            .entry(path.clone())
+            .and_modify(|text| {
+                if text.since != since {
+                    *text = Text::new(since.clone(), value);
+                }
+            })
+            .or_insert_with(|| Text::new(since.clone(), value));
+        Some(text)
+    }
+
+    /// Returns the edits changing `before` into `after`, for the peers.
+    pub fn edits_between(&mut self, before: &InputsData, after: &InputsData) -> Vec<Edit> {
+        let mut edits: Vec<Edit> = vec![];
+        for op in before.patch_to(after) {
+            let path = replaced_path(&op, before, after);
+            let covered = edits.iter().any(|edit| match edit {
+                Edit::Set { path: set, .. } => path.starts_with(set),
+                Edit::Text { .. } => false,
+            });
+            if covered {
+                continue;
+            }
+            let stamp = self.next_stamp();
+            if let (Some(JsonValue::String(b)), Some(JsonValue::String(a))) =
+                (before.get_at(&path), after.get_at(&path))
+            {
+                match self.text(&path, before) {
+                    // Unless it was changed without edits, eg. by a JSON Patch
+                    Some(text) if text.visible() == *b => {
+                        let (after, insert, delete) = text.diff(&stamp, b, a);
+                        let since = text.since.clone();
+                        edits.push(Edit::Text {
+                            stamp,
+                            path,
+                            since,
+                            after,
+                            insert,
+                            delete,
+                        });
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            let value = after.get_at(&path).cloned();
+            self.sets.retain(|set, _| !set.starts_with(&path));
+            self.sets
+                .insert(path.clone(), (stamp.clone(), value.clone()));
+            edits.push(Edit::Set { stamp, path, value });
+        }
+        edits
+    }
+
+    /// Applies the edits of a peer to `data`, which must have been changed
+    /// by the same edits as the data of the peer, in any order.
+    pub fn merge(&mut self, edits: &[Edit], data: &mut InputsData) {
+        for edit in edits {
+            self.clock = self.clock.max(edit.stamp().clock);
+            match edit {
+                Edit::Set { stamp, path, value } => self.merge_set(stamp, path, value, data),
+                Edit::Text {
+                    stamp,
+                    path,
+                    since,
+                    after,
+                    insert,
+                    delete,
+                } => {
+                    // Replaced since
+                    if *since != self.since(path) {
+                        continue;
+                    }
+                    if let Some(text) = self.text(path, data) {
+                        text.apply(stamp, after.as_ref(), insert, delete);
+                        let value = JsonValue::String(text.visible());
+                        set_value(data, path, Some(&value));
+                    }
+                }
+            }
+        }
+    }
+
+    fn merge_set(
+        &mut self,
+        stamp: &Stamp,
+        path: &Path,
+        value: &Option<JsonValue>,
+        data: &mut InputsData,
+    ) {
+        let replaced = self
+            .sets
+            .iter()
+            .any(|(set, (other, _))| path.starts_with(set) && other > stamp);
+        if replaced {
+            return;
+        }
+        self.sets
+            .retain(|set, (other, _)| !set.starts_with(path) || *other > *stamp);
+        self.sets
+            .insert(path.clone(), (stamp.clone(), value.clone()));
+
+        // The newer values inside it are kept
+        let mut newer: Vec<_> = self
+            .sets
+            .iter()
+            .filter(|(set, (other, _))| set.starts_with(path) && other > stamp)
+            .collect();
+        newer.sort_by(|(_, (a, _)), (_, (b, _))| a.cmp(b));
+        set_value(data, path, value.as_ref());
+        for (set, (_, value)) in newer {
+            set_value(data, set, value.as_ref());
+        }
+        for (text_path, text) in &self.texts {
+            if text_path.starts_with(path) && text.since == self.since(text_path) {
+                let value = JsonValue::String(text.visible());
+                set_value(data, text_path, Some(&value));
+            }
+        }
+    }
+}
+
+/// The path replaced by `op`: the array for the insertions and removals of
+/// elements, since they change the indexes of the others.
+fn replaced_path(op: &PatchOp, before: &InputsData, after: &InputsData) -> Path {
+    let path = op.path();
+    let parent = path.parent();
+    let in_array = |data: &InputsData| matches!(data.get_at(&parent), Some(JsonValue::Array(_)));
+    match op {
+        PatchOp::Replace { .. } | PatchOp::Test { .. } => path.clone(),
+        _ if in_array(before) || in_array(after) => parent,
+        _ => path.clone(),
+    }
+}
+
+/// Sets or removes the value at `path`, if its parent still exists.
+fn set_value(data: &mut InputsData, path: &Path, value: Option<&JsonValue>) {
+    let exists = data.get_at(path).is_some();
+    let op = match value {
+        Some(value) if exists => PatchOp::Replace {
+            path: path.clone(),
+            value: value.clone(),
+        },
+        Some(value) => PatchOp::Add {
+            path: path.clone(),
+            value: value.clone(),
+        },
+        None if exists => PatchOp::Remove { path: path.clone() },
+        None => return,
+    };
+    if let Err(e) = data.apply_patch(&[op]) {
+        debug!("Ignored an edit: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Makes the edits of `a` and `b` from `base`, then merges them both
+    /// ways.
+    fn merge_both(
+        base: JsonValue,
+        edit_a: impl Fn(&mut JsonValue),
+        edit_b: impl Fn(&mut JsonValue),
+    ) -> (InputsData, InputsData) {
+        let base = InputsData::from(base);
+        let (mut a, mut b) = (Replica::new("a"), Replica::new("b"));
+        let (mut data_a, mut data_b) = (base.clone(), base.clone());
+        edit_a(data_a.get_at_mut(&Path::default()).unwrap());
+        edit_b(data_b.get_at_mut(&Path::default()).unwrap());
+        let edits_a = a.edits_between(&base, &data_a);
+        let edits_b = b.edits_between(&base, &data_b);
+
+        // Through JSON, like between the peers
+        let edits_a: Vec<Edit> = serde_json::from_value(json!(edits_a)).unwrap();
+        a.merge(&edits_b, &mut data_a);
+        b.merge(&edits_a, &mut data_b);
+        (data_a, data_b)
+    }
+
+    #[test]
+    fn merge_texts() {
+        let (a, b) = merge_both(
+            json!({"name": "web server"}),
+            |data| data["name"] = json!("big web server"),
+            |data| data["name"] = json!("web servers"),
+        );
+        assert_eq!(a, b);
+        assert_eq!(a, InputsData::from(json!({"name": "big web servers"})));
+
+        // At the same place
+        let (a, b) = merge_both(
+            json!({"name": "ab"}),
+            |data| data["name"] = json!("axb"),
+            |data| data["name"] = json!("ayb"),
+        );
+        assert_eq!(a, b);
+        assert_eq!(a, InputsData::from(json!({"name": "ayxb"})));
+
+        // One after the other
+        let base = InputsData::from(json!({"name": ""}));
+        let (mut a, mut b) = (Replica::new("a"), Replica::new("b"));
+        let mut data_a = base.clone();
+        let mut data_b = base.clone();
+        for typed in &["h", "hi", "hi!"] {
+            let before = data_a.clone();
+            *data_a.get_at_mut(&Path::from("name")).unwrap() = json!(typed);
+            let edits = a.edits_between(&before, &data_a);
+            b.merge(&edits, &mut data_b);
+        }
+        assert_eq!(data_b, data_a);
+        let before = data_b.clone();
+        *data_b.get_at_mut(&Path::from("name")).unwrap() = json!("oh hi!");
+        a.merge(&b.edits_between(&before, &data_b), &mut data_a);
+        assert_eq!(data_a, InputsData::from(json!({"name": "oh hi!"})));
+    }
+
+    #[test]
+    fn latest_value_wins() {
+        let (a, b) = merge_both(
+            json!({"port": 80, "tls": false, "name": "x"}),
+            |data| data["port"] = json!(443),
+            |data| {
+                data["port"] = json!(8080);
+                data["tls"] = json!(true);
+            },
+        );
+        assert_eq!(a, b);
+        assert_eq!(a.get_at(&Path::from("tls")), Some(&json!(true)));
+        // Same clock, ordered by site
+        assert_eq!(a.get_at(&Path::from("port")), Some(&json!(8080)));
+
+        // A removed value isn't edited again
+        let (a, b) = merge_both(
+            json!({"server": {"name": "web"}}),
+            |data| data["server"]["name"] = json!("web1"),
+            |data| {
+                data.as_object_mut().unwrap().remove("server");
+            },
+        );
+        assert_eq!(a, b);
+        assert_eq!(a, InputsData::from(json!({})));
+    }
+
+    #[test]
+    fn replaced_arrays() {
+        let (a, b) = merge_both(
+            json!({"hosts": [{"name": "a"}, {"name": "b"}]}),
+            |data| data["hosts"][0]["name"] = json!("a1"),
+            |data| {
+                data["hosts"]
+                    .as_array_mut()
+                    .unwrap()
+                    .push(json!({"name": "c"}))
+            },
+        );
+        assert_eq!(a, b);
+        assert_eq!(
+            a,
+            InputsData::from(json!({"hosts": [{"name": "a"}, {"name": "b"}, {"name": "c"}]}))
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{template_engine::TemplateError, Path};
+use std::{error::Error, fmt};
+
+/// Error telling what failed, for the callers which handle some failures
+/// differently, eg. a failed render keeps the inputs data, while corrupt
+/// saved sessions are discarded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// The scenario isn't valid JSON or YAML, or doesn't have the fields of
+    /// a scenario.
+    ScenarioParse(String),
+    /// The template of the scenario, or one of its partials, doesn't compile.
+    TemplateCompile(TemplateError),
+    /// The template compiled, but failed to render the inputs data.
+    Render(String),
+    /// The saved sessions are corrupt, so they can't be restored.
+    Storage(String),
+    /// Nothing can be read or changed at this path of the inputs data.
+    PathAccess { path: Path, reason: String },
+}
+
+impl AppError {
+    pub fn path_access<T: ToString>(path: &Path, reason: T) -> Self {
+        AppError::PathAccess {
+            path: path.clone(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// A render error, with the messages of the errors which caused it.
+    pub fn render<E: Error>(context: &str, error: E) -> Self {
+        let mut reason = format!("{}: {}", context, error);
+        let mut source = error.source();
+        while let Some(e) = source {
+            reason = format!("{}: {}", reason, e);
+            source = e.source();
+        }
+        AppError::Render(reason)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::ScenarioParse(reason)
+            | AppError::Render(reason)
+            | AppError::Storage(reason) => write!(f, "{}", reason),
+            AppError::TemplateCompile(error) => write!(f, "{}", error),
+            AppError::PathAccess { path, reason } if path == &Path::default() => {
+                write!(f, "Invalid path: {}", reason)
+            }
+            AppError::PathAccess { path, reason } => {
+                write!(f, "Invalid path '{}': {}", path, reason)
+            }
+        }
+    }
+}
+
+impl Error for AppError {}
+
+impl From<TemplateError> for AppError {
+    fn from(e: TemplateError) -> Self {
+        AppError::TemplateCompile(e)
+    }
+}
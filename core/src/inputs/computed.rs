@@ -0,0 +1,114 @@
+use super::InputInfo;
+use crate::{impl_input_for, InputsData, Path};
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// A read-only value derived from other inputs, eg.
+/// `"compute": {"sum": ["price", "shipping"]}`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ComputedInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    pub compute: Computation,
+}
+
+/// How a computed value is derived. The paths are relative to the parent
+/// of the computed input.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Computation {
+    /// Adds the numbers (or numeric strings) at the given paths.
+    Sum(Vec<Path>),
+    /// Joins the non-empty values at the given paths.
+    Concat {
+        paths: Vec<Path>,
+        #[serde(default)]
+        separator: String,
+    },
+    /// Renders a Handlebars snippet with the parent's data.
+    Template(String),
+}
+
+impl ComputedInput {
+    pub fn compute(&self, data: &InputsData, key_base: &Path) -> Result<JsonValue, String> {
+        let values = |paths: &[Path]| -> Vec<JsonValue> {
+            paths
+                .iter()
+                .filter_map(|path| data.get_at(&(key_base + path)).cloned())
+                .collect()
+        };
+
+        Ok(match &self.compute {
+            Computation::Sum(paths) => {
+                let sum = values(paths).iter().try_fold(0., |sum, value| {
+                    match value {
+                        JsonValue::Null => Some(0.),
+                        JsonValue::Number(n) => n.as_f64(),
+                        JsonValue::String(s) if s.trim().is_empty() => Some(0.),
+                        JsonValue::String(s) => s.trim().parse().ok(),
+                        _ => None,
+                    }
+                    .map(|n| sum + n)
+                    .ok_or_else(|| format!("{} is not a number", value))
+                })?;
+                if sum.fract() == 0. && sum.abs() < (1u64 << 53) as f64 {
+                    JsonValue::from(sum as i64)
+                } else {
+                    JsonValue::from(sum)
+                }
+            }
+            Computation::Concat { paths, separator } => JsonValue::String(
+                values(paths)
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        JsonValue::Null => None,
+                        JsonValue::String(s) if s.is_empty() => None,
+                        JsonValue::String(s) => Some(s),
+                        value => Some(value.to_string()),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(separator),
+            ),
+            Computation::Template(template) => {
+                let parent = data.get_at(key_base).unwrap_or(&JsonValue::Null);
+                Handlebars::new()
+                    .render_template(template, parent)
+                    .map(JsonValue::String)
+                    .map_err(|e| e.to_string())?
+            }
+        })
+    }
+}
+
+impl_input_for!(ComputedInput);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn computations() {
+        let data = InputsData::from(json!({"o": {"a": 1, "b": "2.5", "c": "x"}}));
+        let base = Path::from("o");
+        let compute = |compute: JsonValue| {
+            let input: ComputedInput = serde_json::from_value(json!({
+                "key": "r", "name": "R", "compute": compute
+            }))
+            .unwrap();
+            input.compute(&data, &base)
+        };
+
+        assert_eq!(
+            compute(json!({"sum": ["a", "b", "missing"]})),
+            Ok(json!(3.5))
+        );
+        assert!(compute(json!({"sum": ["a", "c"]})).is_err());
+        assert_eq!(
+            compute(json!({"concat": {"paths": ["c", "a"], "separator": "-"}})),
+            Ok(json!("x-1"))
+        );
+        assert_eq!(compute(json!({"template": "{{c}}{{a}}"})), Ok(json!("x1")));
+    }
+}
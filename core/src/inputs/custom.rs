@@ -0,0 +1,132 @@
+use super::InputInfo;
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+thread_local! {
+    static PLUGINS: RefCell<HashMap<String, Rc<dyn InputPlugin>>> = RefCell::new(HashMap::new());
+}
+
+/// An input of a type added with `register_input_type`, eg.
+/// `{"type": "color", "key": "background", "name": "Background", "alpha": true}`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomInput {
+    /// The type the plugin of this input is registered as.
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(flatten)]
+    pub info: InputInfo,
+    /// The other fields of the input, which only its plugin knows about.
+    #[serde(flatten)]
+    pub options: Map<String, JsonValue>,
+}
+
+impl_input_for!(CustomInput);
+
+impl CustomInput {
+    pub fn plugin(&self) -> Option<Rc<dyn InputPlugin>> {
+        input_plugin(&self.type_name)
+    }
+
+    /// Checks a value with the plugin of this input, before it is put in the
+    /// inputs data.
+    pub fn validate(&self, value: &JsonValue) -> Result<(), String> {
+        match self.plugin() {
+            Some(plugin) => plugin.validate(self, value),
+            None => Err(format!("Unknown input type '{}'.", self.type_name)),
+        }
+    }
+}
+
+/// What the scenarios need to know about an input type added to the ones of
+/// `InputTypes`. The views of its inputs are registered by the app.
+pub trait InputPlugin {
+    /// The `type` of the inputs in the scenarios. A plugin with the type of
+    /// one of `InputTypes`, eg. `text`, replaces it.
+    fn type_name(&self) -> &str;
+
+    /// Checks the fields of an input when its scenario is loaded.
+    fn check(&self, _input: &CustomInput) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Checks a value before it is put in the inputs data.
+    fn validate(&self, _input: &CustomInput, _value: &JsonValue) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Adds an input type, for the scenarios loaded from now on. A plugin
+/// registered with the same type replaces the previous one.
+pub fn register_input_type(plugin: impl InputPlugin + 'static) {
+    let plugin: Rc<dyn InputPlugin> = Rc::new(plugin);
+    PLUGINS.with(|plugins| {
+        plugins
+            .borrow_mut()
+            .insert(plugin.type_name().to_owned(), plugin)
+    });
+}
+
+/// Returns the plugin registered for an input type, if any.
+pub fn input_plugin(type_name: &str) -> Option<Rc<dyn InputPlugin>> {
+    PLUGINS.with(|plugins| plugins.borrow().get(type_name).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{inputs::*, scenario};
+    use serde_json::json;
+
+    struct ColorPlugin;
+
+    impl InputPlugin for ColorPlugin {
+        fn type_name(&self) -> &str {
+            "color"
+        }
+
+        fn check(&self, input: &CustomInput) -> Result<(), String> {
+            match input.options.get("alpha") {
+                None | Some(JsonValue::Bool(_)) => Ok(()),
+                Some(_) => Err("'alpha' must be a boolean.".to_owned()),
+            }
+        }
+
+        fn validate(&self, _: &CustomInput, value: &JsonValue) -> Result<(), String> {
+            match value.as_str() {
+                Some(color) if color.starts_with('#') => Ok(()),
+                _ => Err("Expected a color, eg. #ff0000.".to_owned()),
+            }
+        }
+    }
+
+    #[test]
+    fn registered_input_types() {
+        let input = json!({"type": "color", "key": "bg", "name": "Background", "alpha": true});
+        assert!(serde_json::from_value::<InputTypes>(input.clone()).is_err());
+
+        register_input_type(ColorPlugin);
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([input])).unwrap();
+        match &inputs[0] {
+            InputTypes::Custom(custom) => assert_eq!(custom.options["alpha"], json!(true)),
+            other => panic!("Not a custom input: {:?}", other),
+        }
+        assert_eq!(serde_json::to_value(&inputs[0]).unwrap()["type"], "color");
+        assert_eq!(
+            validate_at(&inputs, &Path::from("bg"), &json!("red")),
+            Err(ValidationError::Custom(
+                "Expected a color, eg. #ff0000.".to_owned()
+            ))
+        );
+        assert_eq!(
+            validate_at(&inputs, &Path::from("bg"), &json!("#f00")),
+            Ok(())
+        );
+
+        let invalid = json!({"type": "color", "key": "bg", "name": "Background", "alpha": 1});
+        assert!(serde_json::from_value::<InputTypes>(invalid).is_err());
+
+        let scenario = json!({"template": "{{bg}}", "inputs": [input]});
+        assert!(scenario::validate(&scenario).iter().all(|d| !d.is_error()));
+    }
+}
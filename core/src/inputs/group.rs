@@ -9,6 +9,9 @@ pub struct GroupInput {
     pub inputs: Vec<InputTypes>,
     #[serde(default)]
     pub show_disable_toggle: bool,
+    /// Whether the group starts collapsed, only showing its title.
+    #[serde(default)]
+    pub collapsed: bool,
 }
 
 impl_input_for!(GroupInput);
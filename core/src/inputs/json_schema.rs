@@ -0,0 +1,236 @@
+//! Generates the inputs of a scenario from a JSON Schema, for the teams which
+//! already describe their data with one.
+//!
+//! Only a subset of JSON Schema is supported: the `type`s, `enum`, `items`,
+//! `properties`, `required`, the length and range constraints, `title`,
+//! `description` and `default`.
+
+use super::InputTypes;
+use crate::prelude::*;
+use serde_json::{json, Map};
+
+/// Converts the properties of an object schema into inputs.
+pub fn inputs_from_schema(schema: &JsonValue) -> Result<Vec<InputTypes>> {
+    let definitions = properties_to_inputs(schema, "")?;
+    serde_json::from_value(JsonValue::Array(definitions))
+        .context("Failed to convert the schema into inputs.")
+}
+
+fn properties_to_inputs(schema: &JsonValue, path: &str) -> Result<Vec<JsonValue>> {
+    let properties = match schema.get("properties") {
+        Some(JsonValue::Object(properties)) => properties,
+        Some(_) => bail!("The properties of '{}' must be an object.", path),
+        None => bail!("Expected an object schema with properties at '{}'.", path),
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(JsonValue::as_array)
+        .map(|required| required.iter().filter_map(JsonValue::as_str).collect())
+        .unwrap_or_default();
+
+    properties
+        .iter()
+        .map(|(key, property)| {
+            let path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            property_to_input(key, property, required.contains(&key.as_str()), &path)
+        })
+        .collect()
+}
+
+fn property_to_input(
+    key: &str,
+    schema: &JsonValue,
+    required: bool,
+    path: &str,
+) -> Result<JsonValue> {
+    let mut input = Map::new();
+    input.insert("key".to_owned(), key.into());
+    let name = schema
+        .get("title")
+        .and_then(JsonValue::as_str)
+        .unwrap_or(key);
+    input.insert("name".to_owned(), name.into());
+    for field in &["description", "default"] {
+        if let Some(value) = schema.get(*field) {
+            input.insert((*field).to_owned(), value.clone());
+        }
+    }
+    if required {
+        input.insert("required".to_owned(), true.into());
+    }
+
+    let mut set = |field: &str, value: JsonValue| {
+        input.insert(field.to_owned(), value);
+    };
+    match (schema_type(schema, path)?, schema.get("enum")) {
+        ("string", Some(values)) => {
+            set("type", "select".into());
+            set("options", enum_options(values, path)?);
+        }
+        ("string", None) => {
+            let secret = schema.get("format") == Some(&json!("password"));
+            set("type", if secret { "secret" } else { "text" }.into());
+            if let Some(validation) = string_validation(schema) {
+                set("validation", validation);
+            }
+        }
+        (kind @ "integer", None) | (kind @ "number", None) => {
+            set("type", "number".into());
+            set(
+                "mode",
+                if kind == "integer" {
+                    "integer"
+                } else {
+                    "float"
+                }
+                .into(),
+            );
+            for (keyword, field) in &[
+                ("minimum", "min"),
+                ("maximum", "max"),
+                ("multipleOf", "step"),
+            ] {
+                if let Some(value) = schema.get(*keyword) {
+                    set(field, value.clone());
+                }
+            }
+        }
+        ("boolean", None) => set("type", "boolean".into()),
+        ("object", None) => {
+            set("type", "group".into());
+            set("inputs", properties_to_inputs(schema, path)?.into());
+        }
+        ("array", None) => {
+            let items = schema
+                .get("items")
+                .ok_or_else(|| anyhow!("The array '{}' must have items.", path))?;
+            match (schema_type(items, path)?, items.get("enum")) {
+                ("string", Some(values)) => {
+                    set("type", "multi_select".into());
+                    set("options", enum_options(values, path)?);
+                }
+                ("string", None) => set("type", "tags".into()),
+                ("object", None) => {
+                    set("type", "list".into());
+                    set("inputs", properties_to_inputs(items, path)?.into());
+                    for (keyword, field) in &[("minItems", "min_items"), ("maxItems", "max_items")]
+                    {
+                        if let Some(value) = schema.get(*keyword) {
+                            set(field, value.clone());
+                        }
+                    }
+                }
+                (kind, _) => bail!("Unsupported arrays of {} at '{}'.", kind, path),
+            }
+        }
+        (kind, Some(_)) => bail!("Unsupported enum of type {} at '{}'.", kind, path),
+        (kind, None) => bail!("Unsupported type {} at '{}'.", kind, path),
+    }
+    Ok(JsonValue::Object(input))
+}
+
+/// Returns the `type` of a schema, guessing it for the objects and the enums
+/// which don't specify it.
+fn schema_type<'a>(schema: &'a JsonValue, path: &str) -> Result<&'a str> {
+    match schema.get("type") {
+        Some(JsonValue::String(kind)) => Ok(kind),
+        Some(_) => bail!("Only a single type is supported at '{}'.", path),
+        None if schema.get("properties").is_some() => Ok("object"),
+        None if schema.get("enum").is_some() => Ok("string"),
+        None => bail!("Missing type at '{}'.", path),
+    }
+}
+
+fn enum_options(values: &JsonValue, path: &str) -> Result<JsonValue> {
+    let values = values
+        .as_array()
+        .ok_or_else(|| anyhow!("The enum of '{}' must be an array.", path))?;
+    values
+        .iter()
+        .map(|value| match value {
+            JsonValue::String(value) => Ok(json!({ "value": value })),
+            _ => bail!("Only enums of strings are supported at '{}'.", path),
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(JsonValue::from)
+}
+
+fn string_validation(schema: &JsonValue) -> Option<JsonValue> {
+    let mut validation = Map::new();
+    for (keyword, field) in &[
+        ("minLength", "min_length"),
+        ("maxLength", "max_length"),
+        ("pattern", "pattern"),
+    ] {
+        if let Some(value) = schema.get(*keyword) {
+            validation.insert((*field).to_owned(), value.clone());
+        }
+    }
+    if validation.is_empty() {
+        None
+    } else {
+        Some(validation.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::Input;
+
+    #[test]
+    fn convert_schema() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": { "type": "string", "title": "Name", "maxLength": 20 },
+                "port": { "type": "integer", "minimum": 1, "default": 80 },
+                "protocol": { "enum": ["tcp", "udp"] },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "owner": {
+                    "type": "object",
+                    "properties": { "email": { "type": "string" } }
+                },
+                "users": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": { "properties": { "admin": { "type": "boolean" } } }
+                }
+            }
+        });
+        let inputs = inputs_from_schema(&schema).unwrap();
+
+        let keys: Vec<String> = inputs.iter().map(|i| i.key().to_string()).collect();
+        assert_eq!(keys, ["name", "port", "protocol", "tags", "owner", "users"]);
+        match &inputs[0] {
+            InputTypes::Text(text) => {
+                assert_eq!(text.name(), "Name");
+                assert!(text.required());
+                assert_eq!(text.validation().and_then(|v| v.max_length), Some(20));
+            }
+            other => panic!("Expected a text input, got {:?}", other),
+        }
+        assert!(
+            matches!(&inputs[1], InputTypes::Number(n) if n.default_value() == Some(&json!(80)))
+        );
+        assert!(matches!(&inputs[2], InputTypes::Select(s) if s.options.len() == 2));
+        assert!(matches!(&inputs[3], InputTypes::Tags(_)));
+        assert!(matches!(&inputs[4], InputTypes::Group(g) if g.inputs.len() == 1));
+        assert!(matches!(&inputs[5], InputTypes::List(l) if l.min_items == Some(1)));
+    }
+
+    #[test]
+    fn reject_unsupported_schema() {
+        assert!(inputs_from_schema(&json!({ "type": "string" })).is_err());
+        let schema = json!({ "properties": { "a": { "type": ["string", "null"] } } });
+        assert!(inputs_from_schema(&schema).is_err());
+        let schema =
+            json!({ "properties": { "a": { "type": "array", "items": { "type": "integer" } } } });
+        assert!(inputs_from_schema(&schema).is_err());
+    }
+}
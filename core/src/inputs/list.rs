@@ -1,6 +1,7 @@
 use super::{InputInfo, InputTypes};
 use crate::impl_input_for;
 use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ListInput {
@@ -8,9 +9,16 @@ pub struct ListInput {
     pub info: InputInfo,
     pub inputs: Vec<InputTypes>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub min_length: Option<u64>,
+    pub min_items: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub max_length: Option<u64>,
+    pub max_items: Option<usize>,
+}
+
+impl ListInput {
+    /// The numbers of elements the list can have.
+    pub fn allowed_sizes(&self) -> RangeInclusive<usize> {
+        self.min_items.unwrap_or(0)..=self.max_items.unwrap_or(usize::MAX)
+    }
 }
 
 impl_input_for!(ListInput);
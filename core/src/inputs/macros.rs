@@ -0,0 +1,66 @@
+#[macro_export]
+macro_rules! impl_input_for {
+    ($type:ty) => {
+        impl $crate::inputs::Input for $type {
+            fn key(&self) -> &$crate::Path {
+                &self.info.key
+            }
+
+            fn name(&self) -> &str {
+                &self.info.name
+            }
+
+            fn description(&self) -> Option<&str> {
+                self.info.description.as_deref()
+            }
+
+            fn placeholder(&self) -> Option<&str> {
+                self.info.placeholder.as_deref()
+            }
+
+            fn help_url(&self) -> Option<&str> {
+                self.info.help_url.as_deref()
+            }
+
+            fn required(&self) -> bool {
+                self.info.required
+            }
+
+            fn validation(&self) -> Option<&$crate::inputs::Validation> {
+                self.info.validation.as_ref()
+            }
+
+            fn visible_when(&self) -> Option<&$crate::inputs::Condition> {
+                self.info.visible_when.as_ref()
+            }
+
+            fn default_value(&self) -> Option<&serde_json::Value> {
+                self.info.default.as_ref()
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! for_all_inputtypes_variants {
+    ($self:expr, $ident:ident => $expr:expr) => {
+        match $self {
+            $crate::inputs::InputTypes::Text($ident) => $expr,
+            $crate::inputs::InputTypes::Secret($ident) => $expr,
+            $crate::inputs::InputTypes::Boolean($ident) => $expr,
+            $crate::inputs::InputTypes::Number($ident) => $expr,
+            $crate::inputs::InputTypes::Quantity($ident) => $expr,
+            $crate::inputs::InputTypes::Slider($ident) => $expr,
+            $crate::inputs::InputTypes::Select($ident) => $expr,
+            $crate::inputs::InputTypes::MultiSelect($ident) => $expr,
+            $crate::inputs::InputTypes::RemoteSelect($ident) => $expr,
+            $crate::inputs::InputTypes::Radio($ident) => $expr,
+            $crate::inputs::InputTypes::Group($ident) => $expr,
+            $crate::inputs::InputTypes::List($ident) => $expr,
+            $crate::inputs::InputTypes::Table($ident) => $expr,
+            $crate::inputs::InputTypes::Tags($ident) => $expr,
+            $crate::inputs::InputTypes::Computed($ident) => $expr,
+            $crate::inputs::InputTypes::Custom($ident) => $expr,
+        }
+    };
+}
@@ -0,0 +1,102 @@
+//! Masks formatting structured texts as they are typed, eg.
+//! `"mask": "(999) 999-9999"`.
+//!
+//! In a mask, `9` stands for a digit, `a` for a letter, `h` for an
+//! hexadecimal digit and `*` for a letter or a digit; the other characters are
+//! inserted as they are. `phone`, `mac` and `ipv4` are predefined masks.
+
+/// Formats what is typed to follow the mask, dropping the characters which
+/// don't fit.
+pub fn apply_mask(mask: &str, raw: &str) -> String {
+    match mask {
+        "phone" => apply_pattern("(999) 999-9999", raw),
+        "mac" => apply_pattern("hh:hh:hh:hh:hh:hh", raw).to_ascii_lowercase(),
+        "ipv4" => format_ipv4(raw),
+        pattern => apply_pattern(pattern, raw),
+    }
+}
+
+/// Returns which characters can be typed in place of a character of a mask,
+/// or `None` if it has to be inserted as is.
+fn placeholder(c: char) -> Option<fn(&char) -> bool> {
+    match c {
+        '9' => Some(char::is_ascii_digit),
+        'a' => Some(char::is_ascii_alphabetic),
+        'h' => Some(char::is_ascii_hexdigit),
+        '*' => Some(char::is_ascii_alphanumeric),
+        _ => None,
+    }
+}
+
+fn apply_pattern(pattern: &str, raw: &str) -> String {
+    let mut formatted = String::new();
+    // The literals are only inserted when something is typed after them, so
+    // that they can be erased.
+    let mut literals = String::new();
+    let mut raw = raw.chars().peekable();
+    for c in pattern.chars() {
+        match placeholder(c) {
+            Some(accepts) => match raw.by_ref().find(accepts) {
+                Some(typed) => {
+                    formatted.push_str(&literals);
+                    literals.clear();
+                    formatted.push(typed);
+                }
+                None => break,
+            },
+            None => {
+                if raw.peek() == Some(&c) {
+                    raw.next();
+                }
+                literals.push(c);
+            }
+        }
+    }
+    formatted
+}
+
+/// Keeps up to four numbers from 0 to 255, separated by dots.
+fn format_ipv4(raw: &str) -> String {
+    let mut numbers = vec![String::new()];
+    for c in raw.chars() {
+        let last = numbers.len() - 1;
+        if c.is_ascii_digit() {
+            let extended = format!("{}{}", numbers[last], c);
+            if extended.len() <= 3 && extended.parse::<u8>().is_ok() {
+                numbers[last] = extended;
+            } else if numbers.len() < 4 {
+                numbers.push(c.to_string());
+            } else {
+                break;
+            }
+        } else if c == '.' && !numbers[last].is_empty() && numbers.len() < 4 {
+            numbers.push(String::new());
+        }
+    }
+    numbers.join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_masks() {
+        assert_eq!(apply_mask("phone", ""), "");
+        assert_eq!(apply_mask("phone", "555"), "(555");
+        assert_eq!(apply_mask("phone", "5551"), "(555) 1");
+        assert_eq!(apply_mask("phone", "(555) 123-45678"), "(555) 123-4567");
+        assert_eq!(apply_mask("phone", "55x5"), "(555");
+
+        assert_eq!(apply_mask("mac", "AABBccDD"), "aa:bb:cc:dd");
+        assert_eq!(apply_mask("mac", "aa:gb"), "aa:b");
+
+        assert_eq!(apply_mask("ipv4", "192.168.1.1"), "192.168.1.1");
+        assert_eq!(apply_mask("ipv4", "1921681"), "192.168.1");
+        assert_eq!(apply_mask("ipv4", "300"), "30.0");
+        assert_eq!(apply_mask("ipv4", "10..0.a"), "10.0.");
+        assert_eq!(apply_mask("ipv4", "1.2.3.255.1"), "1.2.3.255");
+
+        assert_eq!(apply_mask("aa-999", "ab12"), "ab-12");
+    }
+}
@@ -0,0 +1,729 @@
+use crate::{for_all_inputtypes_variants, prelude::*, ArrayMerge, InputsData, Path};
+use serde::{de::Error as _, Deserializer, Serializer};
+use std::ops::RangeInclusive;
+
+mod boolean;
+mod computed;
+mod custom;
+mod group;
+mod json_schema;
+mod list;
+mod macros;
+mod mask;
+mod multi_select;
+mod number;
+mod quantity;
+mod radio;
+mod secret;
+mod select;
+mod slider;
+mod table;
+mod tags;
+mod text;
+mod validator;
+pub use boolean::*;
+pub use computed::*;
+pub use custom::*;
+pub use group::*;
+pub use json_schema::*;
+pub use list::*;
+pub use mask::*;
+pub use multi_select::*;
+pub use number::*;
+pub use quantity::*;
+pub use radio::*;
+pub use secret::*;
+pub use select::*;
+pub use slider::*;
+pub use table::*;
+pub use tags::*;
+pub use text::*;
+pub use validator::*;
+
+/// The types of inputs, by their `type` in the scenarios. Other types can be
+/// added with `register_input_type`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+#[serde(remote = "Self")]
+pub enum InputTypes {
+    Text(TextInput),
+    Secret(SecretInput),
+    Boolean(BooleanInput),
+    Number(NumberInput),
+    /// A number with a unit, stored in the canonical unit.
+    Quantity(QuantityInput),
+    Slider(SliderInput),
+    Select(SelectInput),
+    MultiSelect(MultiSelectInput),
+    RemoteSelect(RemoteSelectInput),
+    Radio(RadioInput),
+    Group(GroupInput),
+    /// List differs from groups in that the number of input can
+    /// varry, eg. it can be used to prompt for a list of persons
+    /// of unknown size.
+    List(ListInput),
+    /// A list of objects edited as a grid.
+    Table(TableInput),
+    /// A simpler list of strings, edited as chips.
+    Tags(TagsInput),
+    /// A read-only value derived from other inputs.
+    Computed(ComputedInput),
+    /// An input of a type added with `register_input_type`.
+    #[serde(skip)]
+    Custom(CustomInput),
+}
+
+impl Serialize for InputTypes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            InputTypes::Custom(custom) => custom.serialize(serializer),
+            _ => InputTypes::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for InputTypes {
+    /// The registered types come first, so that a plugin can replace one of
+    /// the types of `InputTypes`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = JsonValue::deserialize(deserializer)?;
+        let plugin = value
+            .get("type")
+            .and_then(JsonValue::as_str)
+            .and_then(input_plugin);
+        let plugin = match plugin {
+            Some(plugin) => plugin,
+            None => return InputTypes::deserialize(value).map_err(D::Error::custom),
+        };
+
+        let custom = CustomInput::deserialize(value).map_err(D::Error::custom)?;
+        plugin
+            .check(&custom)
+            .map_err(|e| D::Error::custom(format!("Invalid input '{}': {}", custom.info.key, e)))?;
+        Ok(InputTypes::Custom(custom))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputInfo {
+    pub key: Path,
+    pub name: String,
+    /// Markdown text shown in the input's help tooltip.
+    pub description: Option<String>,
+    /// Text shown in the empty field, instead of the input's name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    /// Link to a longer documentation, shown in the help tooltip.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help_url: Option<String>,
+    /// The rendered template can't be exported while the input is empty.
+    #[serde(default)]
+    pub required: bool,
+    /// Rules the value must follow to be put in the inputs data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation: Option<Validation>,
+    /// Value put in the inputs data when the scenario is loaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<JsonValue>,
+    /// Only shows the input when another one has a given value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible_when: Option<Condition>,
+}
+
+/// `"visible_when": {"path": "tls.enabled", "equals": true}`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Condition {
+    /// Path of the value to check, relative to the input's parent.
+    pub path: Path,
+    pub equals: JsonValue,
+    /// Removes the value of the input from the data while it is hidden.
+    #[serde(default)]
+    pub clear: bool,
+}
+
+impl Condition {
+    /// A missing value is `null`, which also matches `false`, as unchecked
+    /// booleans aren't in the data.
+    pub fn is_met(&self, data: &InputsData, key_base: &Path) -> bool {
+        let value = data
+            .get_at(&(key_base + &self.path))
+            .unwrap_or(&JsonValue::Null);
+        value == &self.equals || (value.is_null() && self.equals == JsonValue::Bool(false))
+    }
+}
+
+pub trait Input {
+    fn key(&self) -> &Path;
+
+    fn name(&self) -> &str;
+
+    fn description(&self) -> Option<&str>;
+
+    fn placeholder(&self) -> Option<&str>;
+
+    fn help_url(&self) -> Option<&str>;
+
+    fn required(&self) -> bool;
+
+    fn validation(&self) -> Option<&Validation>;
+
+    fn visible_when(&self) -> Option<&Condition>;
+
+    fn default_value(&self) -> Option<&JsonValue>;
+
+    fn is_visible(&self, data: &InputsData, key_base: &Path) -> bool {
+        match self.visible_when() {
+            Some(condition) => condition.is_met(data, key_base),
+            None => true,
+        }
+    }
+}
+
+impl InputTypes {
+    /// Returns the inputs repeated for each element of the array edited by a
+    /// list or a table.
+    pub fn element_inputs(&self) -> &[InputTypes] {
+        match self {
+            InputTypes::List(list) => &list.inputs,
+            InputTypes::Table(table) => &table.columns,
+            _ => &[],
+        }
+    }
+
+    /// Returns whether the name or the key of this input, or of one of its
+    /// nested inputs, contains `filter`, which must be lowercase.
+    pub fn matches_filter(&self, filter: &str) -> bool {
+        let nested = match self {
+            InputTypes::Group(group) => &group.inputs,
+            _ => self.element_inputs(),
+        };
+        name_or_key_matches(self, filter) || nested.iter().any(|i| i.matches_filter(filter))
+    }
+
+    /// Returns the numbers of elements the array edited by a list or a table
+    /// can have.
+    pub fn allowed_sizes(&self) -> RangeInclusive<usize> {
+        match self {
+            InputTypes::List(list) => list.allowed_sizes(),
+            InputTypes::Table(table) => table.allowed_sizes(),
+            _ => 0..=usize::MAX,
+        }
+    }
+}
+
+impl Input for InputTypes {
+    fn key(&self) -> &Path {
+        for_all_inputtypes_variants! { self, i => i.key() }
+    }
+
+    fn name(&self) -> &str {
+        for_all_inputtypes_variants! { self, i => i.name() }
+    }
+
+    fn description(&self) -> Option<&str> {
+        for_all_inputtypes_variants! { self, i => i.description() }
+    }
+
+    fn placeholder(&self) -> Option<&str> {
+        for_all_inputtypes_variants! { self, i => i.placeholder() }
+    }
+
+    fn help_url(&self) -> Option<&str> {
+        for_all_inputtypes_variants! { self, i => i.help_url() }
+    }
+
+    fn required(&self) -> bool {
+        for_all_inputtypes_variants! { self, i => i.required() }
+    }
+
+    fn validation(&self) -> Option<&Validation> {
+        for_all_inputtypes_variants! { self, i => i.validation() }
+    }
+
+    fn visible_when(&self) -> Option<&Condition> {
+        for_all_inputtypes_variants! { self, i => i.visible_when() }
+    }
+
+    fn default_value(&self) -> Option<&JsonValue> {
+        for_all_inputtypes_variants! { self, i => i.default_value() }
+    }
+}
+
+/// Puts the default values of the inputs in the data, including the ones
+/// nested in groups, and adds the elements the lists require.
+pub fn seed_defaults(inputs: &[InputTypes], key_base: &Path, data: &mut InputsData) -> Result<()> {
+    for input in inputs {
+        let key = key_base + input.key();
+        if let Some(default) = input.default_value() {
+            data.merge_at(&key, default.clone(), ArrayMerge::Replace)
+                .with_context(|| format!("Invalid default value for '{}'", key))?;
+        }
+        match input {
+            InputTypes::Group(group) => seed_defaults(&group.inputs, &key, data)?,
+            InputTypes::List(_) | InputTypes::Table(_) if *input.allowed_sizes().start() > 0 => {
+                let len = data
+                    .get_at(&key)
+                    .and_then(JsonValue::as_array)
+                    .map_or(0, Vec::len);
+                data.resize_array_at(&key, len, input.allowed_sizes())?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Puts back the default value of the input whose value is at `path`, and
+/// the ones of its children.
+pub fn reset_at(inputs: &[InputTypes], path: &Path, data: &mut InputsData) -> Result<()> {
+    let input = input_at(inputs, path).ok_or_else(|| anyhow!("No input at '{}'", path))?;
+    if data.get_at(path).is_some() {
+        data.remove_at(path)?;
+    }
+    // The key of the input can have several segments
+    let segments: Vec<&str> = path.get_segments().collect();
+    let key_len = input.key().get_segments().count();
+    let key_base = Path::from_segments(&segments[..segments.len().saturating_sub(key_len)]);
+    seed_defaults(std::slice::from_ref(input), &key_base, data)
+}
+
+/// Calls `visit` with each input, its key and the key it is relative to,
+/// walking into the groups and into each element of the lists and tables.
+/// The children of an input are skipped when `visit` returns `false`.
+pub fn walk_inputs(
+    inputs: &[InputTypes],
+    key_base: &Path,
+    data: &InputsData,
+    visit: &mut impl FnMut(&InputTypes, &Path, &Path) -> bool,
+) {
+    for input in inputs {
+        let key = key_base + input.key();
+        if !visit(input, &key, key_base) {
+            continue;
+        }
+        match input {
+            InputTypes::Group(group) => walk_inputs(&group.inputs, &key, data, visit),
+            InputTypes::List(_) | InputTypes::Table(_) => {
+                let len = data
+                    .get_at(&key)
+                    .and_then(JsonValue::as_array)
+                    .map_or(0, Vec::len);
+                for i in 0..len {
+                    let key = &key + Path::from(i);
+                    walk_inputs(input.element_inputs(), &key, data, visit);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the paths of the values entered in secret inputs, which mustn't
+/// be displayed.
+pub fn secret_paths(inputs: &[InputTypes], key_base: &Path, data: &InputsData) -> Vec<Path> {
+    let mut paths = vec![];
+    walk_inputs(inputs, key_base, data, &mut |input, key, _| {
+        if let InputTypes::Secret(_) = input {
+            paths.push(key.clone());
+        }
+        true
+    });
+    paths
+}
+
+/// Returns the paths of the values which don't fit a new version of the
+/// inputs, because their input was removed or changed of type.
+pub fn incompatible_paths(
+    inputs: &[InputTypes],
+    new_inputs: &[InputTypes],
+    key_base: &Path,
+    data: &InputsData,
+) -> Vec<Path> {
+    let mut paths = vec![];
+    for input in inputs {
+        let key = key_base + input.key();
+        if data.get_at(&key).is_none() {
+            continue;
+        }
+        let new_input = match new_inputs.iter().find(|new| new.key() == input.key()) {
+            Some(new) if std::mem::discriminant(new) == std::mem::discriminant(input) => new,
+            _ => {
+                paths.push(key);
+                continue;
+            }
+        };
+
+        match (input, new_input) {
+            (InputTypes::Custom(custom), InputTypes::Custom(new_custom))
+                if custom.type_name != new_custom.type_name =>
+            {
+                paths.push(key)
+            }
+            (InputTypes::Group(group), InputTypes::Group(new_group)) => paths.extend(
+                incompatible_paths(&group.inputs, &new_group.inputs, &key, data),
+            ),
+            (InputTypes::List(_), _) | (InputTypes::Table(_), _) => {
+                let len = data
+                    .get_at(&key)
+                    .and_then(JsonValue::as_array)
+                    .map_or(0, Vec::len);
+                for i in 0..len {
+                    paths.extend(incompatible_paths(
+                        input.element_inputs(),
+                        new_input.element_inputs(),
+                        &(&key + Path::from(i)),
+                        data,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    paths
+}
+
+/// Returns whether the name or the key of the input contains `filter`, which
+/// must be lowercase.
+pub fn name_or_key_matches(input: &impl Input, filter: &str) -> bool {
+    input.name().to_lowercase().contains(filter)
+        || input.key().to_string().to_lowercase().contains(filter)
+}
+
+/// Returns the paths of the hidden inputs whose value must be cleared, see
+/// `Condition::clear`.
+pub fn hidden_paths_to_clear(
+    inputs: &[InputTypes],
+    key_base: &Path,
+    data: &InputsData,
+) -> Vec<Path> {
+    let mut paths = vec![];
+    walk_inputs(inputs, key_base, data, &mut |input, key, key_base| {
+        if input.is_visible(data, key_base) {
+            return true;
+        }
+        if matches!(input.visible_when(), Some(condition) if condition.clear) {
+            paths.push(key.clone());
+        }
+        false
+    });
+    paths
+}
+
+/// Returns the paths of the selections which aren't available anymore since
+/// the value they depend on changed, see `SelectInput::depends_on`.
+pub fn unavailable_selections(
+    inputs: &[InputTypes],
+    key_base: &Path,
+    data: &InputsData,
+) -> Vec<Path> {
+    let mut paths = vec![];
+    walk_inputs(inputs, key_base, data, &mut |input, key, key_base| {
+        match input {
+            InputTypes::Select(select) if select.is_selection_unavailable(data, key_base) => {
+                paths.push(key.clone())
+            }
+            _ => {}
+        }
+        true
+    });
+    paths
+}
+
+/// Returns the paths of the visible required inputs which have no value.
+pub fn missing_required_paths(
+    inputs: &[InputTypes],
+    key_base: &Path,
+    data: &InputsData,
+) -> Vec<Path> {
+    let mut paths = vec![];
+    walk_inputs(inputs, key_base, data, &mut |input, key, key_base| {
+        if !input.is_visible(data, key_base) {
+            return false;
+        }
+        let can_be_empty = matches!(input, InputTypes::Boolean(_) | InputTypes::Computed(_));
+        if input.required() && !can_be_empty && is_empty_value(data.get_at(key)) {
+            paths.push(key.clone());
+        }
+        true
+    });
+    paths
+}
+
+/// Returns the paths of the groups, with whether they start collapsed, see
+/// `GroupInput::collapsed`.
+pub fn group_paths(inputs: &[InputTypes], key_base: &Path, data: &InputsData) -> Vec<(Path, bool)> {
+    let mut paths = vec![];
+    walk_inputs(inputs, key_base, data, &mut |input, key, _| {
+        if let InputTypes::Group(group) = input {
+            paths.push((key.clone(), group.collapsed));
+        }
+        true
+    });
+    paths
+}
+
+fn is_empty_value(value: Option<&JsonValue>) -> bool {
+    match value {
+        None | Some(JsonValue::Null) => true,
+        Some(JsonValue::String(s)) => s.trim().is_empty(),
+        Some(JsonValue::Array(array)) => array.is_empty(),
+        Some(_) => false,
+    }
+}
+
+/// Computes the values of the computed inputs, along with their path.
+pub fn computed_values(
+    inputs: &[InputTypes],
+    key_base: &Path,
+    data: &InputsData,
+) -> Vec<(Path, Result<JsonValue, String>)> {
+    let mut values = vec![];
+    walk_inputs(inputs, key_base, data, &mut |input, key, key_base| {
+        if let InputTypes::Computed(computed) = input {
+            values.push((key.clone(), computed.compute(data, key_base)));
+        }
+        true
+    });
+    values
+}
+
+/// Removes the values of the inputs which are hidden by a condition asking
+/// for it.
+pub fn clear_hidden_inputs(inputs: &[InputTypes], inputs_data: &mut InputsData) {
+    for path in hidden_paths_to_clear(inputs, &Path::default(), inputs_data) {
+        if inputs_data.get_at(&path).is_some() {
+            if let Err(e) = inputs_data.remove_at(&path) {
+                warn!("Failed to clear hidden input at '{}': {:?}", path, e);
+            }
+        }
+    }
+}
+
+/// Removes the selected values whose option was filtered out by a change of
+/// the value they depend on.
+pub fn clear_unavailable_selections(inputs: &[InputTypes], inputs_data: &mut InputsData) {
+    // Clearing a selection can make the options of another one unavailable
+    loop {
+        let paths = unavailable_selections(inputs, &Path::default(), inputs_data);
+        if paths.is_empty() {
+            break;
+        }
+        for path in paths {
+            if let Err(e) = inputs_data.remove_at(&path) {
+                warn!("Failed to clear the selection at '{}': {:?}", path, e);
+                return;
+            }
+        }
+    }
+}
+
+/// Stores the values of the computed inputs, see `ComputedInput`.
+pub fn update_computed_inputs(inputs: &[InputTypes], inputs_data: &mut InputsData) {
+    for (path, value) in computed_values(inputs, &Path::default(), inputs_data) {
+        let value = value.unwrap_or_else(|e| {
+            debug!("Failed to compute the value at '{}': {}", path, e);
+            JsonValue::Null
+        });
+        if let Err(e) = inputs_data.insert_at(&path, value) {
+            warn!("Failed to save the computed value at '{}': {:?}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn visible_when() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([
+            { "key": "tls", "name": "TLS", "type": "boolean" },
+            {
+                "key": "cert", "name": "Cert", "type": "text",
+                "visible_when": { "path": "tls", "equals": true, "clear": true }
+            },
+            {
+                "key": "port", "name": "Port", "type": "number",
+                "visible_when": { "path": "tls", "equals": false }
+            }
+        ]))
+        .unwrap();
+        let root = Path::default();
+
+        let data = InputsData::from(json!({"cert": "x", "port": 80}));
+        assert!(!inputs[1].is_visible(&data, &root));
+        assert!(inputs[2].is_visible(&data, &root));
+        assert_eq!(
+            hidden_paths_to_clear(&inputs, &root, &data),
+            [&root + &Path::from("cert")]
+        );
+
+        let data = InputsData::from(json!({"tls": true, "cert": "x", "port": 80}));
+        assert!(inputs[1].is_visible(&data, &root));
+        assert!(!inputs[2].is_visible(&data, &root));
+        assert!(hidden_paths_to_clear(&inputs, &root, &data).is_empty());
+    }
+
+    #[test]
+    fn defaults_are_seeded() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([
+            { "key": "port", "name": "Port", "type": "number", "default": 80 },
+            { "key": "host", "name": "Host", "type": "text" },
+            {
+                "key": "tls", "name": "TLS", "type": "group",
+                "inputs": [
+                    { "key": "enabled", "name": "Enabled", "type": "boolean", "default": true }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        let mut data = InputsData::default();
+        seed_defaults(&inputs, &Path::default(), &mut data).unwrap();
+        assert_eq!(
+            data,
+            InputsData::from(json!({"port": 80, "tls": {"enabled": true}}))
+        );
+    }
+
+    #[test]
+    fn missing_required_paths() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([
+            { "key": "name", "name": "Name", "type": "text", "required": true },
+            { "key": "tags", "name": "Tags", "type": "tags", "required": true },
+            { "key": "active", "name": "Active", "type": "boolean", "required": true },
+            {
+                "key": "hosts", "name": "Hosts", "type": "list",
+                "inputs": [{ "key": "ip", "name": "IP", "type": "text", "required": true }]
+            }
+        ]))
+        .unwrap();
+        let root = Path::default();
+
+        let data = InputsData::from(json!({
+            "name": " ",
+            "tags": ["a"],
+            "hosts": [{"ip": "10.0.0.1"}, {}]
+        }));
+        assert_eq!(
+            super::missing_required_paths(&inputs, &root, &data),
+            [Path::from("name"), Path::from("hosts.1.ip")]
+        );
+    }
+
+    #[test]
+    fn reset_at() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([{
+            "key": "tls", "name": "TLS", "type": "group",
+            "inputs": [
+                { "key": "enabled", "name": "Enabled", "type": "boolean", "default": true },
+                { "key": "cert", "name": "Cert", "type": "text" }
+            ]
+        }]))
+        .unwrap();
+
+        let mut data = InputsData::from(json!({"tls": {"enabled": false, "cert": "x"}}));
+        super::reset_at(&inputs, &Path::from("tls.enabled"), &mut data).unwrap();
+        assert_eq!(
+            data,
+            InputsData::from(json!({"tls": {"enabled": true, "cert": "x"}}))
+        );
+        super::reset_at(&inputs, &Path::from("tls.cert"), &mut data).unwrap();
+        assert_eq!(data, InputsData::from(json!({"tls": {"enabled": true}})));
+
+        let mut data = InputsData::from(json!({"tls": {"enabled": false, "cert": "x"}}));
+        super::reset_at(&inputs, &Path::from("tls"), &mut data).unwrap();
+        assert_eq!(data, InputsData::from(json!({"tls": {"enabled": true}})));
+
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([{
+            "key": "server.name", "name": "Name", "type": "text", "default": "web"
+        }]))
+        .unwrap();
+        let mut data = InputsData::from(json!({"server": {"name": "db"}}));
+        super::reset_at(&inputs, &Path::from("server.name"), &mut data).unwrap();
+        assert_eq!(data, InputsData::from(json!({"server": {"name": "web"}})));
+    }
+
+    #[test]
+    fn incompatible_paths() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([
+            { "key": "port", "name": "Port", "type": "number" },
+            { "key": "host", "name": "Host", "type": "text" },
+            {
+                "key": "users", "name": "Users", "type": "list",
+                "inputs": [
+                    { "key": "name", "name": "Name", "type": "text" },
+                    { "key": "admin", "name": "Admin", "type": "boolean" }
+                ]
+            }
+        ]))
+        .unwrap();
+        let new_inputs: Vec<InputTypes> = serde_json::from_value(json!([
+            { "key": "port", "name": "Port", "type": "text" },
+            {
+                "key": "users", "name": "Users", "type": "list",
+                "inputs": [{ "key": "name", "name": "Login", "type": "text" }]
+            }
+        ]))
+        .unwrap();
+
+        let data = InputsData::from(json!({
+            "port": 80,
+            "host": "localhost",
+            "users": [{"name": "a", "admin": true}, {"name": "b"}]
+        }));
+        assert_eq!(
+            super::incompatible_paths(&inputs, &new_inputs, &Path::default(), &data),
+            [
+                Path::from("port"),
+                Path::from("host"),
+                Path::from("users.0.admin")
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_filter() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([
+            { "key": "port", "name": "Listen port", "type": "number" },
+            {
+                "key": "tls", "name": "TLS", "type": "group",
+                "inputs": [{ "key": "cert_path", "name": "Certificate", "type": "text" }]
+            }
+        ]))
+        .unwrap();
+
+        assert!(inputs[0].matches_filter("listen"));
+        assert!(inputs[0].matches_filter("port"));
+        assert!(!inputs[0].matches_filter("cert"));
+        assert!(inputs[1].matches_filter("cert"));
+        assert!(!name_or_key_matches(&inputs[1], "cert"));
+    }
+
+    #[test]
+    fn group_paths() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([
+            {
+                "key": "servers", "name": "Servers", "type": "list",
+                "inputs": [{
+                    "key": "tls", "name": "TLS", "type": "group", "collapsed": true,
+                    "inputs": [{ "key": "cert", "name": "Certificate", "type": "text" }]
+                }]
+            },
+            { "key": "logs", "name": "Logs", "type": "group", "inputs": [] }
+        ]))
+        .unwrap();
+        let data = InputsData::from(json!({ "servers": [{}, {}] }));
+
+        assert_eq!(
+            super::group_paths(&inputs, &Path::default(), &data),
+            [
+                (Path::from("servers.0.tls"), true),
+                (Path::from("servers.1.tls"), true),
+                (Path::from("logs"), false),
+            ]
+        );
+    }
+}
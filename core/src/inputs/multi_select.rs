@@ -0,0 +1,14 @@
+use super::{InputInfo, SelectOption};
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+
+/// Several choices among predefined values, stored as an array of strings
+/// in the order of the options.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultiSelectInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    pub options: Vec<SelectOption>,
+}
+
+impl_input_for!(MultiSelectInput);
@@ -0,0 +1,87 @@
+use super::InputInfo;
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+pub type JsonNumber = serde_json::Number;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NumberInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<JsonNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<JsonNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<JsonNumber>,
+    #[serde(default)]
+    pub mode: NumberMode,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberMode {
+    #[default]
+    Float,
+    Integer,
+}
+
+impl NumberInput {
+    /// Parses what is typed in the input, an empty value being `null`.
+    pub fn parse(&self, raw: &str) -> Result<JsonValue, String> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Ok(JsonValue::Null);
+        }
+
+        let number: JsonNumber = match self.mode {
+            NumberMode::Integer => raw
+                .parse::<i64>()
+                .map(Into::into)
+                .map_err(|_| "Expected an integer.".to_owned())?,
+            NumberMode::Float => raw
+                .parse::<f64>()
+                .ok()
+                .and_then(JsonNumber::from_f64)
+                .ok_or_else(|| "Expected a number.".to_owned())?,
+        };
+
+        let value = number.as_f64().unwrap_or_default();
+        if let Some(min) = self.min.as_ref().filter(|min| min.as_f64() > Some(value)) {
+            return Err(format!("Must be at least {}.", min));
+        }
+        if let Some(max) = self.max.as_ref().filter(|max| max.as_f64() < Some(value)) {
+            return Err(format!("Must be at most {}.", max));
+        }
+        Ok(JsonValue::Number(number))
+    }
+}
+
+impl_input_for!(NumberInput);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_number() {
+        let input: NumberInput = serde_json::from_value(json!({
+            "key": "n", "name": "N", "min": 0, "max": 10.5
+        }))
+        .unwrap();
+        assert_eq!(input.parse(""), Ok(JsonValue::Null));
+        assert_eq!(input.parse(" 2.5 "), Ok(json!(2.5)));
+        assert!(input.parse("abc").is_err());
+        assert!(input.parse("-1").is_err());
+        assert!(input.parse("11").is_err());
+
+        let input = NumberInput {
+            mode: NumberMode::Integer,
+            ..input
+        };
+        assert_eq!(input.parse("3"), Ok(json!(3)));
+        assert!(input.parse("2.5").is_err());
+    }
+}
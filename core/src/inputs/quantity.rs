@@ -0,0 +1,105 @@
+use super::InputInfo;
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// A number typed in one of several units, eg. MB or GB, and stored in the
+/// canonical one so that templates don't need to convert it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuantityInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    /// Name of the unit of the value stored in the inputs data.
+    pub canonical_unit: String,
+    pub units: Vec<Unit>,
+}
+
+/// `{"name": "GB", "factor": 1024}` when the canonical unit is MB.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    /// How many canonical units there are in one of this unit.
+    pub factor: f64,
+}
+
+impl QuantityInput {
+    /// The names of the units which can be chosen, starting with the
+    /// canonical one.
+    pub fn unit_names(&self) -> Vec<&str> {
+        let mut names = vec![self.canonical_unit.as_str()];
+        for unit in &self.units {
+            if !names.contains(&unit.name.as_str()) {
+                names.push(&unit.name);
+            }
+        }
+        names
+    }
+
+    fn factor(&self, unit: &str) -> f64 {
+        self.units
+            .iter()
+            .find(|u| u.name == unit)
+            .map_or(1.0, |u| u.factor)
+    }
+
+    /// Parses what is typed in the given unit, returning the value in the
+    /// canonical unit. An empty value is `null`.
+    pub fn parse(&self, raw: &str, unit: &str) -> Result<JsonValue, String> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Ok(JsonValue::Null);
+        }
+
+        let value = raw
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| "Expected a number.".to_owned())?;
+        Ok(to_json_number(value * self.factor(unit)))
+    }
+
+    /// Converts a value of the inputs data to the given unit.
+    pub fn display(&self, value: &JsonValue, unit: &str) -> Option<String> {
+        let value = value.as_f64()? / self.factor(unit);
+        Some(to_json_number(value).to_string())
+    }
+}
+
+/// Avoids writing `1536.0` when the value is an integer.
+fn to_json_number(value: f64) -> JsonValue {
+    // Rounds away the errors of the conversions, eg. 0.1 * 3
+    let rounded = (value * 1e9).round() / 1e9;
+    if rounded.fract() == 0.0 && rounded.abs() < i64::MAX as f64 {
+        JsonValue::from(rounded as i64)
+    } else {
+        JsonValue::from(rounded)
+    }
+}
+
+impl_input_for!(QuantityInput);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn convert_quantity() {
+        let input: QuantityInput = serde_json::from_value(json!({
+            "key": "memory", "name": "Memory", "canonical_unit": "MB",
+            "units": [{ "name": "GB", "factor": 1024 }, { "name": "KB", "factor": 0.0009765625 }]
+        }))
+        .unwrap();
+        assert_eq!(input.unit_names(), ["MB", "GB", "KB"]);
+
+        assert_eq!(input.parse("", "GB"), Ok(JsonValue::Null));
+        assert_eq!(input.parse("1.5", "GB"), Ok(json!(1536)));
+        assert_eq!(input.parse("512", "KB"), Ok(json!(0.5)));
+        assert_eq!(input.parse("3", "MB"), Ok(json!(3)));
+        assert!(input.parse("abc", "MB").is_err());
+
+        assert_eq!(input.display(&json!(1536), "GB"), Some("1.5".to_owned()));
+        assert_eq!(input.display(&json!(2), "KB"), Some("2048".to_owned()));
+        assert_eq!(input.display(&json!("x"), "MB"), None);
+    }
+}
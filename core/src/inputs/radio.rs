@@ -0,0 +1,13 @@
+use super::{InputInfo, SelectOption};
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+
+/// Mutually exclusive options, all displayed at once.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RadioInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    pub options: Vec<SelectOption>,
+}
+
+impl_input_for!(RadioInput);
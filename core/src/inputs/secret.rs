@@ -0,0 +1,13 @@
+use super::InputInfo;
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+
+/// A masked text field, eg. for passwords or tokens, whose value is hidden
+/// everywhere but in the rendered template.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SecretInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+}
+
+impl_input_for!(SecretInput);
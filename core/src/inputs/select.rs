@@ -0,0 +1,110 @@
+use super::{Input, InputInfo};
+use crate::{impl_input_for, InputsData, Path};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// A choice among predefined values, eg. an environment or a log level.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SelectInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    pub options: Vec<SelectOption>,
+    /// Path of the value which filters the options, eg. a region for a list
+    /// of availability zones. Relative to the input's parent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Path>,
+}
+
+impl SelectInput {
+    /// Returns the options which can be chosen given the value of the
+    /// `depends_on` path.
+    pub fn available_options(&self, data: &InputsData, key_base: &Path) -> Vec<&SelectOption> {
+        let parent_value = match &self.depends_on {
+            Some(path) => data.get_at(&(key_base + path)).unwrap_or(&JsonValue::Null),
+            None => return self.options.iter().collect(),
+        };
+        self.options
+            .iter()
+            .filter(|option| {
+                option.for_values.is_empty() || option.for_values.contains(parent_value)
+            })
+            .collect()
+    }
+
+    /// Whether the selected value isn't one of the available options anymore.
+    pub fn is_selection_unavailable(&self, data: &InputsData, key_base: &Path) -> bool {
+        match data.get_str_at(&(key_base + self.key())) {
+            Ok(Some(selected)) => !self
+                .available_options(data, key_base)
+                .iter()
+                .any(|option| option.value == selected),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SelectOption {
+    /// What is written in the inputs data.
+    pub value: String,
+    /// What is displayed, the value itself by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Only offers the option when the value of the input's `depends_on`
+    /// path is one of these.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub for_values: Vec<JsonValue>,
+}
+
+impl SelectOption {
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.value)
+    }
+}
+
+/// Like a select input, with options fetched from a URL returning a JSON
+/// array of strings or `{"value": .., "label": ..}` objects.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemoteSelectInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    pub url: String,
+}
+
+impl_input_for!(SelectInput);
+impl_input_for!(RemoteSelectInput);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn dependent_options() {
+        let input: SelectInput = serde_json::from_value(json!({
+            "key": "zone", "name": "Zone", "depends_on": "region",
+            "options": [
+                { "value": "eu-1a", "for_values": ["eu"] },
+                { "value": "us-1a", "for_values": ["us"] },
+                { "value": "any" }
+            ]
+        }))
+        .unwrap();
+        let root = Path::default();
+        let values = |data: &InputsData| -> Vec<&str> {
+            input
+                .available_options(data, &root)
+                .iter()
+                .map(|option| option.value.as_str())
+                .collect()
+        };
+
+        let data = InputsData::from(json!({"region": "eu", "zone": "us-1a"}));
+        assert_eq!(values(&data), ["eu-1a", "any"]);
+        assert!(input.is_selection_unavailable(&data, &root));
+
+        let data = InputsData::from(json!({"zone": "any"}));
+        assert_eq!(values(&data), ["any"]);
+        assert!(!input.is_selection_unavailable(&data, &root));
+    }
+}
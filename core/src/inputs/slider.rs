@@ -0,0 +1,16 @@
+use super::{InputInfo, JsonNumber};
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+
+/// A number between bounds, picked with a slider.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SliderInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    pub min: JsonNumber,
+    pub max: JsonNumber,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<JsonNumber>,
+}
+
+impl_input_for!(SliderInput);
@@ -0,0 +1,26 @@
+use super::{InputInfo, InputTypes};
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// A list of objects edited as a grid, with a row per element and a column
+/// per input.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TableInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    pub columns: Vec<InputTypes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_items: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<usize>,
+}
+
+impl TableInput {
+    /// The numbers of rows the table can have.
+    pub fn allowed_sizes(&self) -> RangeInclusive<usize> {
+        self.min_items.unwrap_or(0)..=self.max_items.unwrap_or(usize::MAX)
+    }
+}
+
+impl_input_for!(TableInput);
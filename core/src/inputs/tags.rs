@@ -2,12 +2,11 @@ use super::InputInfo;
 use crate::impl_input_for;
 use serde::{Deserialize, Serialize};
 
+/// Free-form strings, typed one at a time and stored as an array.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct TextInput {
+pub struct TagsInput {
     #[serde(flatten)]
     pub info: InputInfo,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub validate_regex: Option<String>, // TODO: use a regex
 }
 
-impl_input_for!(TextInput);
+impl_input_for!(TagsInput);
@@ -0,0 +1,65 @@
+use super::{apply_mask, InputInfo, Validation};
+use crate::impl_input_for;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TextInput {
+    #[serde(flatten)]
+    pub info: InputInfo,
+    /// Regular expression the whole value must match, if not empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Shown when the value doesn't match the pattern.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern_hint: Option<String>,
+    /// Formats the value as it is typed, see `apply_mask`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mask: Option<String>,
+}
+
+impl TextInput {
+    /// Applies the mask, if any, to what is typed.
+    pub fn normalize(&self, raw: &str) -> String {
+        match &self.mask {
+            Some(mask) => apply_mask(mask, raw),
+            None => raw.to_owned(),
+        }
+    }
+
+    /// Checks the value against the pattern, returning the hint otherwise.
+    /// The pattern is checked like the one of `Validation`.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        if self.pattern.is_none() {
+            return Ok(());
+        }
+        let validation = Validation {
+            pattern: self.pattern.clone(),
+            message: self.pattern_hint.clone(),
+            ..Validation::default()
+        };
+        validation
+            .validate(&JsonValue::String(value.to_owned()))
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl_input_for!(TextInput);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validate_text() {
+        let input: TextInput = serde_json::from_value(json!({
+            "key": "t", "name": "T", "pattern": "\\d+|none", "pattern_hint": "A number"
+        }))
+        .unwrap();
+        assert_eq!(input.validate(""), Ok(()));
+        assert_eq!(input.validate("42"), Ok(()));
+        assert_eq!(input.validate("none"), Ok(()));
+        assert_eq!(input.validate("42a"), Err("A number".to_owned()));
+    }
+}
@@ -0,0 +1,257 @@
+use super::{Input, InputTypes};
+use crate::Path;
+use core::fmt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::{cell::RefCell, collections::HashMap};
+
+thread_local! {
+    /// The patterns compiled by `Validation::check_pattern`, since they are
+    /// checked at each keystroke.
+    static PATTERNS: RefCell<HashMap<String, Result<Regex, String>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Rules checked before a value is put in the inputs data, eg.
+/// `"validation": {"min_length": 3, "pattern": "[a-z]+"}`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Validation {
+    /// Minimum number of characters of a text, or of elements of a list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    /// Maximum number of characters of a text, or of elements of a list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Regular expression the whole text, or every text of a list, must
+    /// match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Shown instead of the generated error messages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    TooShort {
+        min: usize,
+        length: usize,
+    },
+    TooLong {
+        max: usize,
+        length: usize,
+    },
+    PatternMismatch {
+        pattern: String,
+    },
+    InvalidPattern(String),
+    /// The custom message of the validation rules.
+    Custom(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::TooShort { min, length } => {
+                write!(f, "Must have a length of at least {}, got {}.", min, length)
+            }
+            ValidationError::TooLong { max, length } => {
+                write!(f, "Must have a length of at most {}, got {}.", max, length)
+            }
+            ValidationError::PatternMismatch { pattern } => {
+                write!(f, "Must match the pattern: {}", pattern)
+            }
+            ValidationError::InvalidPattern(e) => {
+                write!(f, "Invalid pattern in the scenario: {}", e)
+            }
+            ValidationError::Custom(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Validation {
+    /// Checks a value against the rules. Empty values are accepted, see
+    /// `InputInfo::required` to reject them.
+    pub fn validate(&self, value: &JsonValue) -> Result<(), ValidationError> {
+        let (length, texts): (usize, Vec<&str>) = match value {
+            JsonValue::Null => return Ok(()),
+            JsonValue::String(s) if s.is_empty() => return Ok(()),
+            JsonValue::String(s) => (s.chars().count(), vec![s]),
+            JsonValue::Array(values) => (
+                values.len(),
+                values.iter().filter_map(JsonValue::as_str).collect(),
+            ),
+            _ => return Ok(()),
+        };
+
+        let result = self
+            .check_length(length)
+            .and_then(|()| self.check_pattern(&texts));
+        match (result, &self.message) {
+            (Err(ValidationError::InvalidPattern(e)), _) => Err(ValidationError::InvalidPattern(e)),
+            (Err(_), Some(message)) => Err(ValidationError::Custom(message.clone())),
+            (result, _) => result,
+        }
+    }
+
+    fn check_length(&self, length: usize) -> Result<(), ValidationError> {
+        match (self.min_length, self.max_length) {
+            (Some(min), _) if length < min => Err(ValidationError::TooShort { min, length }),
+            (_, Some(max)) if length > max => Err(ValidationError::TooLong { max, length }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_pattern(&self, texts: &[&str]) -> Result<(), ValidationError> {
+        let pattern = match &self.pattern {
+            Some(pattern) => pattern,
+            None => return Ok(()),
+        };
+
+        let regex = PATTERNS
+            .with(|patterns| {
+                patterns
+                    .borrow_mut()
+                    .entry(pattern.clone())
+                    .or_insert_with(|| {
+                        Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| e.to_string())
+                    })
+                    .clone()
+            })
+            .map_err(ValidationError::InvalidPattern)?;
+        if texts.iter().all(|text| regex.is_match(text)) {
+            Ok(())
+        } else {
+            Err(ValidationError::PatternMismatch {
+                pattern: pattern.clone(),
+            })
+        }
+    }
+}
+
+/// Checks a value against the validation rules of the input at `path`, if
+/// there is one.
+pub fn validate_at(
+    inputs: &[InputTypes],
+    path: &Path,
+    value: &JsonValue,
+) -> Result<(), ValidationError> {
+    let input = match input_at(inputs, path) {
+        Some(input) => input,
+        None => return Ok(()),
+    };
+    if let Some(validation) = input.validation() {
+        validation.validate(value)?;
+    }
+    match input {
+        InputTypes::Custom(custom) => custom.validate(value).map_err(ValidationError::Custom),
+        _ => Ok(()),
+    }
+}
+
+/// Finds the definition of the input whose value is at `path`, skipping the
+/// indexes of the lists and tables. The keys of the inputs can have several
+/// segments, eg. `server.name`.
+pub fn input_at<'a>(inputs: &'a [InputTypes], path: &Path) -> Option<&'a InputTypes> {
+    let segments: Vec<&str> = path.get_segments().collect();
+    let mut rest = &segments[..];
+    let mut inputs = inputs;
+    let mut found: Option<&InputTypes> = None;
+    while let Some(segment) = rest.first() {
+        let in_array = matches!(
+            found,
+            Some(InputTypes::List(_)) | Some(InputTypes::Table(_))
+        );
+        if in_array && segment.parse::<usize>().is_ok() {
+            rest = &rest[1..];
+            continue;
+        }
+
+        let (input, key_len) = inputs
+            .iter()
+            .filter_map(|input| {
+                let key: Vec<&str> = input.key().get_segments().collect();
+                Some((input, key.len())).filter(|_| !key.is_empty() && rest.starts_with(&key))
+            })
+            .max_by_key(|(_, key_len)| *key_len)?;
+        rest = &rest[key_len..];
+        inputs = match input {
+            InputTypes::Group(group) => &group.inputs,
+            _ => input.element_inputs(),
+        };
+        found = Some(input);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validation_rules() {
+        let validation: Validation = serde_json::from_value(json!({
+            "min_length": 2, "max_length": 3, "pattern": "[a-z]+"
+        }))
+        .unwrap();
+        assert_eq!(validation.validate(&json!("")), Ok(()));
+        assert_eq!(validation.validate(&json!("abc")), Ok(()));
+        assert_eq!(validation.validate(&json!(["ab", "cd"])), Ok(()));
+        assert_eq!(
+            validation.validate(&json!("a")),
+            Err(ValidationError::TooShort { min: 2, length: 1 })
+        );
+        assert_eq!(
+            validation.validate(&json!(["a", "b", "c", "d"])),
+            Err(ValidationError::TooLong { max: 3, length: 4 })
+        );
+        assert_eq!(
+            validation.validate(&json!(["ab", "C"])),
+            Err(ValidationError::PatternMismatch {
+                pattern: "[a-z]+".to_owned()
+            })
+        );
+
+        let validation = Validation {
+            message: Some("Nope".to_owned()),
+            ..validation
+        };
+        assert_eq!(
+            validation.validate(&json!("a")),
+            Err(ValidationError::Custom("Nope".to_owned()))
+        );
+    }
+
+    #[test]
+    fn validate_nested_input() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([{
+            "key": "persons", "name": "Persons", "type": "list",
+            "inputs": [{
+                "key": "name", "name": "Name", "type": "text",
+                "validation": { "max_length": 3 }
+            }]
+        }]))
+        .unwrap();
+
+        assert!(validate_at(&inputs, &Path::from("persons.1.name"), &json!("Bob")).is_ok());
+        assert!(validate_at(&inputs, &Path::from("persons.1.name"), &json!("Alice")).is_err());
+        assert!(validate_at(&inputs, &Path::from("unknown"), &json!("Alice")).is_ok());
+    }
+
+    #[test]
+    fn validate_input_with_dotted_key() {
+        let inputs: Vec<InputTypes> = serde_json::from_value(json!([{
+            "key": "server.name", "name": "Name", "type": "text",
+            "validation": { "max_length": 2 }
+        }]))
+        .unwrap();
+
+        assert!(validate_at(&inputs, &Path::from("server.name"), &json!("ok")).is_ok());
+        assert_eq!(
+            validate_at(&inputs, &Path::from("server.name"), &json!("toolong")),
+            Err(ValidationError::TooLong { max: 2, length: 7 })
+        );
+    }
+}
@@ -0,0 +1,974 @@
+use crate::AppError;
+use core::fmt;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value as JsonValue;
+use std::{ops::RangeInclusive, str::FromStr};
+
+mod patch;
+pub use patch::PatchOp;
+
+/// The path of a value in the inputs data, written with dots, eg.
+/// `servers.0.name`, or as a JSON Pointer (RFC 6901) when its keys contain
+/// dots, eg. `/servers/0/example.org`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct Path {
+    segments: Vec<String>,
+}
+
+impl Path {
+    pub fn from_segments(segments: &[&str]) -> Self {
+        Self {
+            segments: segments.iter().map(|s| (*s).to_owned()).collect(),
+        }
+    }
+
+    /// Parses a JSON Pointer, where `~1` stands for `/` and `~0` for `~`, eg.
+    /// `/a~1b/c.d` for the key `c.d` of the key `a/b`.
+    pub fn from_pointer(pointer: &str) -> Result<Self, AppError> {
+        if pointer.is_empty() {
+            return Ok(Self::default());
+        }
+        let invalid = |reason: &str| {
+            let reason = format!("'{}' isn't a JSON Pointer, {}", pointer, reason);
+            AppError::path_access(&Path::default(), reason)
+        };
+        let tokens = pointer
+            .strip_prefix('/')
+            .ok_or_else(|| invalid("it must start with '/'"))?;
+
+        let mut segments = vec![];
+        for token in tokens.split('/') {
+            let mut segment = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    segment.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => segment.push('~'),
+                    Some('1') => segment.push('/'),
+                    _ => return Err(invalid("'~' must be followed by '0' or '1'")),
+                }
+            }
+            segments.push(segment);
+        }
+        Ok(Self { segments })
+    }
+
+    /// Writes this path as a JSON Pointer, see `from_pointer`.
+    pub fn to_pointer(&self) -> String {
+        self.segments
+            .iter()
+            .map(|s| format!("/{}", s.replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
+    /// Whether writing this path with dots would lose its meaning, because
+    /// a key is empty, contains a dot or looks like a JSON Pointer.
+    fn needs_pointer(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| s.is_empty() || s.contains('.'))
+            || self.segments.first().is_some_and(|s| s.starts_with('/'))
+    }
+
+    pub fn get_segments(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().map(String::as_str)
+    }
+
+    /// Returns the path without its last segment.
+    pub fn parent(&self) -> Path {
+        let mut segments: Vec<_> = self.get_segments().collect();
+        segments.pop();
+        Path::from_segments(&segments)
+    }
+
+    /// Whether this path is `base` or one of its descendants.
+    pub fn starts_with(&self, base: &Path) -> bool {
+        let mut segments = self.get_segments();
+        base.get_segments()
+            .all(|segment| segments.next() == Some(segment))
+    }
+
+    /// Returns where this path points once the array element at `moved` is
+    /// moved to `new_index`, see `InputsData::move_at`.
+    pub fn after_move(&self, moved: &Path, new_index: usize) -> Path {
+        let old_index = match moved.index() {
+            Some(index) => index,
+            None => return self.clone(),
+        };
+        self.reindexed(&moved.parent(), |index| match index {
+            i if i == old_index => new_index,
+            i if old_index < i && i <= new_index => i - 1,
+            i if new_index <= i && i < old_index => i + 1,
+            i => i,
+        })
+    }
+
+    /// Returns where this path points once the array element at `swapped` is
+    /// swapped with the one at `other_index`, see `InputsData::swap_at`.
+    pub fn after_swap(&self, swapped: &Path, other_index: usize) -> Path {
+        let index = match swapped.index() {
+            Some(index) => index,
+            None => return self.clone(),
+        };
+        self.reindexed(&swapped.parent(), |i| match i {
+            i if i == index => other_index,
+            i if i == other_index => index,
+            i => i,
+        })
+    }
+
+    /// The index of the array element this path points to, if it does.
+    fn index(&self) -> Option<usize> {
+        self.segments.last()?.parse().ok()
+    }
+
+    /// Changes the index of the element of `array` this path is in, if any.
+    fn reindexed(&self, array: &Path, new_index: impl Fn(usize) -> usize) -> Path {
+        let depth = array.segments.len();
+        let index = match self.segments.get(depth) {
+            Some(index) if self.starts_with(array) => index.parse().ok(),
+            _ => None,
+        };
+        let mut path = self.clone();
+        if let Some(index) = index {
+            path.segments[depth] = new_index(index).to_string();
+        }
+        path
+    }
+
+    /// Follows this path in the given JSON, with array indexes as segments.
+    pub fn lookup<'a>(&self, json: &'a JsonValue) -> Option<&'a JsonValue> {
+        self.get_segments()
+            .try_fold(json, |obj, segment: &str| match obj {
+                JsonValue::Object(obj) => obj.get(segment),
+                JsonValue::Array(arr) => match segment.parse::<usize>() {
+                    Ok(index) => arr.get(index),
+                    Err(_) => None,
+                },
+                _ => None,
+            })
+    }
+
+    pub fn lookup_mut<'a>(&self, json: &'a mut JsonValue) -> Option<&'a mut JsonValue> {
+        self.get_segments()
+            .try_fold(json, |obj, segment: &str| match obj {
+                JsonValue::Object(obj) => obj.get_mut(segment),
+                JsonValue::Array(arr) => match segment.parse::<usize>() {
+                    Ok(index) => arr.get_mut(index),
+                    Err(_) => None,
+                },
+                _ => None,
+            })
+    }
+}
+
+/// Written with dots, unless it needs a JSON Pointer, so that it can be
+/// parsed back with `FromStr`.
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.needs_pointer() {
+            write!(f, "{}", self.to_pointer())
+        } else {
+            write!(f, "{}", self.segments.join("."))
+        }
+    }
+}
+
+/// Parses a JSON Pointer if it starts with `/`, and a path with dots
+/// otherwise.
+impl FromStr for Path {
+    type Err = AppError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        if path.starts_with('/') {
+            Path::from_pointer(path)
+        } else {
+            Ok(Path::from(path))
+        }
+    }
+}
+
+impl Serialize for Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Path {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = String::deserialize(deserializer)?;
+        path.parse().map_err(D::Error::custom)
+    }
+}
+
+impl core::ops::Add for &Path {
+    type Output = Path;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Path {
+            segments: self.segments.iter().chain(&rhs.segments).cloned().collect(),
+        }
+    }
+}
+
+impl core::ops::Add<Path> for &Path {
+    type Output = Path;
+
+    fn add(self, rhs: Path) -> Self::Output {
+        self.add(&rhs)
+    }
+}
+
+/// Splits a path written with dots, see `FromStr` for the JSON Pointers.
+impl From<&str> for Path {
+    fn from(path: &str) -> Self {
+        Self {
+            segments: path
+                .split('.')
+                .filter(|s| !s.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+        }
+    }
+}
+
+impl From<String> for Path {
+    fn from(path: String) -> Self {
+        Path::from(path.as_str())
+    }
+}
+
+impl From<usize> for Path {
+    fn from(index: usize) -> Self {
+        Self {
+            segments: vec![index.to_string()],
+        }
+    }
+}
+
+/// Represents the data entered in the inputs on the page.
+///
+/// Backed by a JSON object.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct InputsData(JsonValue);
+
+impl Default for InputsData {
+    fn default() -> Self {
+        Self(JsonValue::Object(Default::default()))
+    }
+}
+
+impl fmt::Display for InputsData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#}", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl From<JsonValue> for InputsData {
+    fn from(value: JsonValue) -> Self {
+        InputsData(value)
+    }
+}
+
+/// TODO: Create a trait instead
+impl InputsData {
+    /// TODO
+    ///
+    /// Returns an error if the key in ill-formed in the case of an array access.
+    pub fn insert_at(&mut self, path: &Path, value: JsonValue) -> Result<(), AppError> {
+        *path
+            .get_segments()
+            .fold(Ok(&mut self.0), |obj, segment: &str| {
+                Ok(match obj? {
+                    JsonValue::Object(obj) => obj
+                        .entry(segment)
+                        .or_insert(JsonValue::Object(Default::default())),
+                    JsonValue::Array(arr) => {
+                        let index = segment.parse::<usize>().map_err(|_| {
+                            AppError::path_access(
+                                path,
+                                format!("'{}' isn't an index of the array", segment),
+                            )
+                        })?;
+
+                        // Extend the array if needed
+                        let len = arr.len();
+                        arr.resize_with(len.max(index + 1), || {
+                            JsonValue::Object(Default::default())
+                        });
+
+                        // unwrap is guaranteed to work thanks to the resize above
+                        arr.get_mut(index).unwrap()
+                    }
+                    _ => {
+                        return Err(AppError::path_access(
+                            path,
+                            format!("the data is ill-formed at segment: {}", segment),
+                        ))
+                    }
+                })
+            })? = value;
+        Ok(())
+    }
+
+    pub fn get_at(&self, path: &Path) -> Option<&JsonValue> {
+        path.lookup(&self.0)
+    }
+
+    pub fn get_at_mut(&mut self, path: &Path) -> Option<&mut JsonValue> {
+        path.lookup_mut(&mut self.0)
+    }
+
+    /// Returns the string at `path`, or `None` when there is nothing or
+    /// `null` there.
+    pub fn get_str_at(&self, path: &Path) -> Result<Option<&str>, AppError> {
+        self.get_typed_at(path, "a string", JsonValue::as_str)
+    }
+
+    /// Returns the number at `path`, or `None` when there is nothing or
+    /// `null` there.
+    pub fn get_number_at(&self, path: &Path) -> Result<Option<f64>, AppError> {
+        self.get_typed_at(path, "a number", JsonValue::as_f64)
+    }
+
+    /// Returns the boolean at `path`, or `None` when there is nothing or
+    /// `null` there.
+    pub fn get_bool_at(&self, path: &Path) -> Result<Option<bool>, AppError> {
+        self.get_typed_at(path, "a boolean", JsonValue::as_bool)
+    }
+
+    fn get_typed_at<'a, T>(
+        &'a self,
+        path: &Path,
+        expected: &str,
+        as_type: impl Fn(&'a JsonValue) -> Option<T>,
+    ) -> Result<Option<T>, AppError> {
+        match self.get_at(path) {
+            None | Some(JsonValue::Null) => Ok(None),
+            Some(value) => as_type(value).map(Some).ok_or_else(|| {
+                let reason = format!("expected {}, found {}", expected, kind_of(value));
+                AppError::path_access(path, reason)
+            }),
+        }
+    }
+
+    /// Resizes the array at `path`, clamping its new size to `allowed_sizes`.
+    pub fn resize_array_at(
+        &mut self,
+        path: &Path,
+        new_size: usize,
+        allowed_sizes: RangeInclusive<usize>,
+    ) -> Result<(), AppError> {
+        let new_size = new_size
+            .max(*allowed_sizes.start())
+            .min(*allowed_sizes.end());
+
+        // Make sure that `path` points to an array.
+        // Returns an error if the key is ill-formed or invalid because of array access
+        if !self.get_at(&path).map(JsonValue::is_array).unwrap_or(false) {
+            self.insert_at(&path, serde_json::json!([]))?;
+        }
+
+        self
+            .get_at_mut(&path)
+            .map(JsonValue::as_array_mut)
+            .flatten()
+            .unwrap() // guaranteed by the if above
+            .resize_with(new_size, || JsonValue::Object(Default::default()));
+        Ok(())
+    }
+
+    pub fn remove_at(&mut self, path: &Path) -> Result<Option<JsonValue>, AppError> {
+        let (base, last) = {
+            let mut segments: Vec<_> = path.get_segments().collect();
+
+            if segments.is_empty() {
+                let mut previous = JsonValue::Object(Default::default());
+                std::mem::swap(&mut previous, &mut self.0);
+                return Ok(Some(previous));
+            }
+
+            let last = segments
+                .pop()
+                .ok_or_else(|| AppError::path_access(path, "the path is empty"))?;
+            (Path::from_segments(&segments), last)
+        };
+
+        match self.get_at_mut(&base) {
+            Some(JsonValue::Object(obj)) => Ok(obj.remove(last)),
+            Some(JsonValue::Array(arr)) => match last.parse::<usize>() {
+                Ok(index) if index < arr.len() => Ok(Some(arr.remove(index))),
+                Ok(_) => Ok(None),
+                Err(e) => Err(AppError::path_access(
+                    path,
+                    format!("'{}' isn't an index of the array: {}", last, e),
+                )),
+            },
+            Some(JsonValue::String(_)) => {
+                Err(AppError::path_access(&base, "cannot remove from String"))
+            }
+            Some(JsonValue::Number(_)) => {
+                Err(AppError::path_access(&base, "cannot remove from Number"))
+            }
+            Some(JsonValue::Bool(_)) => {
+                Err(AppError::path_access(&base, "cannot remove from Bool"))
+            }
+            Some(JsonValue::Null) => Err(AppError::path_access(&base, "cannot remove from Null")),
+            None => Err(AppError::path_access(&base, "nothing is there")),
+        }
+    }
+
+    /// Merges `value` into the data at `path`: the fields of objects are
+    /// merged recursively, the arrays as `arrays` tells, and other values
+    /// replace the existing ones.
+    ///
+    /// Returns the paths of the values which were replaced by different ones,
+    /// the missing and `null` values not being in conflict.
+    pub fn merge_at(
+        &mut self,
+        path: &Path,
+        value: JsonValue,
+        arrays: ArrayMerge,
+    ) -> Result<Vec<Path>, AppError> {
+        let mut conflicts = vec![];
+        match self.get_at_mut(path) {
+            Some(existing) => merge(existing, value, arrays, path, &mut conflicts),
+            None => self.insert_at(path, value)?,
+        }
+        Ok(conflicts)
+    }
+
+    /// Moves the array element at `path` to `new_index` in the same array,
+    /// shifting the elements in between. Returns the index it was moved to,
+    /// which is the last one when `new_index` is past the end.
+    pub fn move_at(&mut self, path: &Path, new_index: usize) -> Result<usize, AppError> {
+        let (arr, index) = self.array_element_at(path)?;
+        let element = arr.remove(index);
+        let new_index = new_index.min(arr.len());
+        arr.insert(new_index, element);
+        Ok(new_index)
+    }
+
+    /// Swaps the array element at `path` with the one at `other_index` in the
+    /// same array.
+    pub fn swap_at(&mut self, path: &Path, other_index: usize) -> Result<(), AppError> {
+        let (arr, index) = self.array_element_at(path)?;
+        if other_index >= arr.len() {
+            let reason = format!("nothing to swap with at index {}", other_index);
+            return Err(AppError::path_access(path, reason));
+        }
+        arr.swap(index, other_index);
+        Ok(())
+    }
+
+    /// Inserts a copy of the array element at `path` right after it.
+    pub fn duplicate_at(&mut self, path: &Path) -> Result<(), AppError> {
+        let (arr, index) = self.array_element_at(path)?;
+        let copy = arr[index].clone();
+        arr.insert(index + 1, copy);
+        Ok(())
+    }
+
+    /// Puts back a value returned by `remove_at`: array elements are inserted
+    /// back at their index, shifting the following ones.
+    pub fn restore_at(&mut self, path: &Path, value: JsonValue) -> Result<(), AppError> {
+        let mut segments: Vec<_> = path.get_segments().collect();
+        let last = segments.pop();
+        let base = Path::from_segments(&segments);
+        match (self.get_at_mut(&base), last.map(str::parse::<usize>)) {
+            (Some(JsonValue::Array(arr)), Some(Ok(index))) => {
+                arr.insert(index.min(arr.len()), value);
+                Ok(())
+            }
+            _ => self.insert_at(path, value),
+        }
+    }
+
+    /// Returns the paths whose value differs in `other`, along with the value
+    /// here and the one in `other`. Missing values are the same as `null`.
+    pub fn diff<'a>(&'a self, other: &'a InputsData) -> Vec<Change<'a>> {
+        let mut changes = vec![];
+        diff(&mut vec![], Some(&self.0), Some(&other.0), &mut changes);
+        changes
+    }
+
+    /// Returns the array containing the element at `path`, and its index.
+    fn array_element_at(&mut self, path: &Path) -> Result<(&mut Vec<JsonValue>, usize), AppError> {
+        let mut segments: Vec<_> = path.get_segments().collect();
+        let last = segments
+            .pop()
+            .ok_or_else(|| AppError::path_access(path, "expected the path of an array element"))?;
+        let base = Path::from_segments(&segments);
+        let index = last.parse::<usize>().map_err(|_| {
+            AppError::path_access(path, format!("'{}' isn't an index of the array", last))
+        })?;
+
+        match self.get_at_mut(&base) {
+            Some(JsonValue::Array(arr)) if index < arr.len() => Ok((arr, index)),
+            Some(JsonValue::Array(_)) => Err(AppError::path_access(path, "nothing is there")),
+            _ => Err(AppError::path_access(&base, "there is no array")),
+        }
+    }
+}
+
+/// A value which differs between two inputs data, see `InputsData::diff`.
+pub type Change<'a> = (Path, Option<&'a JsonValue>, Option<&'a JsonValue>);
+
+fn diff<'a>(
+    segments: &mut Vec<String>,
+    before: Option<&'a JsonValue>,
+    after: Option<&'a JsonValue>,
+    changes: &mut Vec<Change<'a>>,
+) {
+    let mut visit = |segment: String, before, after, changes: &mut Vec<Change<'a>>| {
+        segments.push(segment);
+        diff(segments, before, after, changes);
+        segments.pop();
+    };
+    match (before, after) {
+        (Some(JsonValue::Object(before)), Some(JsonValue::Object(after))) => {
+            let added = after.keys().filter(|key| !before.contains_key(*key));
+            for key in before.keys().chain(added) {
+                visit(key.clone(), before.get(key), after.get(key), changes);
+            }
+        }
+        (Some(JsonValue::Array(before)), Some(JsonValue::Array(after))) => {
+            for i in 0..before.len().max(after.len()) {
+                visit(i.to_string(), before.get(i), after.get(i), changes);
+            }
+        }
+        (before, after) => {
+            let before = before.filter(|value| !value.is_null());
+            let after = after.filter(|value| !value.is_null());
+            if before != after {
+                let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+                changes.push((Path::from_segments(&segments), before, after));
+            }
+        }
+    }
+}
+
+/// Names the type of a JSON value, for the error messages.
+fn kind_of(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "a boolean",
+        JsonValue::Number(_) => "a number",
+        JsonValue::String(_) => "a string",
+        JsonValue::Array(_) => "an array",
+        JsonValue::Object(_) => "an object",
+    }
+}
+
+/// How `InputsData::merge_at` merges an array in an existing one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrayMerge {
+    /// The merged array replaces the existing one.
+    Replace,
+    /// The elements of the merged array are added after the existing ones.
+    Concat,
+}
+
+fn merge(
+    existing: &mut JsonValue,
+    value: JsonValue,
+    arrays: ArrayMerge,
+    path: &Path,
+    conflicts: &mut Vec<Path>,
+) {
+    match (existing, value) {
+        (JsonValue::Object(existing), JsonValue::Object(value)) => {
+            for (key, value) in value {
+                match existing.get_mut(&key) {
+                    Some(field) => {
+                        let path = path + &Path::from_segments(&[&key]);
+                        merge(field, value, arrays, &path, conflicts)
+                    }
+                    None => {
+                        existing.insert(key, value);
+                    }
+                }
+            }
+        }
+        (JsonValue::Array(existing), JsonValue::Array(value)) if arrays == ArrayMerge::Concat => {
+            existing.extend(value)
+        }
+        (existing, value) => {
+            if !existing.is_null() && *existing != value {
+                conflicts.push(path.clone());
+            }
+            *existing = value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn Path_display() {
+        ["", "a.path", "with.0.index"]
+            .iter()
+            .for_each(|s| assert_eq!(&Path::from(*s).to_string(), s))
+    }
+
+    #[test]
+    fn Path_parent() {
+        assert_eq!(Path::from("a.b.0").parent(), Path::from("a.b"));
+        assert_eq!(Path::from("a").parent(), Path::default());
+        assert_eq!(Path::default().parent(), Path::default());
+    }
+
+    #[test]
+    fn Path_starts_with() {
+        let path = Path::from("a.bc.0");
+        assert!(path.starts_with(&Path::default()));
+        assert!(path.starts_with(&Path::from("a.bc")));
+        assert!(path.starts_with(&path));
+        assert!(!path.starts_with(&Path::from("a.b")));
+        assert!(!Path::from("a").starts_with(&path));
+    }
+
+    #[test]
+    fn Path_add_refs() {
+        [
+            (&Path::from("left") + &Path::from("right"), "left.right"),
+            (&Path::default() + &Path::from("right"), "right"),
+            (&Path::from("left") + &Path::default(), "left"),
+        ]
+        .iter()
+        .for_each(|(actual, expected)| assert_eq!(&actual.to_string(), expected))
+    }
+
+    #[test]
+    fn Path_after_move_and_swap() {
+        let moved = Path::from("a.1");
+        let paths = ["a.0.x", "a.1.x", "a.2", "a.3", "b.1", "a"];
+        let after_move: Vec<_> = paths
+            .iter()
+            .map(|p| Path::from(*p).after_move(&moved, 3).to_string())
+            .collect();
+        assert_eq!(after_move, ["a.0.x", "a.3.x", "a.1", "a.2", "b.1", "a"]);
+        let after_swap: Vec<_> = paths
+            .iter()
+            .map(|p| Path::from(*p).after_swap(&moved, 3).to_string())
+            .collect();
+        assert_eq!(after_swap, ["a.0.x", "a.3.x", "a.2", "a.1", "b.1", "a"]);
+    }
+
+    #[test]
+    fn Path_json_pointer() {
+        let path = Path::from_pointer("/servers/0/example.org/a~1b~0c").unwrap();
+        assert_eq!(
+            path.get_segments().collect::<Vec<_>>(),
+            ["servers", "0", "example.org", "a/b~c"]
+        );
+        assert_eq!(path.to_pointer(), "/servers/0/example.org/a~1b~0c");
+        assert_eq!(path.to_string(), path.to_pointer());
+        assert_eq!(path.to_string().parse::<Path>().unwrap(), path);
+        assert_eq!(Path::from_pointer("").unwrap(), Path::default());
+        assert_eq!(Path::from_pointer("/a/b").unwrap(), Path::from("a.b"));
+        assert_eq!(Path::from("a.b").to_string(), "a.b");
+        assert!(Path::from_pointer("a/b").is_err());
+        assert!(Path::from_pointer("/a~2").is_err());
+
+        let json = json!({"a.b": {"c": 1}});
+        assert_eq!(
+            Path::from_pointer("/a.b/c").unwrap().lookup(&json),
+            Some(&json!(1))
+        );
+        assert_eq!(
+            serde_json::from_value::<Vec<Path>>(json!(["a.b", "/a.b"])).unwrap(),
+            [Path::from("a.b"), Path::from_segments(&["a.b"])]
+        );
+    }
+
+    #[test]
+    fn InputsData_insert_in_empty_data() {
+        let mut data = InputsData(json!({}));
+        data.insert_at(&Path::from("some.path"), json!("data"))
+            .unwrap();
+        assert_eq!(data.0, json!({"some": {"path": "data"}}));
+    }
+
+    #[test]
+    fn InputsData_insert_at_empty_object() {
+        let mut data = InputsData(json!({"some": {}}));
+        data.insert_at(&Path::from("some.path"), json!("data"))
+            .unwrap();
+        assert_eq!(data.0, json!({"some": {"path": "data"}}));
+    }
+
+    #[test]
+    fn InputsData_insert_in_number_fails() {
+        let json = json!({"some": 42});
+        let mut data = InputsData(json.clone());
+        assert!(data
+            .insert_at(&Path::from("some.path"), json!("data"))
+            .is_err());
+        assert_eq!(data.0, json);
+    }
+
+    #[test]
+    fn InputsData_insert_at_array_fails_if_not_an_index() {
+        let json = json!({"some": []});
+        let mut data = InputsData(json.clone());
+        assert!(data
+            .insert_at(&Path::from("some.path"), json!("data"))
+            .is_err());
+        assert_eq!(data.0, json);
+    }
+
+    #[test]
+    fn InputsData_insert_in_array_at_first_position() {
+        let mut data = InputsData(json!({"some": []}));
+        data.insert_at(&Path::from("some.0"), json!("data"))
+            .unwrap();
+        assert_eq!(data.0, json!({"some": ["data"]}));
+    }
+
+    #[test]
+    fn InputsData_insert_in_array_at_middle() {
+        let mut data = InputsData(json!({"some": []}));
+        data.insert_at(&Path::from("some.3"), json!("data"))
+            .unwrap();
+        assert_eq!(data.0, json!({"some": [{}, {}, {}, "data"]}));
+    }
+
+    #[test]
+    fn InputsData_insert_deeply() {
+        let mut data: InputsData = json!({"some": {"complex": [{}, null, {"json": {}}]}}).into();
+        data.insert_at(&Path::from("some.complex.2.json"), json!("data"))
+            .unwrap();
+        assert_eq!(
+            data.0,
+            json!({"some": {"complex": [{}, null, {"json": "data"}]}})
+        );
+    }
+
+    #[test]
+    fn InputsData_get_at_empty_path() {
+        let data: InputsData = json!({"a": "b"}).into();
+        assert_eq!(data.get_at(&Path::from("")), Some(&data.0));
+        assert_eq!(data.get_at(&Path::from(".")), Some(&data.0));
+        assert_eq!(data.get_at(&Path::from("..")), Some(&data.0));
+        assert_eq!(data.get_at(&Path::from("...")), Some(&data.0));
+    }
+
+    #[test]
+    fn InputsData_get_at() {
+        let json = json!({"a": "b", "c": ["d", {"e": 1}, 2], "f": {"g": "h"}});
+        let data = InputsData::from(json.clone());
+        [
+            ("", Some(&json)),
+            ("a", Some(&json["a"])),
+            ("foo", None),
+            ("c", Some(&json["c"])),
+            ("c.0", Some(&json["c"][0])),
+            ("c.1", Some(&json["c"][1])),
+            ("c.2", Some(&json["c"][2])),
+            ("c.3", None),
+            ("f", Some(&json["f"])),
+            ("f.g", Some(&json["f"]["g"])),
+            ("f.foo", None),
+        ]
+        .iter()
+        .map(|(s, exp)| (Path::from(*s), exp))
+        .for_each(|(p, expected)| assert_eq!(data.get_at(&p), *expected))
+    }
+
+    #[test]
+    fn InputsData_get_at_same_as_get_at_mut() {
+        let mut data: InputsData =
+            json!({"a": "b", "c": ["d", {"e": 1}, 2], "f": {"g": "h"}}).into();
+        [
+            "", "a", "foo", "c", "c.0", "c.1", "c.2", "c.3", "f", "f.g", "f.foo",
+        ]
+        .iter()
+        .map(|s| Path::from(*s))
+        .for_each(|p| assert_eq!(data.get_at(&p).cloned(), data.get_at_mut(&p).cloned()))
+    }
+
+    #[test]
+    fn InputsData_resize_array_from_zero() {
+        let mut data: InputsData = json!({"a": []}).into();
+        data.resize_array_at(&Path::from("a"), 5, 0..=usize::MAX)
+            .unwrap();
+        assert_eq!(data.0, json!({"a": [{}, {}, {}, {}, {}]}))
+    }
+
+    #[test]
+    fn InputsData_resize_array_that_doesnt_exist_yet() {
+        let mut data: InputsData = json!({"a": {}}).into();
+        data.resize_array_at(&Path::from("a.b"), 2, 0..=usize::MAX)
+            .unwrap();
+        assert_eq!(data.0, json!({"a": {"b": [{}, {}]}}))
+    }
+
+    #[test]
+    fn InputsData_resize_at_number_will_change_it_into_an_array() {
+        let mut data: InputsData = json!({"a": 42}).into();
+        data.resize_array_at(&Path::from("a"), 1, 0..=usize::MAX)
+            .unwrap();
+        assert_eq!(data.0, json!({"a": [{}]}))
+    }
+
+    #[test]
+    fn InputsData_resize_array_is_clamped() {
+        let mut data: InputsData = json!({"a": [1, 2]}).into();
+        data.resize_array_at(&Path::from("a"), 0, 1..=3).unwrap();
+        assert_eq!(data.0, json!({"a": [1]}));
+        data.resize_array_at(&Path::from("a"), 5, 1..=3).unwrap();
+        assert_eq!(data.0, json!({"a": [1, {}, {}]}))
+    }
+
+    #[test]
+    fn InputsData_remove_at_empty_path() {
+        let mut data: InputsData = json!({"a": "b"}).into();
+        assert_eq!(
+            data.remove_at(&Path::from("")).unwrap().unwrap(),
+            json!({"a": "b"})
+        );
+        assert_eq!(data.0, json!({}))
+    }
+
+    #[test]
+    fn InputsData_remove_at_from_beginning() {
+        let mut data: InputsData = json!({"a": [1, 2, 3]}).into();
+        assert_eq!(data.remove_at(&Path::from("a.0")).unwrap(), Some(json!(1)));
+        assert_eq!(data.0, json!({"a": [2, 3]}));
+        assert_eq!(data.remove_at(&Path::from("a.0")).unwrap(), Some(json!(2)));
+        assert_eq!(data.0, json!({"a": [3]}));
+        assert_eq!(data.remove_at(&Path::from("a.0")).unwrap(), Some(json!(3)));
+        assert_eq!(data.0, json!({"a": []}));
+        assert_eq!(data.remove_at(&Path::from("a.0")).unwrap(), None);
+        assert_eq!(data.0, json!({"a": []}));
+    }
+
+    #[test]
+    fn InputsData_move_at() {
+        let mut data = InputsData(json!({"some": ["a", "b", "c"]}));
+        data.move_at(&Path::from("some.0"), 2).unwrap();
+        assert_eq!(data.0, json!({"some": ["b", "c", "a"]}));
+        data.move_at(&Path::from("some.2"), 0).unwrap();
+        assert_eq!(data.0, json!({"some": ["a", "b", "c"]}));
+        assert_eq!(data.move_at(&Path::from("some.1"), 42), Ok(2));
+        assert_eq!(data.0, json!({"some": ["a", "c", "b"]}));
+
+        assert!(data.move_at(&Path::from("some.3"), 0).is_err());
+        assert_eq!(
+            data.move_at(&Path::from("some"), 0),
+            Err(AppError::path_access(
+                &Path::from("some"),
+                "'some' isn't an index of the array"
+            ))
+        );
+    }
+
+    #[test]
+    fn InputsData_typed_accessors() {
+        let data = InputsData(json!({"a": "x", "b": 1.5, "c": true, "d": null}));
+        assert_eq!(data.get_str_at(&Path::from("a")), Ok(Some("x")));
+        assert_eq!(data.get_number_at(&Path::from("b")), Ok(Some(1.5)));
+        assert_eq!(data.get_bool_at(&Path::from("c")), Ok(Some(true)));
+        assert_eq!(data.get_str_at(&Path::from("d")), Ok(None));
+        assert_eq!(data.get_bool_at(&Path::from("e.f")), Ok(None));
+        assert_eq!(
+            data.get_number_at(&Path::from("a")),
+            Err(AppError::path_access(
+                &Path::from("a"),
+                "expected a number, found a string"
+            ))
+        );
+    }
+
+    #[test]
+    fn InputsData_swap_at() {
+        let mut data = InputsData(json!({"some": ["a", "b", "c"]}));
+        data.swap_at(&Path::from("some.0"), 2).unwrap();
+        assert_eq!(data.0, json!({"some": ["c", "b", "a"]}));
+        data.swap_at(&Path::from("some.1"), 1).unwrap();
+        assert_eq!(data.0, json!({"some": ["c", "b", "a"]}));
+
+        assert!(data.swap_at(&Path::from("some.0"), 3).is_err());
+        assert!(data.swap_at(&Path::from("other.0"), 1).is_err());
+    }
+
+    #[test]
+    fn InputsData_duplicate_at() {
+        let mut data = InputsData(json!({"some": [{"a": [1]}, "b"]}));
+        data.duplicate_at(&Path::from("some.0")).unwrap();
+        assert_eq!(data.0, json!({"some": [{"a": [1]}, {"a": [1]}, "b"]}));
+
+        assert!(data.duplicate_at(&Path::from("some.3")).is_err());
+        assert!(data.duplicate_at(&Path::from("other.0")).is_err());
+    }
+
+    #[test]
+    fn InputsData_restore_at() {
+        let mut data = InputsData(json!({"some": ["a", "b", "c"], "other": 1}));
+        let path = Path::from("some.1");
+        let removed = data.remove_at(&path).unwrap().unwrap();
+        data.restore_at(&path, removed).unwrap();
+        assert_eq!(data.0, json!({"some": ["a", "b", "c"], "other": 1}));
+
+        let removed = data.remove_at(&Path::from("other")).unwrap().unwrap();
+        data.restore_at(&Path::from("other"), removed).unwrap();
+        assert_eq!(data.0, json!({"some": ["a", "b", "c"], "other": 1}));
+    }
+
+    #[test]
+    fn InputsData_merge_at() {
+        let mut data = InputsData(json!({"a": {"b": 1, "c": {"d": [1, 2]}}}));
+        let merged = json!({"b": 1, "c": {"d": [3], "e": 4}, "f": 5});
+        let conflicts = data
+            .merge_at(&Path::from("a"), merged, ArrayMerge::Replace)
+            .unwrap();
+        assert_eq!(
+            data.0,
+            json!({"a": {"b": 1, "c": {"d": [3], "e": 4}, "f": 5}})
+        );
+        assert_eq!(conflicts, [Path::from("a.c.d")]);
+
+        let merged = json!({"c": {"d": [4]}, "f": null});
+        let conflicts = data
+            .merge_at(&Path::from("a"), merged, ArrayMerge::Concat)
+            .unwrap();
+        assert_eq!(
+            data.0["a"],
+            json!({"b": 1, "c": {"d": [3, 4], "e": 4}, "f": null})
+        );
+        assert_eq!(conflicts, [Path::from("a.f")]);
+
+        data.merge_at(&Path::from("g.h"), json!([1]), ArrayMerge::Replace)
+            .unwrap();
+        assert_eq!(data.get_at(&Path::from("g")), Some(&json!({"h": [1]})));
+    }
+
+    #[test]
+    fn InputsData_diff() {
+        let before = InputsData(json!({"a": {"b": 1, "c": null}, "d": [1, 2], "e": "x"}));
+        let after = InputsData(json!({"a": {"b": 2}, "d": [1], "f": true}));
+        assert_eq!(
+            before.diff(&after),
+            [
+                (Path::from("a.b"), Some(&json!(1)), Some(&json!(2))),
+                (Path::from("d.1"), Some(&json!(2)), None),
+                (Path::from("e"), Some(&json!("x")), None),
+                (Path::from("f"), None, Some(&json!(true))),
+            ]
+        );
+        assert!(before.diff(&before).is_empty());
+    }
+}
@@ -0,0 +1,296 @@
+//! JSON Patch (RFC 6902), to exchange the changes of inputs data instead of
+//! whole documents, eg. `[{"op": "replace", "path": "/name", "value": "web"}]`.
+
+use super::{kind_of, InputsData, Path};
+use crate::AppError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value as JsonValue;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "op")]
+pub enum PatchOp {
+    /// Adds a field to an object, or inserts an element in an array at an
+    /// index or at the end, with `-` as index.
+    Add {
+        #[serde(with = "pointer")]
+        path: Path,
+        value: JsonValue,
+    },
+    Remove {
+        #[serde(with = "pointer")]
+        path: Path,
+    },
+    Replace {
+        #[serde(with = "pointer")]
+        path: Path,
+        value: JsonValue,
+    },
+    Move {
+        #[serde(with = "pointer")]
+        from: Path,
+        #[serde(with = "pointer")]
+        path: Path,
+    },
+    Copy {
+        #[serde(with = "pointer")]
+        from: Path,
+        #[serde(with = "pointer")]
+        path: Path,
+    },
+    /// Fails the patch unless the value at `path` is `value`.
+    Test {
+        #[serde(with = "pointer")]
+        path: Path,
+        value: JsonValue,
+    },
+}
+
+impl PatchOp {
+    /// The path the operation changes, or tests.
+    pub fn path(&self) -> &Path {
+        match self {
+            PatchOp::Add { path, .. }
+            | PatchOp::Remove { path }
+            | PatchOp::Replace { path, .. }
+            | PatchOp::Move { path, .. }
+            | PatchOp::Copy { path, .. }
+            | PatchOp::Test { path, .. } => path,
+        }
+    }
+}
+
+/// The paths of the patches are always JSON Pointers, even when they could
+/// be written with dots.
+mod pointer {
+    use super::*;
+    use serde::de::Error as _;
+
+    pub fn serialize<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&path.to_pointer())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Path, D::Error> {
+        let pointer = String::deserialize(deserializer)?;
+        Path::from_pointer(&pointer).map_err(D::Error::custom)
+    }
+}
+
+impl InputsData {
+    /// Returns the patch changing this data into `other`, made of `add`,
+    /// `remove` and `replace` operations.
+    pub fn patch_to(&self, other: &InputsData) -> Vec<PatchOp> {
+        let mut patch = vec![];
+        patch_between(&Path::default(), &self.0, &other.0, &mut patch);
+        patch
+    }
+
+    /// Applies the operations in order. Nothing is changed when one fails.
+    pub fn apply_patch(&mut self, patch: &[PatchOp]) -> Result<(), AppError> {
+        let mut patched = self.0.clone();
+        for op in patch {
+            apply(&mut patched, op)?;
+        }
+        self.0 = patched;
+        Ok(())
+    }
+}
+
+fn patch_between(path: &Path, before: &JsonValue, after: &JsonValue, patch: &mut Vec<PatchOp>) {
+    match (before, after) {
+        (JsonValue::Object(before), JsonValue::Object(after)) => {
+            for (key, value) in before {
+                let path = path + &Path::from_segments(&[key]);
+                match after.get(key) {
+                    Some(after) => patch_between(&path, value, after, patch),
+                    None => patch.push(PatchOp::Remove { path }),
+                }
+            }
+            for (key, value) in after.iter().filter(|(key, _)| !before.contains_key(*key)) {
+                let path = path + &Path::from_segments(&[key]);
+                let value = value.clone();
+                patch.push(PatchOp::Add { path, value });
+            }
+        }
+        (JsonValue::Array(before), JsonValue::Array(after)) => {
+            for (i, (before, after)) in before.iter().zip(after).enumerate() {
+                patch_between(&(path + Path::from(i)), before, after, patch);
+            }
+            // From the end, so that the indexes of the others don't change
+            for i in (after.len()..before.len()).rev() {
+                let path = path + Path::from(i);
+                patch.push(PatchOp::Remove { path });
+            }
+            for value in after.iter().skip(before.len()) {
+                let path = path + &Path::from_segments(&["-"]);
+                let value = value.clone();
+                patch.push(PatchOp::Add { path, value });
+            }
+        }
+        (before, after) if before != after => {
+            let path = path.clone();
+            let value = after.clone();
+            patch.push(PatchOp::Replace { path, value });
+        }
+        _ => {}
+    }
+}
+
+fn apply(json: &mut JsonValue, op: &PatchOp) -> Result<(), AppError> {
+    match op {
+        PatchOp::Add { path, value } => add(json, path, value.clone()),
+        PatchOp::Remove { path } => remove(json, path).map(|_| ()),
+        PatchOp::Replace { path, value } => {
+            let existing = path
+                .lookup_mut(json)
+                .ok_or_else(|| AppError::path_access(path, "nothing to replace"))?;
+            *existing = value.clone();
+            Ok(())
+        }
+        PatchOp::Move { from, path } => {
+            if path.starts_with(from) && path != from {
+                let reason = format!("can't be moved into itself, at '{}'", path);
+                return Err(AppError::path_access(from, reason));
+            }
+            let value = remove(json, from)?;
+            add(json, path, value)
+        }
+        PatchOp::Copy { from, path } => {
+            let value = from
+                .lookup(json)
+                .cloned()
+                .ok_or_else(|| AppError::path_access(from, "nothing to copy"))?;
+            add(json, path, value)
+        }
+        PatchOp::Test { path, value } => match path.lookup(json) {
+            Some(existing) if existing == value => Ok(()),
+            Some(existing) => {
+                let reason = format!("expected {}, found {}", value, existing);
+                Err(AppError::path_access(path, reason))
+            }
+            None => Err(AppError::path_access(path, "nothing to test")),
+        },
+    }
+}
+
+/// Splits a path into the one of its parent and its last segment.
+fn split_last(path: &Path) -> Option<(Path, &str)> {
+    let last = path.get_segments().last()?;
+    Some((path.parent(), last))
+}
+
+fn add(json: &mut JsonValue, path: &Path, value: JsonValue) -> Result<(), AppError> {
+    let (parent, last) = match split_last(path) {
+        Some(split) => split,
+        None => {
+            *json = value;
+            return Ok(());
+        }
+    };
+    match parent.lookup_mut(json) {
+        Some(JsonValue::Object(object)) => {
+            object.insert(last.to_owned(), value);
+            Ok(())
+        }
+        Some(JsonValue::Array(array)) => {
+            let index = match last {
+                "-" => array.len(),
+                index => array_index(path, index, array.len() + 1)?,
+            };
+            array.insert(index, value);
+            Ok(())
+        }
+        Some(other) => {
+            let reason = format!("can't add to {}", kind_of(other));
+            Err(AppError::path_access(&parent, reason))
+        }
+        None => Err(AppError::path_access(&parent, "nothing is there")),
+    }
+}
+
+fn remove(json: &mut JsonValue, path: &Path) -> Result<JsonValue, AppError> {
+    let (parent, last) = split_last(path)
+        .ok_or_else(|| AppError::path_access(path, "the whole data can't be removed"))?;
+    let removed = match parent.lookup_mut(json) {
+        Some(JsonValue::Object(object)) => object.remove(last),
+        Some(JsonValue::Array(array)) => {
+            let index = array_index(path, last, array.len())?;
+            Some(array.remove(index))
+        }
+        _ => None,
+    };
+    removed.ok_or_else(|| AppError::path_access(path, "nothing to remove"))
+}
+
+/// Parses the index of an array element, which must be below `len`.
+fn array_index(path: &Path, index: &str, len: usize) -> Result<usize, AppError> {
+    match index.parse::<usize>() {
+        Ok(index) if index < len => Ok(index),
+        _ => {
+            let reason = format!("'{}' isn't an index of the array", index);
+            Err(AppError::path_access(path, reason))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn patch_between_data() {
+        let before = InputsData(json!({"a": {"b": 1, "c": 2}, "d": [1, 2, 3], "e": [1]}));
+        let after = InputsData(json!({"a": {"b": 5, "x.y": true}, "d": [1], "e": [1, 2, 3]}));
+        let patch = before.patch_to(&after);
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            json!([
+                {"op": "replace", "path": "/a/b", "value": 5},
+                {"op": "remove", "path": "/a/c"},
+                {"op": "add", "path": "/a/x.y", "value": true},
+                {"op": "remove", "path": "/d/2"},
+                {"op": "remove", "path": "/d/1"},
+                {"op": "add", "path": "/e/-", "value": 2},
+                {"op": "add", "path": "/e/-", "value": 3},
+            ])
+        );
+
+        let mut patched = before.clone();
+        patched.apply_patch(&patch).unwrap();
+        assert_eq!(patched, after);
+        assert!(after.patch_to(&after).is_empty());
+    }
+
+    #[test]
+    fn apply_patches() {
+        let mut data = InputsData(json!({"a": [1, 2], "b": {"c": "x"}}));
+        let patch: Vec<PatchOp> = serde_json::from_value(json!([
+            {"op": "test", "path": "/b/c", "value": "x"},
+            {"op": "add", "path": "/a/0", "value": 0},
+            {"op": "move", "from": "/b/c", "path": "/d"},
+            {"op": "copy", "from": "/a", "path": "/b/a"},
+        ]))
+        .unwrap();
+        data.apply_patch(&patch).unwrap();
+        assert_eq!(
+            data.0,
+            json!({"a": [0, 1, 2], "b": {"a": [0, 1, 2]}, "d": "x"})
+        );
+
+        // Nothing is changed by a failed patch
+        let patch: Vec<PatchOp> = serde_json::from_value(json!([
+            {"op": "remove", "path": "/a/0"},
+            {"op": "test", "path": "/d", "value": "y"},
+        ]))
+        .unwrap();
+        assert!(data.apply_patch(&patch).is_err());
+        assert_eq!(data.0["a"], json!([0, 1, 2]));
+
+        let patch = [PatchOp::Replace {
+            path: Path::from("nothing.here"),
+            value: json!(1),
+        }];
+        assert!(data.apply_patch(&patch).is_err());
+    }
+}
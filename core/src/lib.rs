@@ -0,0 +1,15 @@
+//! The scenarios, their inputs and the rendering of their templates, without
+//! the web interface, so that they can be tested natively and used outside of
+//! the browser.
+
+pub mod crdt;
+mod error;
+pub mod inputs;
+pub mod json_path;
+mod prelude;
+pub mod scenario;
+pub mod template_engine;
+pub mod webhook;
+
+pub use error::AppError;
+pub use json_path::{ArrayMerge, InputsData, PatchOp, Path};
@@ -0,0 +1,5 @@
+pub use anyhow::{anyhow, bail, Context, Result};
+pub use core::fmt;
+pub use log::{debug, warn};
+pub use serde::{Deserialize, Serialize};
+pub use serde_json::Value as JsonValue;
@@ -0,0 +1,310 @@
+use crate::{
+    inputs::{inputs_from_schema, seed_defaults, update_computed_inputs, Input, InputTypes},
+    prelude::*,
+    template_engine::{EngineKind, HelperDefinition, Whitespace},
+    webhook::Webhook,
+    AppError, InputsData, Path,
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
+
+mod extend;
+pub mod github;
+mod migrate;
+mod validate;
+pub use extend::{base_of, extend, MAX_BASES};
+pub use migrate::{migrate, NewerScenarioError, CURRENT_VERSION};
+pub use validate::{validate, Diagnostic, Severity};
+
+/// Name of the template of a scenario which only has one.
+pub const MAIN_TEMPLATE: &str = "t";
+
+/// A scenario represents the template to be rendered and the format
+/// of inputs needed to generate it.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Scenario {
+    /// Version of the format of the scenario, see `migrate`.
+    #[serde(default = "current_version")]
+    pub version: u64,
+    /// Shown above the inputs and in the navigation bar.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// What the scenario generates, in Markdown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub template: Template,
+    #[serde(default)]
+    pub inputs: Vec<InputTypes>,
+    /// A JSON Schema from which the inputs are generated, instead of listing
+    /// them. See `Scenario::generate_inputs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<JsonValue>,
+    /// The template engine used to render the template.
+    #[serde(default)]
+    pub engine: EngineKind,
+    /// Custom helpers available in the template, by name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub helpers: HashMap<String, HelperDefinition>,
+    /// Partial templates, usable in the template as `{{> name}}`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub partials: HashMap<String, TemplateSource>,
+    /// Fails the rendering when the template uses a missing variable,
+    /// instead of rendering it as an empty string.
+    #[serde(default)]
+    pub strict_mode: bool,
+    /// Opts out of the helpers shipped with the engine (`uppercase`,
+    /// `add`, `format_date`...).
+    #[serde(default)]
+    pub disable_builtin_helpers: bool,
+    /// Trimming of the lines holding block tags and of blank lines.
+    #[serde(default)]
+    pub whitespace: Whitespace,
+    /// Language of the rendered templates, eg. `yaml` or `nginx`, to
+    /// highlight them. The extension of the name of a template takes
+    /// precedence, eg. for `values.yaml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_language: Option<String>,
+    /// Where the "Send" button posts the rendered template, unless another
+    /// one is set in the settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<Webhook>,
+    /// A JSON object downloaded when the scenario is opened and merged into
+    /// the default values of its inputs, eg. the values in production. The
+    /// `?data_url=` of a link takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_url: Option<String>,
+    /// Renders the templates once per element of a list of the inputs data,
+    /// eg. a Kubernetes manifest per service. The element is the context of
+    /// the template, with `_index` and `_root` added, or `_item` holding it
+    /// if it isn't an object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_each: Option<RenderEach>,
+    /// Splits the inputs into pages filled one after the other, like a
+    /// wizard. All the inputs are on a single page by default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<Step>,
+}
+
+fn current_version() -> u64 {
+    CURRENT_VERSION
+}
+
+/// The languages in which a scenario can be written. YAML is handier for the
+/// multiline templates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// Guesses the format from the extension of a file name or of a URL,
+    /// and otherwise from the content.
+    pub fn detect(source: &str, name: Option<&str>) -> Format {
+        let extension = name
+            .and_then(|name| name.rsplit('.').next())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("json") => Format::Json,
+            Some("yaml") | Some("yml") => Format::Yaml,
+            _ if source.trim_start().starts_with('{') => Format::Json,
+            _ => Format::Yaml,
+        }
+    }
+
+    /// Parses a scenario as a JSON value, which is then deserialized.
+    pub fn parse(self, source: &str) -> Result<JsonValue, AppError> {
+        match self {
+            Format::Json => serde_json::from_str(source)
+                .map_err(|e| AppError::ScenarioParse(format!("Invalid JSON: {}", e))),
+            Format::Yaml => serde_yaml::from_str(source)
+                .map_err(|e| AppError::ScenarioParse(format!("Invalid YAML: {}", e))),
+        }
+    }
+}
+
+/// `{"name": "Network", "inputs": ["host", "port"]}`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Step {
+    pub name: String,
+    /// Keys of the top-level inputs shown in this step.
+    pub inputs: Vec<Path>,
+}
+
+/// `{"path": "services", "separator": "---\n"}`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RenderEach {
+    /// Path of the list in the inputs data, eg. `services`.
+    pub path: Path,
+    /// Put between the documents when they are shown together.
+    #[serde(default)]
+    pub separator: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Template {
+    Single(TemplateSource),
+    /// Several templates rendered from the same inputs, eg. a configuration
+    /// file and its systemd unit, each shown in its own tab.
+    Named(BTreeMap<String, TemplateSource>),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum TemplateSource {
+    StringTemplate(String),
+    StringListTemplate(Vec<String>),
+}
+
+impl Template {
+    /// Returns the names of the templates to render, `MAIN_TEMPLATE` being
+    /// the name of a single template.
+    pub fn names(&self) -> Vec<&str> {
+        match self {
+            Template::Single(_) => vec![MAIN_TEMPLATE],
+            Template::Named(templates) => templates.keys().map(String::as_str).collect(),
+        }
+    }
+
+    /// Returns the templates to render along with their name.
+    pub fn sources(&self) -> Vec<(&str, Cow<'_, str>)> {
+        match self {
+            Template::Single(source) => vec![(MAIN_TEMPLATE, source.source())],
+            Template::Named(templates) => templates
+                .iter()
+                .map(|(name, source)| (name.as_str(), source.source()))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Cow<'_, str>> {
+        match self {
+            Template::Single(source) if name == MAIN_TEMPLATE => Some(source.source()),
+            Template::Single(_) => None,
+            Template::Named(templates) => templates.get(name).map(TemplateSource::source),
+        }
+    }
+
+    /// Replaces the template with the given name, which is ignored for
+    /// single templates.
+    pub fn set(&mut self, name: &str, source: String) {
+        match self {
+            Template::Single(_) => *self = Template::Single(TemplateSource::StringTemplate(source)),
+            Template::Named(templates) => {
+                templates.insert(name.to_owned(), TemplateSource::StringTemplate(source));
+            }
+        }
+    }
+}
+
+impl TemplateSource {
+    /// Returns the template source, with the lines joined if needed.
+    pub fn source(&self) -> Cow<'_, str> {
+        match self {
+            TemplateSource::StringTemplate(s) => Cow::Borrowed(s),
+            TemplateSource::StringListTemplate(ls) => Cow::Owned(ls.join("\n")),
+        }
+    }
+}
+
+impl Scenario {
+    /// Replaces the schema of the scenario, if any, by the inputs generated
+    /// from it.
+    pub fn generate_inputs(&mut self) -> Result<()> {
+        let schema = match self.schema.take() {
+            Some(schema) => schema,
+            None => return Ok(()),
+        };
+        if !self.inputs.is_empty() {
+            bail!("A scenario can't have both inputs and a schema.");
+        }
+        self.inputs = inputs_from_schema(&schema)?;
+        Ok(())
+    }
+
+    /// Returns the inputs shown in the given step. The inputs which aren't in
+    /// any step are shown in the last one.
+    pub fn step_inputs(&self, step: usize) -> Vec<&InputTypes> {
+        if self.steps.is_empty() {
+            return self.inputs.iter().collect();
+        }
+
+        let is_last = step + 1 >= self.steps.len();
+        self.inputs
+            .iter()
+            .filter(|input| match self.steps.get(step) {
+                Some(current) if current.inputs.contains(input.key()) => true,
+                _ => is_last && !self.steps.iter().any(|s| s.inputs.contains(input.key())),
+            })
+            .collect()
+    }
+}
+
+#[allow(unused)]
+pub struct ScenarioAsJson<'a>(&'a Scenario);
+
+/// The inputs data of a freshly loaded scenario, see `InputInfo::default`.
+pub fn initial_inputs_data(scenario: &Scenario) -> Result<InputsData> {
+    let mut inputs_data = InputsData::default();
+    seed_defaults(&scenario.inputs, &Path::default(), &mut inputs_data)?;
+    update_computed_inputs(&scenario.inputs, &mut inputs_data);
+    Ok(inputs_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn step_inputs() {
+        let scenario: Scenario = serde_json::from_value(json!({
+            "template": "",
+            "inputs": [
+                { "key": "a", "name": "A", "type": "text" },
+                { "key": "b", "name": "B", "type": "text" },
+                { "key": "c", "name": "C", "type": "text" }
+            ],
+            "steps": [
+                { "name": "First", "inputs": ["b"] },
+                { "name": "Last", "inputs": ["c"] }
+            ]
+        }))
+        .unwrap();
+        let keys = |step| -> Vec<String> {
+            scenario
+                .step_inputs(step)
+                .iter()
+                .map(|input| input.key().to_string())
+                .collect()
+        };
+
+        assert_eq!(keys(0), ["b"]);
+        assert_eq!(keys(1), ["a", "c"]);
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(Format::detect("{}", None), Format::Json);
+        assert_eq!(Format::detect("  {\"a\": 1}", None), Format::Json);
+        assert_eq!(Format::detect("template: ''", None), Format::Yaml);
+        assert_eq!(Format::detect("{}", Some("scenario.YML")), Format::Yaml);
+        assert_eq!(
+            Format::detect("a: 1", Some("https://x.org/s.json")),
+            Format::Json
+        );
+
+        let yaml = "template: |\n  Hello {{name}}\n  !\ninputs: []\n";
+        let value = Format::Yaml.parse(yaml).unwrap();
+        assert_eq!(
+            value,
+            json!({"template": "Hello {{name}}\n!\n", "inputs": []})
+        );
+        assert!(Format::Json.parse(yaml).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Lets a scenario reuse another one, eg. a base scenario shared by a team,
+//! with `"extends": "<URL or name of an example>"`.
+//!
+//! The scenario is merged in its base: its inputs replace the ones of the
+//! base with the same key and the others are added after them, its named
+//! templates, partials and helpers are added to the ones of the base, and its
+//! other fields replace the ones of the base.
+
+use crate::prelude::*;
+
+/// How many scenarios can extend each other, to stop on cycles.
+pub const MAX_BASES: usize = 8;
+
+/// Fields merged by name with the ones of the base, instead of replacing them.
+const MERGED_FIELDS: &[&str] = &["template", "partials", "helpers"];
+
+/// The scenario extended by this one, if any.
+pub fn base_of(scenario: &JsonValue) -> Option<&str> {
+    scenario.get("extends").and_then(JsonValue::as_str)
+}
+
+/// Merges a scenario in the one it extends. Both must be migrated to the
+/// current version, see `migrate`.
+///
+/// The result extends the base of `base`, if it has one.
+pub fn extend(base: JsonValue, scenario: JsonValue) -> Result<JsonValue> {
+    let (mut merged, fields) = match (base, scenario) {
+        (JsonValue::Object(base), JsonValue::Object(fields)) => (base, fields),
+        _ => bail!("A scenario must be an object."),
+    };
+
+    for (name, value) in fields {
+        if name == "extends" {
+            continue;
+        }
+        let merged_value = match (name.as_str(), merged.remove(&name), value) {
+            ("inputs", Some(JsonValue::Array(inputs)), JsonValue::Array(overrides)) => {
+                JsonValue::Array(merge_inputs(inputs, overrides))
+            }
+            (name, Some(JsonValue::Object(mut base)), JsonValue::Object(overrides))
+                if MERGED_FIELDS.contains(&name) =>
+            {
+                base.extend(overrides);
+                JsonValue::Object(base)
+            }
+            (_, _, value) => value,
+        };
+        merged.insert(name, merged_value);
+    }
+    Ok(JsonValue::Object(merged))
+}
+
+/// Replaces the inputs which have the same key as an override, and adds the
+/// other overrides at the end.
+fn merge_inputs(mut inputs: Vec<JsonValue>, overrides: Vec<JsonValue>) -> Vec<JsonValue> {
+    for input in overrides {
+        let existing = inputs
+            .iter_mut()
+            .find(|existing| input.get("key").is_some() && existing.get("key") == input.get("key"));
+        match existing {
+            Some(existing) => *existing = input,
+            None => inputs.push(input),
+        }
+    }
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extend_scenario() {
+        let base = json!({
+            "extends": "https://example.org/root.json",
+            "title": "Base",
+            "template": { "main": "{{> header}} {{name}}", "other": "{{port}}" },
+            "partials": { "header": "Hello", "footer": "Bye" },
+            "inputs": [
+                { "key": "name", "name": "Name", "type": "text" },
+                { "key": "port", "name": "Port", "type": "number" }
+            ],
+            "strict_mode": true
+        });
+        let scenario = json!({
+            "extends": "base",
+            "title": "Child",
+            "template": { "main": "{{> header}} {{name}}!" },
+            "partials": { "header": "Hi" },
+            "inputs": [
+                { "key": "port", "name": "Port", "type": "number", "default": 80 },
+                { "key": "host", "name": "Host", "type": "text" }
+            ]
+        });
+
+        assert_eq!(
+            extend(base, scenario).unwrap(),
+            json!({
+                "extends": "https://example.org/root.json",
+                "title": "Child",
+                "template": { "main": "{{> header}} {{name}}!", "other": "{{port}}" },
+                "partials": { "header": "Hi", "footer": "Bye" },
+                "inputs": [
+                    { "key": "name", "name": "Name", "type": "text" },
+                    { "key": "port", "name": "Port", "type": "number", "default": 80 },
+                    { "key": "host", "name": "Host", "type": "text" }
+                ],
+                "strict_mode": true
+            })
+        );
+    }
+
+    #[test]
+    fn replace_single_template() {
+        let base = json!({ "template": { "a": "A", "b": "B" } });
+        let scenario = json!({ "extends": "base", "template": "Single" });
+        assert_eq!(
+            extend(base, scenario).unwrap(),
+            json!({ "template": "Single" })
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Resolves the GitHub pages of scenarios to their raw content, so that the
+//! scenario libraries kept in a repository or a gist can be loaded from the
+//! URL in the address bar.
+
+use std::borrow::Cow;
+
+/// Returns the URL of the raw file shown by a GitHub or gist page, or the URL
+/// itself if it isn't one.
+///
+/// - `https://github.com/<owner>/<repo>/blob/<branch>/<path>`
+/// - `https://gist.github.com/<user>/<id>`, which is its first file
+pub fn raw_url(url: &str) -> Cow<'_, str> {
+    let without_scheme = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+    let without_fragment = without_scheme
+        .split(&['?', '#'][..])
+        .next()
+        .unwrap_or_default();
+    let mut segments = without_fragment.split('/').filter(|s| !s.is_empty());
+    let host = segments.next().unwrap_or_default();
+    let segments: Vec<&str> = segments.collect();
+
+    match (host, segments.as_slice()) {
+        ("github.com", [owner, repo, "blob", branch, path @ ..])
+        | ("github.com", [owner, repo, "raw", branch, path @ ..])
+            if !path.is_empty() =>
+        {
+            Cow::Owned(format!(
+                "https://raw.githubusercontent.com/{}/{}/{}/{}",
+                owner,
+                repo,
+                branch,
+                path.join("/")
+            ))
+        }
+        ("gist.github.com", [user, id]) => Cow::Owned(format!(
+            "https://gist.githubusercontent.com/{}/{}/raw",
+            user, id
+        )),
+        ("gist.github.com", [user, id, "raw", rest @ ..]) => Cow::Owned(format!(
+            "https://gist.githubusercontent.com/{}/{}/raw/{}",
+            user,
+            id,
+            rest.join("/")
+        )),
+        _ => Cow::Borrowed(url),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_github_urls() {
+        assert_eq!(
+            raw_url("https://github.com/team/scenarios/blob/master/web/nginx.yaml"),
+            "https://raw.githubusercontent.com/team/scenarios/master/web/nginx.yaml"
+        );
+        assert_eq!(
+            raw_url("https://github.com/team/scenarios/raw/v1/a.json?x=1#L3"),
+            "https://raw.githubusercontent.com/team/scenarios/v1/a.json"
+        );
+        assert_eq!(
+            raw_url("https://gist.github.com/bob/0123abcd"),
+            "https://gist.githubusercontent.com/bob/0123abcd/raw"
+        );
+        assert_eq!(
+            raw_url("https://gist.github.com/bob/0123abcd/raw/f00/s.json"),
+            "https://gist.githubusercontent.com/bob/0123abcd/raw/f00/s.json"
+        );
+
+        for url in &[
+            "https://raw.githubusercontent.com/team/scenarios/master/a.json",
+            "https://github.com/team/scenarios",
+            "https://example.com/blob/a/b/c/d.json",
+        ] {
+            assert_eq!(raw_url(url), *url);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Upgrades the scenarios written for a previous format, so that they keep
+//! loading when the format changes.
+//!
+//! To change the format in a way that breaks the existing scenarios, add a
+//! migration at the end of `MIGRATIONS`: it is given the scenario as JSON,
+//! in the format of the previous version.
+
+use crate::prelude::*;
+
+/// Migrations from each version to the next one, starting from version 1.
+const MIGRATIONS: &[fn(&mut JsonValue)] = &[rename_legacy_constraints];
+
+/// The version of the scenarios deserialized by `Scenario`.
+pub const CURRENT_VERSION: u64 = MIGRATIONS.len() as u64 + 1;
+
+/// A scenario written for a version of the app newer than this one.
+#[derive(Debug)]
+pub struct NewerScenarioError(pub u64);
+
+impl fmt::Display for NewerScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The scenario was written for a newer version of the app ({} > {}), reload the page \
+             to update it.",
+            self.0, CURRENT_VERSION
+        )
+    }
+}
+
+impl std::error::Error for NewerScenarioError {}
+
+/// Upgrades a scenario to the current version. The scenarios without a
+/// version were written for the first one.
+pub fn migrate(mut scenario: JsonValue) -> Result<JsonValue> {
+    let version = match scenario.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| anyhow!("Invalid scenario version: {}", version))?,
+    };
+    if version > CURRENT_VERSION {
+        return Err(NewerScenarioError(version).into());
+    }
+
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(&mut scenario);
+    }
+    if let JsonValue::Object(fields) = &mut scenario {
+        fields.insert("version".to_owned(), CURRENT_VERSION.into());
+    }
+    Ok(scenario)
+}
+
+/// Calls `f` on every input of the scenario, including the nested ones.
+fn for_each_input(scenario: &mut JsonValue, f: &impl Fn(&mut JsonValue)) {
+    fn visit(inputs: Option<&mut JsonValue>, f: &impl Fn(&mut JsonValue)) {
+        if let Some(JsonValue::Array(inputs)) = inputs {
+            for input in inputs {
+                f(input);
+                visit(input.get_mut("inputs"), f);
+                visit(input.get_mut("columns"), f);
+            }
+        }
+    }
+    visit(scenario.get_mut("inputs"), f);
+}
+
+fn rename_field(input: &mut JsonValue, from: &str, to: &str) {
+    if let JsonValue::Object(fields) = input {
+        if let Some(value) = fields.remove(from) {
+            fields.entry(to).or_insert(value);
+        }
+    }
+}
+
+/// Version 2: `validate_regex` of the text inputs became `pattern`, and the
+/// `min_length` and `max_length` of the lists became `min_items` and
+/// `max_items`.
+fn rename_legacy_constraints(scenario: &mut JsonValue) {
+    for_each_input(
+        scenario,
+        &|input| match input.get("type").and_then(JsonValue::as_str) {
+            Some("text") => rename_field(input, "validate_regex", "pattern"),
+            Some("list") => {
+                rename_field(input, "min_length", "min_items");
+                rename_field(input, "max_length", "max_items");
+            }
+            _ => {}
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrate_legacy_scenario() {
+        let scenario = json!({
+            "template": "",
+            "inputs": [{
+                "key": "g", "name": "G", "type": "group",
+                "inputs": [
+                    { "key": "t", "name": "T", "type": "text", "validate_regex": "\\d+" },
+                    { "key": "l", "name": "L", "type": "list", "min_length": 1, "inputs": [] }
+                ]
+            }]
+        });
+        assert_eq!(
+            migrate(scenario).unwrap(),
+            json!({
+                "template": "",
+                "version": CURRENT_VERSION,
+                "inputs": [{
+                    "key": "g", "name": "G", "type": "group",
+                    "inputs": [
+                        { "key": "t", "name": "T", "type": "text", "pattern": "\\d+" },
+                        { "key": "l", "name": "L", "type": "list", "min_items": 1, "inputs": [] }
+                    ]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn reject_newer_scenario() {
+        let current = json!({ "template": "", "inputs": [], "version": CURRENT_VERSION });
+        assert_eq!(migrate(current.clone()).unwrap(), current);
+
+        let newer = json!({ "template": "", "inputs": [], "version": CURRENT_VERSION + 1 });
+        assert!(migrate(newer).unwrap_err().is::<NewerScenarioError>());
+        assert!(migrate(json!({ "version": "2" })).is_err());
+    }
+}
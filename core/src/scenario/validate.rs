@@ -0,0 +1,358 @@
+//! Checks a scenario before it is loaded, to list all its mistakes at once
+//! instead of failing on the first one.
+
+use super::Scenario;
+use crate::{
+    inputs::{input_at, input_plugin, Input, InputTypes, SelectOption},
+    prelude::*,
+    template_engine::{EngineKind, HandlebarsEngine},
+    Path,
+};
+use std::collections::HashSet;
+
+/// The `type`s of the inputs, see `InputTypes`, besides the ones added with
+/// `register_input_type`.
+const INPUT_TYPES: &[&str] = &[
+    "text",
+    "secret",
+    "boolean",
+    "number",
+    "quantity",
+    "slider",
+    "select",
+    "multi_select",
+    "remote_select",
+    "radio",
+    "group",
+    "list",
+    "table",
+    "tags",
+    "computed",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    /// Prevents the scenario from being loaded.
+    Error,
+    Warning,
+}
+
+/// A problem found in a scenario, see `validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The input the problem is about, if any.
+    pub path: Option<Path>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(path: Option<&Path>, message: impl ToString) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            path: path.cloned(),
+            message: message.to_string(),
+        }
+    }
+
+    fn warning(path: Option<&Path>, message: impl ToString) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(path, message)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+/// Checks a scenario, still as JSON so that the inputs which can't be
+/// deserialized are reported one by one.
+///
+/// The duplicate paths, unknown input types and invalid defaults are errors.
+/// The variables of a Handlebars template which aren't declared by an input
+/// are warnings, since the template may set them itself.
+pub fn validate(json: &JsonValue) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    if let Some(inputs) = json.get("inputs") {
+        check_inputs(
+            inputs,
+            &Path::default(),
+            &mut HashSet::new(),
+            &mut diagnostics,
+        );
+    }
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        return diagnostics;
+    }
+
+    let mut scenario: Scenario = match serde_json::from_value(json.clone()) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            diagnostics.push(Diagnostic::error(None, format!("Invalid scenario: {}", e)));
+            return diagnostics;
+        }
+    };
+    if let Err(e) = scenario.generate_inputs() {
+        diagnostics.push(Diagnostic::error(None, format!("{:#}", e)));
+        return diagnostics;
+    }
+
+    // A template which doesn't compile is shown in the editor, to be fixed
+    if scenario.engine == EngineKind::Handlebars {
+        if let Ok(engine) = HandlebarsEngine::with_scenario(&scenario) {
+            for variable in engine.referenced_variables() {
+                let path = Path::from(variable.as_str());
+                if !is_declared(&scenario.inputs, &path) {
+                    let message = "The template uses this variable, but no input declares it.";
+                    diagnostics.push(Diagnostic::warning(Some(&path), message));
+                }
+            }
+        }
+    }
+    diagnostics
+}
+
+fn check_inputs(
+    inputs: &JsonValue,
+    key_base: &Path,
+    seen: &mut HashSet<Path>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let inputs = match inputs.as_array() {
+        Some(inputs) => inputs,
+        None => {
+            let path = Some(key_base).filter(|p| **p != Path::default());
+            diagnostics.push(Diagnostic::error(path, "The inputs must be an array."));
+            return;
+        }
+    };
+
+    for input in inputs {
+        let errors_before = diagnostics.iter().filter(|d| d.is_error()).count();
+
+        let key = match input.get("key").and_then(JsonValue::as_str) {
+            Some(key) => key,
+            None => {
+                let path = Some(key_base).filter(|p| **p != Path::default());
+                diagnostics.push(Diagnostic::error(path, "An input doesn't have a key."));
+                continue;
+            }
+        };
+        let path = key_base + &Path::from(key);
+        if !seen.insert(path.clone()) {
+            diagnostics.push(Diagnostic::error(
+                Some(&path),
+                "Another input has the same path.",
+            ));
+        }
+
+        match input.get("type").and_then(JsonValue::as_str) {
+            Some(kind) if INPUT_TYPES.contains(&kind) || input_plugin(kind).is_some() => {}
+            Some(kind) => {
+                let message = format!("Unknown input type '{}'.", kind);
+                diagnostics.push(Diagnostic::error(Some(&path), message));
+            }
+            None => diagnostics.push(Diagnostic::error(Some(&path), "Missing input type.")),
+        }
+        for children in &["inputs", "columns"] {
+            if let Some(children) = input.get(*children) {
+                check_inputs(children, &path, seen, diagnostics);
+            }
+        }
+
+        // Otherwise the errors would be reported again by the parent
+        if diagnostics.iter().filter(|d| d.is_error()).count() > errors_before {
+            continue;
+        }
+        match serde_json::from_value::<InputTypes>(input.clone()) {
+            Ok(input) => {
+                if let Err(message) = check_default(&input) {
+                    diagnostics.push(Diagnostic::error(Some(&path), message));
+                }
+            }
+            Err(e) => {
+                let message = format!("Invalid input: {}", e);
+                diagnostics.push(Diagnostic::error(Some(&path), message));
+            }
+        }
+    }
+}
+
+/// Checks that the default value of an input is one it could hold.
+fn check_default(input: &InputTypes) -> Result<(), String> {
+    let default = match input.default_value() {
+        Some(JsonValue::Null) | None => return Ok(()),
+        Some(default) => default,
+    };
+
+    let is_option = |options: &[SelectOption], value: &JsonValue| {
+        options
+            .iter()
+            .any(|option| Some(option.value.as_str()) == value.as_str())
+    };
+    let are_options = |options: &[SelectOption]| match default.as_array() {
+        Some(values) => values.iter().all(|value| is_option(options, value)),
+        None => false,
+    };
+    let (valid, expected) = match input {
+        InputTypes::Text(_) | InputTypes::Secret(_) | InputTypes::RemoteSelect(_) => {
+            (default.is_string(), "a string")
+        }
+        InputTypes::Select(select) => (is_option(&select.options, default), "one of the options"),
+        InputTypes::Radio(radio) => (is_option(&radio.options, default), "one of the options"),
+        InputTypes::MultiSelect(select) => (are_options(&select.options), "an array of options"),
+        InputTypes::Number(_) | InputTypes::Slider(_) | InputTypes::Quantity(_) => {
+            (default.is_number(), "a number")
+        }
+        InputTypes::Boolean(_) => (default.is_boolean(), "a boolean"),
+        InputTypes::Tags(_) => match default.as_array() {
+            Some(values) => (
+                values.iter().all(JsonValue::is_string),
+                "an array of strings",
+            ),
+            None => (false, "an array of strings"),
+        },
+        InputTypes::List(_) | InputTypes::Table(_) => (default.is_array(), "an array"),
+        InputTypes::Group(_) => (default.is_object(), "an object"),
+        InputTypes::Computed(_) => (false, "nothing, since the value is computed"),
+        InputTypes::Custom(custom) => match custom.validate(default) {
+            Ok(()) => (true, "a value of its type"),
+            Err(e) => return Err(format!("Invalid default value {}: {}", default, e)),
+        },
+    };
+    if !valid {
+        return Err(format!(
+            "Invalid default value {}: expected {}.",
+            default, expected
+        ));
+    }
+
+    match input.validation() {
+        Some(validation) => validation
+            .validate(default)
+            .map_err(|e| format!("Invalid default value {}: {}", default, e)),
+        None => Ok(()),
+    }
+}
+
+/// Whether a variable of the template is the value of an input, or a
+/// property of one, eg. `persons.length`.
+fn is_declared(inputs: &[InputTypes], path: &Path) -> bool {
+    let segments: Vec<&str> = path.get_segments().collect();
+    (1..=segments.len()).rev().any(|len| {
+        let prefix = Path::from_segments(&segments[..len]);
+        match input_at(inputs, &prefix) {
+            Some(InputTypes::Group(_)) => len == segments.len(),
+            Some(_) => true,
+            None => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn messages(json: JsonValue) -> Vec<(Severity, String)> {
+        validate(&json)
+            .into_iter()
+            .map(|d| {
+                let path = d.path.map(|p| p.to_string()).unwrap_or_default();
+                (d.severity, format!("{}: {}", path, d.message))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid_scenario() {
+        let scenario = json!({
+            "template": "{{name}} {{#each persons}}{{first_name}}{{/each}} {{persons.length}}",
+            "inputs": [
+                { "key": "name", "name": "Name", "type": "text", "default": "Bob" },
+                {
+                    "key": "persons", "name": "Persons", "type": "list",
+                    "inputs": [{ "key": "first_name", "name": "First name", "type": "text" }]
+                }
+            ]
+        });
+        assert_eq!(messages(scenario), []);
+    }
+
+    #[test]
+    fn report_all_errors() {
+        let scenario = json!({
+            "template": "",
+            "inputs": [
+                { "key": "a", "name": "A", "type": "text" },
+                { "key": "a", "name": "A", "type": "text" },
+                { "key": "b", "name": "B", "type": "colour" },
+                {
+                    "key": "c", "name": "C", "type": "group",
+                    "inputs": [{ "key": "d", "name": "D", "type": "number", "default": "1" }]
+                },
+                {
+                    "key": "e", "name": "E", "type": "select", "default": "z",
+                    "options": [{ "value": "x" }]
+                },
+                {
+                    "key": "f", "name": "F", "type": "text", "default": "abc",
+                    "validation": { "max_length": 2 }
+                }
+            ]
+        });
+        assert_eq!(
+            messages(scenario),
+            [
+                (
+                    Severity::Error,
+                    "a: Another input has the same path.".to_owned()
+                ),
+                (
+                    Severity::Error,
+                    "b: Unknown input type 'colour'.".to_owned()
+                ),
+                (
+                    Severity::Error,
+                    "c.d: Invalid default value \"1\": expected a number.".to_owned()
+                ),
+                (
+                    Severity::Error,
+                    "e: Invalid default value \"z\": expected one of the options.".to_owned()
+                ),
+                (
+                    Severity::Error,
+                    "f: Invalid default value \"abc\": Must have a length of at most 2, got 3."
+                        .to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn warn_about_undeclared_variables() {
+        let scenario = json!({
+            "template": "{{author.name}} {{author.age}} {{title}}",
+            "inputs": [{
+                "key": "author", "name": "Author", "type": "group",
+                "inputs": [{ "key": "name", "name": "Name", "type": "text" }]
+            }]
+        });
+        assert_eq!(
+            messages(scenario),
+            [
+                (
+                    Severity::Warning,
+                    "author.age: The template uses this variable, but no input declares it."
+                        .to_owned()
+                ),
+                (
+                    Severity::Warning,
+                    "title: The template uses this variable, but no input declares it.".to_owned()
+                ),
+            ]
+        );
+    }
+}
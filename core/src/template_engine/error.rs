@@ -0,0 +1,94 @@
+use crate::scenario::MAIN_TEMPLATE;
+use std::fmt;
+
+/// Error returned when the template of a scenario, or one of its partials,
+/// fails to compile. The position is known when the engine reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateError {
+    /// Name of the partial or named template that failed, `None` for the
+    /// main template.
+    pub template: Option<String>,
+    /// 1-based line of the error.
+    pub line: Option<usize>,
+    /// 1-based column of the error.
+    pub column: Option<usize>,
+    pub reason: String,
+}
+
+impl TemplateError {
+    pub fn new<T: ToString>(reason: T) -> Self {
+        Self {
+            template: None,
+            line: None,
+            column: None,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Sets the template in which the error is, if it isn't the main one.
+    pub fn in_template(mut self, name: &str) -> Self {
+        self.template = Some(name)
+            .filter(|n| *n != MAIN_TEMPLATE)
+            .map(ToOwned::to_owned);
+        self
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Template error")?;
+        if let Some(template) = &self.template {
+            write!(f, " in '{}'", template)?;
+        }
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " at line {}, column {}", line, column)?,
+            (Some(line), None) => write!(f, " at line {}", line)?,
+            _ => {}
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl From<handlebars::TemplateError> for TemplateError {
+    fn from(e: handlebars::TemplateError) -> Self {
+        Self {
+            template: None,
+            line: e.line_no,
+            column: e.column_no,
+            reason: e.reason.to_string(),
+        }
+    }
+}
+
+impl From<tera::Error> for TemplateError {
+    /// Tera only reports the position in the message of the parsing error,
+    /// as ` --> line:column`.
+    fn from(e: tera::Error) -> Self {
+        let mut messages = vec![e.to_string()];
+        let mut source = std::error::Error::source(&e);
+        while let Some(e) = source {
+            messages.push(e.to_string());
+            source = e.source();
+        }
+
+        let position = messages.iter().find_map(|m| {
+            let (_, position) = m.split_once("--> ")?;
+            let position = position.split_whitespace().next()?;
+            let (line, column) = position.split_once(':')?;
+            Some((line.parse().ok()?, column.parse().ok()?))
+        });
+        let template = messages[0]
+            .strip_prefix("Failed to parse '")
+            .and_then(|m| m.split('\'').next())
+            .filter(|name| *name != MAIN_TEMPLATE);
+
+        Self {
+            template: template.map(ToOwned::to_owned),
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+            reason: messages.last().cloned().unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use super::{
+    helpers, unresolved,
+    whitespace::{Syntax, Whitespace},
+    Rendered, TemplateEngine, TemplateError,
+};
+use crate::{
+    scenario::{Scenario, MAIN_TEMPLATE},
+    AppError,
+};
+use anyhow::Result;
+use handlebars::Handlebars;
+use serde::Serialize;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
+    hash::{Hash, Hasher},
+};
+
+/// Maximum number of compiled registries kept around.
+const CACHE_CAPACITY: usize = 8;
+
+pub struct HandlebarsEngine {
+    inner: Handlebars<'static>,
+    /// Names of the templates to render, the others being partials.
+    outputs: Vec<String>,
+    /// Hash of the sources `inner` was compiled from.
+    inner_hash: Option<u64>,
+    /// Previously compiled registries, by hash of their sources, so that
+    /// loading the same scenario again (eg. restoring it) is free.
+    cache: HashMap<u64, Handlebars<'static>>,
+    strict_mode: bool,
+    whitespace: Whitespace,
+}
+
+impl HandlebarsEngine {
+    /// Creates an engine without template, with the built-in helpers.
+    pub fn new_uninit() -> Self {
+        Self {
+            inner: new_registry(true),
+            outputs: vec![],
+            inner_hash: None,
+            cache: HashMap::new(),
+            strict_mode: false,
+            whitespace: Whitespace::default(),
+        }
+    }
+
+    pub fn strict_mode(&self) -> bool {
+        self.strict_mode
+    }
+
+    pub fn with_scenario(scenario: &Scenario) -> Result<Self> {
+        let mut s = Self::new_uninit();
+        s.set_template(scenario)?;
+        Ok(s)
+    }
+
+    /// Paths of the data used by the templates, see
+    /// `unresolved::referenced_variables`.
+    pub fn referenced_variables(&self) -> BTreeSet<String> {
+        self.outputs
+            .iter()
+            .flat_map(|name| unresolved::referenced_variables(&self.inner, name))
+            .collect()
+    }
+
+    #[allow(unused)]
+    fn is_initialized(&self) -> bool {
+        self.inner.has_template(MAIN_TEMPLATE)
+    }
+
+    /// Replaces the current registry, keeping it in the cache.
+    fn swap_registry(&mut self, registry: Handlebars<'static>, hash: u64) {
+        let previous = std::mem::replace(&mut self.inner, registry);
+        if let Some(previous_hash) = self.inner_hash.replace(hash) {
+            if self.cache.len() >= CACHE_CAPACITY {
+                self.cache.clear();
+            }
+            self.cache.insert(previous_hash, previous);
+        }
+        self.inner.set_strict_mode(self.strict_mode);
+    }
+}
+
+/// Hashes everything a registry is compiled from.
+fn hash_sources(scenario: &Scenario) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    scenario.template.sources().hash(&mut hasher);
+    scenario.disable_builtin_helpers.hash(&mut hasher);
+    scenario.whitespace.hash(&mut hasher);
+
+    let mut partials: Vec<_> = scenario.partials.iter().collect();
+    partials.sort_by_key(|(name, _)| *name);
+    for (name, partial) in partials {
+        (name, partial.source()).hash(&mut hasher);
+    }
+
+    let mut helpers: Vec<_> = scenario.helpers.iter().collect();
+    helpers.sort_by_key(|(name, _)| *name);
+    helpers.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+fn new_registry(builtin_helpers: bool) -> Handlebars<'static> {
+    let mut registry = Handlebars::default();
+    if builtin_helpers {
+        helpers::register_builtin_helpers(&mut registry);
+    }
+    registry
+}
+
+impl TemplateEngine for HandlebarsEngine {
+    /// Registers the helpers and partials declared by the scenario, then
+    /// compiles its template.
+    fn set_template(&mut self, scenario: &Scenario) -> Result<(), TemplateError> {
+        let hash = hash_sources(scenario);
+        if self.inner_hash == Some(hash) {
+            return Ok(());
+        }
+        if let Some(registry) = self.cache.remove(&hash) {
+            self.swap_registry(registry, hash);
+            self.whitespace = scenario.whitespace;
+            self.outputs = scenario
+                .template
+                .names()
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect();
+            return Ok(());
+        }
+
+        // Start from a fresh registry so that the helpers of a previously
+        // loaded scenario don't leak into this one. The current one is kept
+        // if the new template fails to compile.
+        let mut registry = new_registry(!scenario.disable_builtin_helpers);
+        for (name, helper) in &scenario.helpers {
+            registry.register_helper(name, Box::new(helper.clone()));
+        }
+
+        let whitespace = scenario.whitespace;
+        for (name, partial) in &scenario.partials {
+            registry
+                .register_partial(
+                    name,
+                    whitespace.prepare(partial.source(), Syntax::Handlebars),
+                )
+                .map_err(|e| TemplateError::from(e).in_template(name))?;
+        }
+
+        for (name, source) in scenario.template.sources() {
+            registry
+                .register_template_string(name, whitespace.prepare(source, Syntax::Handlebars))
+                .map_err(|e| TemplateError::from(e).in_template(name))?;
+        }
+        self.swap_registry(registry, hash);
+        self.whitespace = whitespace;
+        self.outputs = scenario
+            .template
+            .names()
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect();
+        Ok(())
+    }
+
+    fn set_strict_mode(&mut self, enabled: bool) {
+        self.strict_mode = enabled;
+        self.inner.set_strict_mode(enabled);
+    }
+
+    fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+
+    fn read_keys(&self, name: &str) -> Option<BTreeSet<String>> {
+        unresolved::read_keys(&self.inner, name)
+    }
+
+    fn render_named<T: Serialize>(&self, name: &str, data: &T) -> Result<Rendered, AppError> {
+        let data = serde_json::to_value(data)
+            .map_err(|e| AppError::render("Failed to serialize the data", e))?;
+        let output = self
+            .inner
+            .render(name, &data)
+            .map_err(|e| AppError::render("Handlebars template engine failed to render data", e))?;
+        Ok(Rendered {
+            output: self.whitespace.finish(output),
+            unresolved: unresolved::unresolved_variables(&self.inner, name, &data),
+            ..Rendered::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn compiled_registries_are_cached() {
+        let scenario =
+            |template| serde_json::from_value(json!({"template": template, "inputs": []}));
+        let (a, b): (Scenario, Scenario) = (scenario("a").unwrap(), scenario("b").unwrap());
+
+        let mut engine = HandlebarsEngine::new_uninit();
+        engine.set_template(&a).unwrap();
+        engine.set_template(&b).unwrap();
+        assert_eq!(engine.cache.len(), 1);
+        engine.set_template(&a).unwrap();
+        assert_eq!(engine.cache.len(), 1);
+        assert_eq!(
+            engine
+                .render_named(MAIN_TEMPLATE, &json!({}))
+                .unwrap()
+                .output,
+            "a"
+        );
+    }
+}
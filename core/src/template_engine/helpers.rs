@@ -0,0 +1,270 @@
+use crate::json_path::Path;
+use chrono::{NaiveDate, NaiveDateTime};
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, JsonRender, RenderContext, RenderError, ScopedJson,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// A helper declared in the `helpers` section of a scenario, registered
+/// under its name before the template is compiled.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum HelperDefinition {
+    /// Applies a chain of string transformations to the first parameter,
+    /// eg. `{{shout name}}` with `"transforms": ["trim", "uppercase"]`.
+    Transform { transforms: Vec<Transform> },
+    /// Renders a small Handlebars expression in which the parameters are
+    /// available as `args` and the hash parameters by their name,
+    /// eg. `"expression": "{{args.[0]}}{{sep}}{{args.[1]}}"`.
+    Expression { expression: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    Trim,
+    Uppercase,
+    Lowercase,
+    Prefix(String),
+    Suffix(String),
+    Replace {
+        from: String,
+        to: String,
+    },
+    /// Replaces an empty string by the given value.
+    Default(String),
+}
+
+impl Transform {
+    pub(super) fn apply(&self, s: String) -> String {
+        match self {
+            Transform::Trim => s.trim().to_owned(),
+            Transform::Uppercase => s.to_uppercase(),
+            Transform::Lowercase => s.to_lowercase(),
+            Transform::Prefix(prefix) => format!("{}{}", prefix, s),
+            Transform::Suffix(suffix) => format!("{}{}", s, suffix),
+            Transform::Replace { from, to } => s.replace(from, to),
+            Transform::Default(default) if s.is_empty() => default.clone(),
+            Transform::Default(_) => s,
+        }
+    }
+}
+
+impl HelperDef for HelperDefinition {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<Option<ScopedJson<'reg, 'rc>>, RenderError> {
+        let result = match self {
+            HelperDefinition::Transform { transforms } => {
+                let input = h.param(0).map(|p| p.value().render()).unwrap_or_default();
+                transforms.iter().fold(input, |s, t| t.apply(s))
+            }
+            HelperDefinition::Expression { expression } => {
+                let mut data: serde_json::Map<_, _> = h
+                    .hash()
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.value().clone()))
+                    .collect();
+                data.insert(
+                    "args".to_owned(),
+                    h.params().iter().map(|p| p.value().clone()).collect(),
+                );
+                r.render_template(expression, &data)
+                    .map_err(|e| RenderError::new(format!("`{}` helper: {}", h.name(), e)))?
+            }
+        };
+        Ok(Some(ScopedJson::Derived(JsonValue::String(result))))
+    }
+}
+
+/// Registers the helpers that are available to every scenario, unless it
+/// opts out with `disable_builtin_helpers`.
+pub fn register_builtin_helpers(registry: &mut Handlebars) {
+    registry.register_helper("uppercase", Box::new(StringHelper(str::to_uppercase)));
+    registry.register_helper("lowercase", Box::new(StringHelper(str::to_lowercase)));
+    registry.register_helper("snake_case", Box::new(StringHelper(to_snake_case)));
+    registry.register_helper(
+        "add",
+        Box::new(ArithmeticHelper {
+            identity: 0.,
+            op: |a, b| a + b,
+        }),
+    );
+    registry.register_helper(
+        "multiply",
+        Box::new(ArithmeticHelper {
+            identity: 1.,
+            op: |a, b| a * b,
+        }),
+    );
+    registry.register_helper("format_date", Box::new(FormatDateHelper));
+    registry.register_helper("ref", Box::new(RefHelper));
+}
+
+/// Applies a function to the first parameter, rendered as a string.
+struct StringHelper(fn(&str) -> String);
+
+impl HelperDef for StringHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _: &'reg Handlebars,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<Option<ScopedJson<'reg, 'rc>>, RenderError> {
+        let input = h.param(0).map(|p| p.value().render()).unwrap_or_default();
+        Ok(Some(ScopedJson::Derived(JsonValue::String((self.0)(
+            &input,
+        )))))
+    }
+}
+
+/// Folds all the parameters, which must be numbers or numeric strings.
+struct ArithmeticHelper {
+    identity: f64,
+    op: fn(f64, f64) -> f64,
+}
+
+impl HelperDef for ArithmeticHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _: &'reg Handlebars,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<Option<ScopedJson<'reg, 'rc>>, RenderError> {
+        let result = h.params().iter().try_fold(self.identity, |acc, param| {
+            let n = match param.value() {
+                JsonValue::Number(n) => n.as_f64(),
+                JsonValue::String(s) => s.trim().parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                RenderError::new(format!(
+                    "`{}` helper: {} is not a number",
+                    h.name(),
+                    param.value()
+                ))
+            })?;
+            Ok::<_, RenderError>((self.op)(acc, n))
+        })?;
+
+        // Keep integers looking like integers, ie. 3 instead of 3.0
+        let result = if result.fract() == 0. && result.abs() < (1u64 << 53) as f64 {
+            JsonValue::from(result as i64)
+        } else {
+            JsonValue::from(result)
+        };
+        Ok(Some(ScopedJson::Derived(result)))
+    }
+}
+
+/// `{{format_date value "%d %B %Y" from="%d/%m/%Y"}}`
+///
+/// Without `from`, the value is parsed either as an RFC 3339 date-time,
+/// a `%Y-%m-%d %H:%M:%S` date-time or a `%Y-%m-%d` date. Empty values are
+/// rendered as empty strings.
+struct FormatDateHelper;
+
+impl HelperDef for FormatDateHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _: &'reg Handlebars,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<Option<ScopedJson<'reg, 'rc>>, RenderError> {
+        let input = h.param(0).map(|p| p.value().render()).unwrap_or_default();
+        let format = h
+            .param(1)
+            .and_then(|p| p.value().as_str())
+            .ok_or_else(|| RenderError::new("`format_date` helper: missing format parameter"))?;
+
+        if input.trim().is_empty() {
+            return Ok(Some(ScopedJson::Derived(JsonValue::String(String::new()))));
+        }
+
+        let date_time = match h.hash_get("from").and_then(|p| p.value().as_str()) {
+            Some(from) => parse_date_time(&input, &[from]),
+            None => parse_date_time(&input, &["%+", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d"]),
+        }
+        .ok_or_else(|| {
+            RenderError::new(format!("`format_date` helper: can't parse '{}'", input))
+        })?;
+
+        Ok(Some(ScopedJson::Derived(JsonValue::String(
+            date_time.format(format).to_string(),
+        ))))
+    }
+}
+
+/// `{{ref "servers.0.name"}}`
+///
+/// Looks up a path in the whole inputs data, whatever the current block
+/// context is. A missing value is `null`, or an error in strict mode.
+struct RefHelper;
+
+impl HelperDef for RefHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars,
+        ctx: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<Option<ScopedJson<'reg, 'rc>>, RenderError> {
+        let path = h
+            .param(0)
+            .and_then(|p| p.value().as_str())
+            .ok_or_else(|| RenderError::new("`ref` helper: missing path parameter"))?;
+
+        let path: Path = path
+            .parse()
+            .map_err(|e| RenderError::new(format!("`ref` helper: {}", e)))?;
+        match path.lookup(ctx.data()) {
+            Some(value) => Ok(Some(ScopedJson::Derived(value.clone()))),
+            None if r.strict_mode() => Err(RenderError::new(format!(
+                "`ref` helper: nothing at '{}'",
+                path
+            ))),
+            None => Ok(Some(ScopedJson::Derived(JsonValue::Null))),
+        }
+    }
+}
+
+fn parse_date_time(input: &str, formats: &[&str]) -> Option<NaiveDateTime> {
+    let input = input.trim();
+    formats.iter().find_map(|format| {
+        NaiveDateTime::parse_from_str(input, format)
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(input, format)
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+    })
+}
+
+fn to_snake_case(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 4);
+    let mut previous_is_lower = false;
+    for c in s.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && previous_is_lower {
+                result.push('_');
+            }
+            previous_is_lower = c.is_lowercase() || c.is_numeric();
+            result.extend(c.to_lowercase());
+        } else {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+            previous_is_lower = false;
+        }
+    }
+    result.trim_end_matches('_').to_owned()
+}
@@ -0,0 +1,497 @@
+use crate::{
+    scenario::{RenderEach, Scenario},
+    AppError, InputsData,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeSet;
+
+mod error;
+mod handlebars_engine;
+mod helpers;
+mod tera_engine;
+mod unresolved;
+mod whitespace;
+pub use error::TemplateError;
+pub use handlebars_engine::HandlebarsEngine;
+pub use helpers::HelperDefinition;
+pub use tera_engine::TeraEngine;
+pub use whitespace::Whitespace;
+
+pub trait TemplateEngine {
+    /// Compiles the template of the scenario, along with everything it
+    /// declares for it (helpers, partials...).
+    fn set_template(&mut self, scenario: &Scenario) -> Result<(), TemplateError>;
+
+    /// Makes the rendering fail on missing variables, naming them.
+    fn set_strict_mode(&mut self, enabled: bool);
+
+    /// Whether the rendering always fails on missing variables, in which case
+    /// `set_strict_mode` does nothing.
+    fn always_strict(&self) -> bool {
+        false
+    }
+
+    /// Names of the templates that can be rendered, see `Template::names`.
+    fn outputs(&self) -> &[String];
+
+    /// The top-level keys of the data that the template `name` may read, so
+    /// that changing the others doesn't need to render it again. `None` if
+    /// it may read any of them.
+    fn read_keys(&self, _name: &str) -> Option<BTreeSet<String>> {
+        None
+    }
+
+    fn render_named<T: Serialize>(&self, name: &str, data: &T) -> Result<Rendered, AppError>;
+}
+
+/// The output of a template.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Rendered {
+    pub output: String,
+    /// Variables used by the template which resolved to nothing, most
+    /// likely because the inputs they come from weren't filled.
+    pub unresolved: BTreeSet<String>,
+    /// How long the rendering took, in milliseconds.
+    pub duration_ms: f64,
+    /// Labels and outputs of the documents, when the template is rendered
+    /// once per element of a list. `output` holds all of them.
+    pub documents: Vec<(String, String)>,
+}
+
+/// The template engines a scenario can choose from.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineKind {
+    #[default]
+    Handlebars,
+    Tera,
+}
+
+/// Dispatches to the engine selected by the loaded scenario.
+pub struct Engine {
+    inner: AnyEngine,
+    /// The strict mode asked for, kept for the engines which don't have one,
+    /// so that it applies again to the next engine.
+    strict_mode: bool,
+}
+
+enum AnyEngine {
+    Handlebars(HandlebarsEngine),
+    Tera(TeraEngine),
+}
+
+impl Engine {
+    pub fn new_uninit() -> Self {
+        Engine {
+            inner: AnyEngine::Handlebars(HandlebarsEngine::new_uninit()),
+            strict_mode: false,
+        }
+    }
+
+    pub fn kind(&self) -> EngineKind {
+        match self.inner {
+            AnyEngine::Handlebars(_) => EngineKind::Handlebars,
+            AnyEngine::Tera(_) => EngineKind::Tera,
+        }
+    }
+}
+
+impl TemplateEngine for Engine {
+    /// Switches to the engine requested by the scenario if needed, keeping
+    /// the strict mode setting.
+    fn set_template(&mut self, scenario: &Scenario) -> Result<(), TemplateError> {
+        if self.kind() != scenario.engine {
+            self.inner = match scenario.engine {
+                EngineKind::Handlebars => AnyEngine::Handlebars(HandlebarsEngine::new_uninit()),
+                EngineKind::Tera => AnyEngine::Tera(TeraEngine::new_uninit()),
+            };
+            self.set_strict_mode(self.strict_mode);
+        }
+
+        match &mut self.inner {
+            AnyEngine::Handlebars(engine) => engine.set_template(scenario),
+            AnyEngine::Tera(engine) => engine.set_template(scenario),
+        }
+    }
+
+    fn set_strict_mode(&mut self, enabled: bool) {
+        self.strict_mode = enabled;
+        match &mut self.inner {
+            AnyEngine::Handlebars(engine) => engine.set_strict_mode(enabled),
+            AnyEngine::Tera(engine) => engine.set_strict_mode(enabled),
+        }
+    }
+
+    fn always_strict(&self) -> bool {
+        match &self.inner {
+            AnyEngine::Handlebars(engine) => engine.always_strict(),
+            AnyEngine::Tera(engine) => engine.always_strict(),
+        }
+    }
+
+    fn outputs(&self) -> &[String] {
+        match &self.inner {
+            AnyEngine::Handlebars(engine) => engine.outputs(),
+            AnyEngine::Tera(engine) => engine.outputs(),
+        }
+    }
+
+    fn read_keys(&self, name: &str) -> Option<BTreeSet<String>> {
+        match &self.inner {
+            AnyEngine::Handlebars(engine) => engine.read_keys(name),
+            AnyEngine::Tera(engine) => engine.read_keys(name),
+        }
+    }
+
+    fn render_named<T: Serialize>(&self, name: &str, data: &T) -> Result<Rendered, AppError> {
+        match &self.inner {
+            AnyEngine::Handlebars(engine) => engine.render_named(name, data),
+            AnyEngine::Tera(engine) => engine.render_named(name, data),
+        }
+    }
+}
+
+/// Renders a template once per element of a list, see
+/// `Scenario::render_each`.
+pub fn render_each_element<T: TemplateEngine>(
+    name: &str,
+    inputs_data: &InputsData,
+    render_each: &RenderEach,
+    template_engine: &T,
+) -> Result<Rendered, AppError> {
+    let elements = match inputs_data.get_at(&render_each.path) {
+        Some(JsonValue::Array(elements)) => elements,
+        None | Some(JsonValue::Null) => return Ok(Rendered::default()),
+        Some(_) => {
+            return Err(AppError::path_access(
+                &render_each.path,
+                "it isn't a list, so the template can't be rendered once per element",
+            ))
+        }
+    };
+
+    let root = serde_json::to_value(inputs_data)
+        .map_err(|e| AppError::render("Failed to serialize the data", e))?;
+    let mut rendered = Rendered::default();
+    for (index, element) in elements.iter().enumerate() {
+        let mut context = match element {
+            JsonValue::Object(element) => element.clone(),
+            _ => std::iter::once(("_item".to_owned(), element.clone())).collect(),
+        };
+        context.insert("_index".to_owned(), index.into());
+        context.insert("_root".to_owned(), root.clone());
+        let document = template_engine.render_named(name, &context).map_err(|e| {
+            AppError::render(&format!("Failed to render the element {}", index + 1), e)
+        })?;
+
+        let label = match element.get("name") {
+            Some(JsonValue::String(name)) if !name.is_empty() => name.clone(),
+            _ => format!("#{}", index + 1),
+        };
+        rendered.unresolved.extend(document.unresolved);
+        rendered.documents.push((label, document.output));
+    }
+    rendered.output = rendered
+        .documents
+        .iter()
+        .map(|(_, output)| output.as_str())
+        .collect::<Vec<_>>()
+        .join(&render_each.separator);
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::MAIN_TEMPLATE;
+    use serde_json::{json, Value as JsonValue};
+
+    fn engine_for(scenario: JsonValue) -> HandlebarsEngine {
+        let scenario: Scenario = serde_json::from_value(scenario).unwrap();
+        HandlebarsEngine::with_scenario(&scenario).unwrap()
+    }
+
+    #[test]
+    fn transform_helper() {
+        let engine = engine_for(json!({
+            "template": "{{shout name}}",
+            "inputs": [],
+            "helpers": {
+                "shout": {
+                    "type": "transform",
+                    "transforms": ["trim", "uppercase", {"suffix": "!"}, {"default": "?"}]
+                }
+            }
+        }));
+        assert_eq!(
+            engine
+                .render_named(MAIN_TEMPLATE, &json!({"name": " bob "}))
+                .unwrap()
+                .output,
+            "BOB!"
+        );
+    }
+
+    #[test]
+    fn expression_helper() {
+        let engine = engine_for(json!({
+            "template": "{{join a b sep=\", \"}}",
+            "inputs": [],
+            "helpers": {
+                "join": { "type": "expression", "expression": "{{args.[0]}}{{sep}}{{args.[1]}}" }
+            }
+        }));
+        assert_eq!(
+            engine
+                .render_named(MAIN_TEMPLATE, &json!({"a": "x", "b": "y"}))
+                .unwrap()
+                .output,
+            "x, y"
+        );
+    }
+
+    #[test]
+    fn builtin_helpers() {
+        let engine = engine_for(json!({
+            "template": "{{uppercase a}} {{lowercase a}} {{snake_case a}} {{add 1 \"2\" 3}} \
+                         {{multiply 2 1.5}} {{format_date d \"%d/%m/%Y\"}} \
+                         {{format_date e \"%Y\" from=\"%d.%m.%Y\"}}",
+            "inputs": [],
+        }));
+        assert_eq!(
+            engine
+                .render_named(
+                    MAIN_TEMPLATE,
+                    &json!({"a": "Hello bigWorld", "d": "2020-05-17", "e": "01.02.1999"})
+                )
+                .unwrap()
+                .output,
+            "HELLO BIGWORLD hello bigworld hello_big_world 6 3 17/05/2020 1999"
+        );
+    }
+
+    #[test]
+    fn ref_helper() {
+        let mut engine = engine_for(json!({
+            "template": "{{#each servers}}{{name}}@{{ref \"domain\"}} {{/each}}{{ref \"servers.1.name\"}}{{ref \"nope\"}}",
+            "inputs": [],
+        }));
+        let data = json!({"domain": "example.org", "servers": [{"name": "a"}, {"name": "b"}]});
+        assert_eq!(
+            engine.render_named(MAIN_TEMPLATE, &data).unwrap().output,
+            "a@example.org b@example.org b"
+        );
+
+        engine.set_strict_mode(true);
+        assert!(engine.render_named(MAIN_TEMPLATE, &data).is_err());
+    }
+
+    #[test]
+    fn whitespace_control() {
+        let template = "list:\n  {{#each xs}}\n  - {{this}}\n  {{/each}}\n\n\n{{! end }}\nend";
+        let mut engine = engine_for(json!({
+            "template": template,
+            "inputs": [],
+            "whitespace": { "trim_standalone_tags": true, "collapse_blank_lines": true }
+        }));
+        let data = json!({"xs": [1, 2]});
+        assert_eq!(
+            engine.render_named(MAIN_TEMPLATE, &data).unwrap().output,
+            "list:\n  - 1\n  - 2\n\nend"
+        );
+
+        let scenario = serde_json::from_value(json!({"template": template, "inputs": []})).unwrap();
+        engine.set_template(&scenario).unwrap();
+        assert_eq!(
+            engine.render_named(MAIN_TEMPLATE, &data).unwrap().output,
+            "list:\n  \n  - 1\n  \n  - 2\n  \n\n\n\nend"
+        );
+    }
+
+    #[test]
+    fn read_keys() {
+        let engine = engine_for(json!({
+            "template": {
+                "main": "{{a.b}} {{uppercase c}} {{#each xs}}{{name}} {{@root.d}}{{/each}} \
+                         {{#with e}}{{f}}{{/with}} {{ref \"/g.h/i\"}} {{> p}}",
+                "parent": "{{#each xs}}{{../a}}{{/each}}",
+                "this": "{{lowercase this}}",
+                "computed_ref": "{{ref a}}",
+                "inline": "{{#*inline \"q\"}}{{a}}{{/inline}}{{> q}}",
+            },
+            "inputs": [],
+            "partials": { "p": "{{#if j}}{{k}}{{/if}}" }
+        }));
+        let keys = engine.read_keys("main").unwrap();
+        assert_eq!(
+            keys.iter().map(String::as_str).collect::<Vec<_>>(),
+            ["a", "c", "d", "e", "g.h", "j", "k", "xs"]
+        );
+        for name in &["parent", "this", "computed_ref", "inline", "unknown"] {
+            assert_eq!(engine.read_keys(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn unresolved_variables() {
+        let engine = engine_for(json!({
+            "template": "{{a}} {{b.c}} {{uppercase d}} {{#if e}}{{f}}{{/if}} \
+                         {{#each xs}}{{name}} {{@root.g}}{{/each}} {{> p}}",
+            "inputs": [],
+            "partials": { "p": "{{h}}" }
+        }));
+        let data = json!({"a": "x", "b": {"c": ""}, "f": null, "xs": [{}], "g": 1});
+        let unresolved: Vec<_> = engine
+            .render_named(MAIN_TEMPLATE, &data)
+            .unwrap()
+            .unresolved
+            .into_iter()
+            .collect();
+        assert_eq!(unresolved, ["b.c", "d", "e", "f", "h"]);
+    }
+
+    #[test]
+    fn builtin_helpers_can_be_disabled() {
+        let engine = engine_for(json!({
+            "template": "{{uppercase a}}",
+            "inputs": [],
+            "disable_builtin_helpers": true,
+        }));
+        assert!(engine
+            .render_named(MAIN_TEMPLATE, &json!({"a": "b"}))
+            .is_err());
+    }
+
+    #[test]
+    fn partials() {
+        let engine = engine_for(json!({
+            "template": "{{> header}}body",
+            "inputs": [],
+            "partials": { "header": ["# {{title}}", ""] },
+        }));
+        assert_eq!(
+            engine
+                .render_named(MAIN_TEMPLATE, &json!({"title": "T"}))
+                .unwrap()
+                .output,
+            "# T\nbody"
+        );
+    }
+
+    #[test]
+    fn tera_engine() {
+        let scenario: Scenario = serde_json::from_value(json!({
+            "template": "{% include \"greeting\" %}, {{ name | shout }}",
+            "inputs": [],
+            "engine": "tera",
+            "partials": { "greeting": "Hello" },
+            "helpers": { "shout": { "type": "transform", "transforms": ["uppercase"] } },
+        }))
+        .unwrap();
+        let mut engine = Engine::new_uninit();
+        engine.set_template(&scenario).unwrap();
+        assert_eq!(engine.kind(), EngineKind::Tera);
+        assert_eq!(
+            engine
+                .render_named(MAIN_TEMPLATE, &json!({"name": "bob"}))
+                .unwrap()
+                .output,
+            "Hello, BOB"
+        );
+    }
+
+    #[test]
+    fn strict_mode_after_tera() {
+        let scenario = |engine| {
+            serde_json::from_value::<Scenario>(json!({
+                "template": "[{{missing}}]",
+                "inputs": [],
+                "engine": engine,
+            }))
+            .unwrap()
+        };
+        let mut engine = Engine::new_uninit();
+        engine.set_strict_mode(false);
+        engine.set_template(&scenario("tera")).unwrap();
+        engine.set_strict_mode(false);
+        assert!(engine.always_strict());
+        assert!(engine.render_named(MAIN_TEMPLATE, &json!({})).is_err());
+        engine.set_template(&scenario("handlebars")).unwrap();
+        assert!(!engine.always_strict());
+        let rendered = engine.render_named(MAIN_TEMPLATE, &json!({})).unwrap();
+        assert_eq!(rendered.output, "[]");
+
+        engine.set_strict_mode(true);
+        engine.set_template(&scenario("tera")).unwrap();
+        engine.set_template(&scenario("handlebars")).unwrap();
+        assert!(engine.render_named(MAIN_TEMPLATE, &json!({})).is_err());
+    }
+
+    #[test]
+    fn template_errors_are_located() {
+        let scenario = |engine, template| {
+            serde_json::from_value::<Scenario>(json!({
+                "template": template,
+                "inputs": [],
+                "engine": engine,
+                "partials": { "p": "ok" },
+            }))
+            .unwrap()
+        };
+
+        let error = Engine::new_uninit()
+            .set_template(&scenario("handlebars", "line\n{{#if a}}"))
+            .unwrap_err();
+        assert_eq!((error.template, error.line), (None, Some(2)));
+
+        let error = Engine::new_uninit()
+            .set_template(&scenario("tera", "line\nline\n {{ a ! }}"))
+            .unwrap_err();
+        assert_eq!((error.template, error.line), (None, Some(3)));
+    }
+
+    #[test]
+    fn named_templates() {
+        let engine = engine_for(json!({
+            "template": { "b.conf": "b={{b}}", "a.conf": ["a={{a}}", "{{> p}}"] },
+            "inputs": [],
+            "partials": { "p": "partial" },
+        }));
+        assert_eq!(engine.outputs(), &["a.conf", "b.conf"]);
+        let data = json!({"a": 1, "b": 2});
+        assert_eq!(
+            engine.render_named("a.conf", &data).unwrap().output,
+            "a=1\npartial"
+        );
+        assert_eq!(engine.render_named("b.conf", &data).unwrap().output, "b=2");
+    }
+
+    #[test]
+    fn strict_mode() {
+        let mut engine = engine_for(json!({"template": "{{a.b}}", "inputs": []}));
+        assert_eq!(
+            engine
+                .render_named(MAIN_TEMPLATE, &json!({}))
+                .unwrap()
+                .output,
+            ""
+        );
+        engine.set_strict_mode(true);
+        let error = engine.render_named(MAIN_TEMPLATE, &json!({})).unwrap_err();
+        assert!(format!("{:#}", error).contains("a.b"));
+    }
+
+    #[test]
+    fn helpers_dont_leak_between_scenarios() {
+        let mut engine = engine_for(json!({
+            "template": "",
+            "inputs": [],
+            "helpers": { "h": { "type": "transform", "transforms": [] } }
+        }));
+        let scenario =
+            serde_json::from_value(json!({"template": "{{h x}}", "inputs": []})).unwrap();
+        engine.set_template(&scenario).unwrap();
+        assert!(engine.render_named(MAIN_TEMPLATE, &json!({})).is_err());
+    }
+}
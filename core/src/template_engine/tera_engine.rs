@@ -0,0 +1,108 @@
+use super::{
+    helpers::Transform,
+    whitespace::{Syntax, Whitespace},
+    HelperDefinition, Rendered, TemplateEngine, TemplateError,
+};
+use crate::{scenario::Scenario, AppError};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tera::Tera;
+
+pub struct TeraEngine {
+    inner: Tera,
+    /// Names of the templates to render, the others being partials.
+    outputs: Vec<String>,
+    whitespace: Whitespace,
+}
+
+impl TeraEngine {
+    pub fn new_uninit() -> Self {
+        Self {
+            inner: Tera::default(),
+            outputs: vec![],
+            whitespace: Whitespace::default(),
+        }
+    }
+}
+
+impl TemplateEngine for TeraEngine {
+    /// Partials are added as named templates, usable with
+    /// `{% include "name" %}`, and transform helpers are registered as
+    /// filters, eg. `{{ name | shout }}`.
+    fn set_template(&mut self, scenario: &Scenario) -> Result<(), TemplateError> {
+        let mut tera = Tera::default();
+        for (name, helper) in &scenario.helpers {
+            match helper {
+                HelperDefinition::Transform { transforms } => {
+                    tera.register_filter(name, TransformFilter(transforms.clone()))
+                }
+                HelperDefinition::Expression { .. } => {
+                    return Err(TemplateError::new(format!(
+                        "Helper '{}': expression helpers are only supported by the Handlebars \
+                         engine",
+                        name
+                    )))
+                }
+            }
+        }
+
+        let whitespace = scenario.whitespace;
+        let templates = scenario
+            .partials
+            .iter()
+            .map(|(name, partial)| (name.as_str(), partial.source()))
+            .chain(scenario.template.sources())
+            .map(|(name, source)| (name, whitespace.prepare(source, Syntax::Tera)));
+        tera.add_raw_templates(templates)?;
+        self.inner = tera;
+        self.whitespace = whitespace;
+        self.outputs = scenario
+            .template
+            .names()
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect();
+        Ok(())
+    }
+
+    /// Tera always fails on undefined variables, so there is nothing to do.
+    fn set_strict_mode(&mut self, _: bool) {}
+
+    fn always_strict(&self) -> bool {
+        true
+    }
+
+    fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+
+    /// Tera fails on undefined variables, so none are ever unresolved.
+    fn render_named<T: Serialize>(&self, name: &str, data: &T) -> Result<Rendered, AppError> {
+        let context = tera::Context::from_serialize(data).map_err(|e| {
+            AppError::render("Tera template engine only accepts objects as data", e)
+        })?;
+        self.inner
+            .render(name, &context)
+            .map(|output| Rendered {
+                output: self.whitespace.finish(output),
+                ..Rendered::default()
+            })
+            .map_err(|e| AppError::render("Tera template engine failed to render data", e))
+    }
+}
+
+struct TransformFilter(Vec<Transform>);
+
+impl tera::Filter for TransformFilter {
+    fn filter(&self, value: &JsonValue, _: &HashMap<String, JsonValue>) -> tera::Result<JsonValue> {
+        let input = match value {
+            JsonValue::String(s) => s.clone(),
+            JsonValue::Null => String::new(),
+            value => value.to_string(),
+        };
+        Ok(JsonValue::String(
+            self.0.iter().fold(input, |s, t| t.apply(s)),
+        ))
+    }
+}
@@ -0,0 +1,255 @@
+use crate::json_path::Path;
+use handlebars::{
+    template::{Parameter, TemplateElement},
+    Handlebars, Template,
+};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeSet;
+
+/// Block helpers whose content is rendered with another context, in which
+/// only the `@root.` variables can be checked.
+const CONTEXT_CHANGING_HELPERS: &[&str] = &["each", "with"];
+
+/// Lists the variables used by a compiled Handlebars template (and the
+/// partials it includes) that are missing, `null` or empty in `data`.
+///
+/// The variables used inside an `#each` or a `#with` block are relative to
+/// its context, so only the ones starting with `@root.` are reported.
+pub(super) fn unresolved_variables(
+    registry: &Handlebars,
+    name: &str,
+    data: &JsonValue,
+) -> BTreeSet<String> {
+    find_variables(registry, name)
+        .into_iter()
+        .filter(|(_, path)| is_nothing(Path::from(path.as_str()).lookup(data)))
+        .map(|(raw, _)| raw.to_owned())
+        .collect()
+}
+
+/// Lists the paths in the root data of the variables used by a compiled
+/// Handlebars template, with the same limitations as `unresolved_variables`.
+pub(super) fn referenced_variables(registry: &Handlebars, name: &str) -> BTreeSet<String> {
+    find_variables(registry, name)
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// Lists the top-level keys of the root data that a compiled Handlebars
+/// template may read, so that changing the others can't change its output.
+///
+/// Returns `None` when it may read any of them, eg. with `{{this}}`, a `../`
+/// path, a partial which isn't registered, or `ref` with a computed path.
+pub(super) fn read_keys(registry: &Handlebars, name: &str) -> Option<BTreeSet<String>> {
+    let finder = run_finder(registry, name);
+    if finder.whole_data {
+        return None;
+    }
+    let keys = finder
+        .variables
+        .into_iter()
+        .filter_map(|(_, path)| path.split('.').next().map(ToOwned::to_owned))
+        .chain(finder.ref_keys)
+        .collect();
+    Some(keys)
+}
+
+/// Returns the variables as they are written, along with their path in the
+/// root data.
+fn find_variables<'a>(registry: &'a Handlebars, name: &str) -> BTreeSet<(&'a str, String)> {
+    run_finder(registry, name).variables
+}
+
+fn run_finder<'a>(registry: &'a Handlebars, name: &str) -> Finder<'a> {
+    let mut finder = Finder {
+        registry,
+        visited_partials: BTreeSet::new(),
+        variables: BTreeSet::new(),
+        ref_keys: BTreeSet::new(),
+        whole_data: false,
+    };
+    match registry.get_template(name) {
+        Some(template) => finder.template(template, 0),
+        None => finder.whole_data = true,
+    }
+    finder
+}
+
+struct Finder<'a> {
+    registry: &'a Handlebars<'a>,
+    visited_partials: BTreeSet<&'a str>,
+    variables: BTreeSet<(&'a str, String)>,
+    /// Top-level keys of the paths looked up with the `ref` helper.
+    ref_keys: BTreeSet<String>,
+    /// Whether the template may read data which isn't in `variables` and
+    /// `ref_keys`, see `read_keys`.
+    whole_data: bool,
+}
+
+impl<'a> Finder<'a> {
+    fn template(&mut self, template: &'a Template, depth: usize) {
+        for element in &template.elements {
+            self.element(element, depth);
+        }
+    }
+
+    fn element(&mut self, element: &'a TemplateElement, depth: usize) {
+        match element {
+            TemplateElement::HTMLExpression(param) => self.param(param, depth),
+            TemplateElement::Expression(expression) => {
+                let is_helper = !expression.params.is_empty()
+                    || !expression.hash.is_empty()
+                    || expression
+                        .name
+                        .as_name()
+                        .and_then(|name| self.registry.get_helper(name))
+                        .is_some();
+                if !is_helper {
+                    self.param(&expression.name, depth);
+                }
+                self.ref_param(&expression.name, &expression.params);
+                self.params(&expression.params, expression.hash.values(), depth);
+            }
+            TemplateElement::HelperBlock(block) => {
+                self.ref_param(&block.name, &block.params);
+                self.params(&block.params, block.hash.values(), depth);
+                let inner_depth = match block.name.as_name() {
+                    Some(name) if CONTEXT_CHANGING_HELPERS.contains(&name) => depth + 1,
+                    _ => depth,
+                };
+                if let Some(template) = &block.template {
+                    self.template(template, inner_depth);
+                }
+                if let Some(inverse) = &block.inverse {
+                    self.template(inverse, depth);
+                }
+            }
+            TemplateElement::PartialExpression(partial)
+            | TemplateElement::PartialBlock(partial) => {
+                self.params(&partial.params, partial.hash.values(), depth);
+                // The context of the partial can be changed by its parameter
+                let depth = if partial.params.is_empty() {
+                    depth
+                } else {
+                    depth + 1
+                };
+                match partial.name.as_name() {
+                    Some(name) if !self.visited_partials.insert(name) => {}
+                    Some(name) => match self.registry.get_template(name) {
+                        Some(template) => self.template(template, depth),
+                        // eg. an inline partial, whose content isn't known here
+                        None => self.whole_data = true,
+                    },
+                    None => self.whole_data = true,
+                }
+                if let Some(template) = &partial.template {
+                    self.template(template, depth);
+                }
+            }
+            TemplateElement::RawString(_)
+            | TemplateElement::Comment(_)
+            | TemplateElement::DecoratorExpression(_)
+            | TemplateElement::DecoratorBlock(_) => {}
+        }
+    }
+
+    fn params(
+        &mut self,
+        params: &'a [Parameter],
+        hash: impl Iterator<Item = &'a Parameter>,
+        depth: usize,
+    ) {
+        for param in params.iter().chain(hash) {
+            self.param(param, depth);
+        }
+    }
+
+    /// Notes what the `ref` helper reads, if `name` is this helper.
+    fn ref_param(&mut self, name: &Parameter, params: &[Parameter]) {
+        if name.as_name() != Some("ref") || self.registry.get_helper("ref").is_none() {
+            return;
+        }
+        match ref_key(params) {
+            Some(key) => {
+                self.ref_keys.insert(key);
+            }
+            None => self.whole_data = true,
+        }
+    }
+
+    fn param(&mut self, param: &'a Parameter, depth: usize) {
+        match param {
+            Parameter::Path(_) => {
+                let raw = param.as_name().unwrap_or_default();
+                match root_path(raw, depth) {
+                    Some(path) => {
+                        self.variables.insert((raw, path));
+                    }
+                    None if reads_whole_data(raw, depth) => self.whole_data = true,
+                    None => {}
+                }
+            }
+            Parameter::Subexpression(subexpression) => {
+                if let (Some(params), Some(hash)) = (subexpression.params(), subexpression.hash()) {
+                    if let TemplateElement::Expression(expression) = &*subexpression.element {
+                        self.ref_param(&expression.name, params);
+                    }
+                    self.params(params, hash.values(), depth);
+                }
+            }
+            Parameter::Name(_) | Parameter::Literal(_) => {}
+        }
+    }
+}
+
+/// Returns the top-level key of the path looked up by the `ref` helper, when
+/// it is written in the template.
+fn ref_key(params: &[Parameter]) -> Option<String> {
+    match params.first() {
+        Some(Parameter::Literal(JsonValue::String(path))) => {
+            let path: Path = path.parse().ok()?;
+            let key = path.get_segments().next()?.to_owned();
+            Some(key)
+        }
+        _ => None,
+    }
+}
+
+/// Whether a variable which isn't a path in the root data may still read
+/// it, eg. `this` outside of the blocks, or `../` from inside one.
+fn reads_whole_data(raw: &str, depth: usize) -> bool {
+    raw.starts_with("@root") || raw.contains("..") || (depth == 0 && !raw.starts_with('@'))
+}
+
+/// Converts a Handlebars path to a path in the root data, if it is one.
+fn root_path(raw: &str, depth: usize) -> Option<String> {
+    let path = match raw.strip_prefix("@root.") {
+        Some(path) => path,
+        None if depth > 0 => return None,
+        None => raw
+            .strip_prefix("this.")
+            .or_else(|| raw.strip_prefix("./"))
+            .unwrap_or(raw),
+    };
+
+    let is_simple = path.split(&['.', '/'][..]).all(|segment| {
+        !segment.is_empty()
+            && segment != "this"
+            && !segment.starts_with('@')
+            && !segment.contains('[')
+    });
+    if is_simple {
+        Some(path.replace('/', "."))
+    } else {
+        None
+    }
+}
+
+fn is_nothing(value: Option<&JsonValue>) -> bool {
+    match value {
+        None | Some(JsonValue::Null) => true,
+        Some(JsonValue::String(s)) => s.is_empty(),
+        Some(_) => false,
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// How the whitespace of a template and its output is handled, so that
+/// generated YAML and configuration files don't end up with stray blank
+/// lines.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default
+)]
+#[serde(default)]
+pub struct Whitespace {
+    /// Removes the lines which only hold a block tag, eg. `{{#each xs}}`,
+    /// `{{else}}` or a comment, like Mustache's standalone tags.
+    pub trim_standalone_tags: bool,
+    /// Collapses consecutive blank lines of the output into a single one.
+    pub collapse_blank_lines: bool,
+}
+
+/// The tag syntax of a template engine.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Syntax {
+    Handlebars,
+    Tera,
+}
+
+impl Whitespace {
+    /// Prepares a template source before it is compiled.
+    pub(super) fn prepare<'a>(&self, source: Cow<'a, str>, syntax: Syntax) -> Cow<'a, str> {
+        if self.trim_standalone_tags {
+            Cow::Owned(trim_standalone_tags(&source, syntax))
+        } else {
+            source
+        }
+    }
+
+    /// Post-processes a rendered template.
+    pub(super) fn finish(&self, output: String) -> String {
+        if self.collapse_blank_lines {
+            collapse_blank_lines(&output)
+        } else {
+            output
+        }
+    }
+}
+
+impl Syntax {
+    /// If `tag` is a single block tag, returns the position of its closing
+    /// delimiter, including the whitespace control character if any.
+    fn block_tag_end(self, tag: &str) -> Option<usize> {
+        match self {
+            Syntax::Handlebars => {
+                let inner = tag.strip_prefix("{{")?.strip_suffix("}}")?;
+                if inner.starts_with('{') || inner.contains("{{") || inner.contains("}}") {
+                    return None;
+                }
+
+                let start = inner.trim_start_matches('~').trim_start();
+                let is_else = matches!(
+                    start.strip_prefix("else"),
+                    Some(rest) if rest.is_empty() || rest.starts_with(&[' ', '~'][..])
+                );
+                if !is_else && !start.starts_with(&['#', '/', '^', '!'][..]) {
+                    return None;
+                }
+
+                let mut end = tag.len() - "}}".len();
+                if inner.ends_with('~') {
+                    end -= 1;
+                } else if start.starts_with("!--") && inner.ends_with("--") {
+                    end -= 2;
+                }
+                Some(end)
+            }
+            Syntax::Tera => {
+                let (open, close) = match tag.get(..2)? {
+                    "{%" => ("{%", "%}"),
+                    "{#" => ("{#", "#}"),
+                    _ => return None,
+                };
+                let inner = tag.strip_prefix(open)?.strip_suffix(close)?;
+                if inner.contains(open) || inner.contains(close) || inner.contains("{{") {
+                    return None;
+                }
+
+                let end = tag.len() - close.len();
+                Some(if inner.ends_with('-') { end - 1 } else { end })
+            }
+        }
+    }
+}
+
+/// Rewrites the lines which only hold a block tag so that they don't leave
+/// an empty line in the output. Instead of being removed, the indentation
+/// and the line break are moved inside the tag, which keeps the line numbers
+/// of compile errors right.
+fn trim_standalone_tags(source: &str, syntax: Syntax) -> String {
+    let mut result = String::with_capacity(source.len());
+    for line in source.split_inclusive('\n') {
+        let content = line.trim_end_matches(&['\n', '\r'][..]);
+        let line_break = &line[content.len()..];
+        let tag = content.trim();
+
+        match syntax.block_tag_end(tag) {
+            Some(end) if !line_break.is_empty() => {
+                let indent = &content[..content.len() - content.trim_start().len()];
+                result.push_str(&tag[..end]);
+                result.push_str(indent);
+                result.push_str(line_break);
+                result.push_str(&tag[end..]);
+            }
+            _ => result.push_str(line),
+        }
+    }
+    result
+}
+
+fn collapse_blank_lines(output: &str) -> String {
+    let mut result = String::with_capacity(output.len());
+    let mut previous_is_blank = false;
+    for line in output.split_inclusive('\n') {
+        let is_blank = line.trim().is_empty();
+        if !(is_blank && previous_is_blank) {
+            result.push_str(line);
+        }
+        previous_is_blank = is_blank;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standalone_handlebars_tags() {
+        let source = "a:\n  {{#each xs~}}\n  - {{this}}\n  {{else}}\n  {{!-- none --}}\n  \
+                      {{/each}}\nb: {{#if c}}d{{/if}}\n";
+        assert_eq!(
+            trim_standalone_tags(source, Syntax::Handlebars),
+            "a:\n{{#each xs  \n~}}  - {{this}}\n{{else  \n}}{{!-- none   \n--}}{{/each  \n}}b: \
+             {{#if c}}d{{/if}}\n"
+        );
+    }
+
+    #[test]
+    fn standalone_tera_tags() {
+        let source = "{% for x in xs -%}\r\n  {# comment #}\n- {{ x }}\n{% endfor %}";
+        assert_eq!(
+            trim_standalone_tags(source, Syntax::Tera),
+            "{% for x in xs \r\n-%}{# comment   \n#}- {{ x }}\n{% endfor %}"
+        );
+    }
+
+    #[test]
+    fn blank_lines_are_collapsed() {
+        assert_eq!(collapse_blank_lines("a\n\n  \n\nb\n\n"), "a\n\nb\n\n");
+    }
+}
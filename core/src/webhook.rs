@@ -0,0 +1,79 @@
+//! Sends the rendered template to a URL, eg. to push it into a CI or a
+//! configuration service.
+
+use crate::prelude::*;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Where the rendered template is posted, configured by the scenario or in
+/// the settings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct Webhook {
+    pub url: String,
+    /// Sent along with the output, eg. `Authorization`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Posts `{"output": ..., "data": ...}` as JSON, instead of the output as
+    /// text.
+    pub include_data: bool,
+}
+
+impl Webhook {
+    pub fn is_configured(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+
+    /// Returns the content type and the body of the request.
+    pub fn body(&self, output: &str, data: &impl Serialize) -> Result<(&'static str, String)> {
+        if self.include_data {
+            let body = json!({ "output": output, "data": data });
+            Ok(("application/json", serde_json::to_string(&body)?))
+        } else {
+            Ok(("text/plain; charset=utf-8", output.to_owned()))
+        }
+    }
+
+    /// Formats the headers as `Name: value` lines, see `parse_headers`.
+    pub fn headers_text(&self) -> String {
+        self.headers
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Parses `Name: value` lines, skipping the ones without a name.
+    pub fn parse_headers(text: &str) -> BTreeMap<String, String> {
+        text.lines()
+            .filter_map(|line| {
+                let colon = line.find(':')?;
+                let name = line[..colon].trim();
+                let value = line[colon + 1..].trim();
+                Some((name.to_owned(), value.to_owned())).filter(|_| !name.is_empty())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_headers() {
+        let headers = Webhook::parse_headers("Authorization: Bearer a:b\n\n: x\nX-Env:ci");
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["Authorization"], "Bearer a:b");
+        assert_eq!(headers["X-Env"], "ci");
+
+        let webhook = Webhook {
+            headers,
+            ..Webhook::default()
+        };
+        assert_eq!(
+            webhook.headers_text(),
+            "Authorization: Bearer a:b\nX-Env: ci"
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use super::{Change, NotificationBus, NotificationSender, SharedState, StateStore, StoreOutput};
+use crate::{prelude::*, state::Session, InputsData};
+use std::time::Duration;
+use yew::{
+    agent::{Bridge, Bridged, Dispatched, Dispatcher},
+    format::Text,
+    services::{
+        fetch::{FetchService, FetchTask, Request, Response},
+        timeout::{TimeoutService, TimeoutTask},
+    },
+    worker::{Agent, AgentLink, Context, HandlerId},
+};
+
+/// Delay without edits before posting them, so that typing doesn't post
+/// every character.
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Posts the changes of the inputs data of the active tab to a URL, see
+/// `Settings::changes_webhook_url`, for another system to follow them.
+///
+/// The changes are posted as a JSON Patch, eg.
+/// `[{"op": "replace", "path": "/name", "value": "web"}]`, from the data
+/// posted last. The first post of a tab replaces the whole data, with the
+/// path `""`. The changes which couldn't be posted are posted along with the
+/// next ones.
+pub struct ChangesWebhook {
+    link: AgentLink<Self>,
+    _store: Box<dyn Bridge<StateStore>>,
+    notification_bus: Dispatcher<NotificationBus>,
+    fetch_service: FetchService,
+    timeout: TimeoutService,
+    debounce_task: Option<TimeoutTask>,
+    /// The post in progress, with the inputs data it brings the URL to.
+    post_task: Option<(FetchTask, InputsData)>,
+    url: String,
+    /// The last state sent by the `StateStore`.
+    state: SharedState,
+    /// The inputs data as the URL knows it, which the patches are computed
+    /// from. `None` when no URL is set or the tab is empty.
+    posted: Option<InputsData>,
+    /// Whether the last post failed, to only notify the first failure.
+    failing: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ChangesWebhookRequest {
+    /// Posts the changes to this URL, from the current inputs data, or stops
+    /// posting them if it is empty.
+    SetUrl(String),
+}
+
+pub enum ChangesWebhookMsg {
+    Store(StoreOutput),
+    Debounced,
+    Posted(Result<(), String>),
+}
+
+impl ChangesWebhook {
+    fn active_inputs_data(&self) -> Option<InputsData> {
+        match self.state.get().active() {
+            Session::Loaded { inputs_data, .. } => Some(inputs_data.clone()),
+            Session::Init => None,
+        }
+    }
+
+    /// Posts the whole inputs data of the active tab, which the next
+    /// changes are relative to.
+    fn reset(&mut self) {
+        self.post_task = None;
+        self.posted = match self.active_inputs_data() {
+            Some(_) if !self.url.is_empty() => Some(InputsData::from(JsonValue::Null)),
+            _ => None,
+        };
+        self.schedule();
+    }
+
+    fn schedule(&mut self) {
+        if self.posted.is_none() {
+            return;
+        }
+        let callback = self.link.callback(|_| ChangesWebhookMsg::Debounced);
+        self.debounce_task = Some(self.timeout.spawn(DEBOUNCE, callback));
+    }
+
+    fn post(&mut self) {
+        // The changes made meanwhile are posted once it is done
+        if self.post_task.is_some() {
+            return;
+        }
+        let (posted, current) = match (&self.posted, self.active_inputs_data()) {
+            (Some(posted), Some(current)) => (posted, current),
+            _ => return,
+        };
+        let patch = posted.patch_to(&current);
+        if patch.is_empty() {
+            return;
+        }
+
+        let callback = self.link.callback(|response: Response<Text>| {
+            let (meta, body) = response.into_parts();
+            ChangesWebhookMsg::Posted(match body {
+                Ok(_) if meta.status.is_success() => Ok(()),
+                Ok(_) => Err(tr!("the server responded {}.", meta.status)),
+                Err(_) => Err(tr(
+                    "the server is unreachable or doesn't allow cross-origin requests (CORS)."
+                )
+                .to_owned()),
+            })
+        });
+        let task = serde_json::to_string(&patch)
+            .map_err(|e| e.to_string())
+            .and_then(|body| {
+                Request::post(&self.url)
+                    .header("Content-Type", "application/json-patch+json")
+                    .body(Ok(body))
+                    .map_err(|e| e.to_string())
+            })
+            .and_then(|request| {
+                self.fetch_service
+                    .fetch(request, callback)
+                    .map_err(ToString::to_string)
+            });
+        match task {
+            Ok(task) => self.post_task = Some((task, current)),
+            Err(e) => self.on_posted(Err(e)),
+        }
+    }
+
+    fn on_posted(&mut self, result: Result<(), String>) {
+        let posting = self.post_task.take();
+        match result {
+            Ok(()) => {
+                self.failing = false;
+                if let Some((_, data)) = posting {
+                    self.posted = Some(data);
+                }
+                self.post();
+            }
+            Err(e) => {
+                if !self.failing {
+                    self.notif_error(tr!("Failed to post the changes to {}: {}", self.url, e));
+                }
+                self.failing = true;
+            }
+        }
+    }
+}
+
+impl NotificationSender for ChangesWebhook {
+    const SOURCE: &'static str = "ChangesWebhook";
+
+    fn notification_bus(&mut self) -> &mut Dispatcher<NotificationBus> {
+        &mut self.notification_bus
+    }
+}
+
+impl Agent for ChangesWebhook {
+    type Reach = Context;
+    type Message = ChangesWebhookMsg;
+    type Input = ChangesWebhookRequest;
+    type Output = ();
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Self {
+            _store: StateStore::bridge(link.callback(ChangesWebhookMsg::Store)),
+            link,
+            notification_bus: NotificationBus::dispatcher(),
+            fetch_service: FetchService::new(),
+            timeout: TimeoutService::new(),
+            debounce_task: None,
+            post_task: None,
+            url: String::new(),
+            state: SharedState::default(),
+            posted: None,
+            failing: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            ChangesWebhookMsg::Store(StoreOutput::Changed(state, change)) => {
+                self.state = state;
+                match change {
+                    Change::Inputs { .. } | Change::Removed(..) => self.schedule(),
+                    // The changes are relative to the data of the active tab
+                    Change::ActiveSession | Change::Replaced => self.reset(),
+                    _ => {}
+                }
+            }
+            ChangesWebhookMsg::Store(_) => {}
+            ChangesWebhookMsg::Debounced => {
+                self.debounce_task = None;
+                self.post();
+            }
+            ChangesWebhookMsg::Posted(result) => self.on_posted(result),
+        }
+    }
+
+    fn handle_input(&mut self, request: Self::Input, _: HandlerId) {
+        match request {
+            ChangesWebhookRequest::SetUrl(url) => {
+                self.url = url.trim().to_owned();
+                self.failing = false;
+                self.reset();
+            }
+        }
+    }
+}
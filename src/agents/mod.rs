@@ -1,3 +1,13 @@
+mod changes_webhook;
 mod notification_bus;
+mod options_fetcher;
+mod state_store;
+mod sync;
 
+pub use changes_webhook::{ChangesWebhook, ChangesWebhookRequest};
 pub use notification_bus::*;
+pub use options_fetcher::{FetchedOptions, OptionsFetcher, OptionsRequest};
+pub use state_store::{
+    Change, RestoreError, SaveError, SharedState, StateStore, StoreOutput, StoreRequest,
+};
+pub use sync::{SyncAgent, SyncOutput, SyncRequest};
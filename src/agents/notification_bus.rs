@@ -8,58 +8,106 @@ pub struct NotificationBus {
 }
 
 pub trait NotificationSender {
+    /// Shown in the details of the notifications, eg. `App`.
+    const SOURCE: &'static str;
+
     fn notification_bus(&mut self) -> &mut Dispatcher<NotificationBus>;
 
+    fn notify(&mut self, notification: Notification) {
+        self.notification_bus()
+            .send(NotificationRequest::New(notification));
+    }
+
     fn notif_success<T: ToString>(&mut self, text: T) {
         let s = text.to_string();
         debug!("Success: {:?}", &s);
-        self.notification_bus()
-            .send(NotificationRequest::New(Notification {
-                text: s,
-                level: NotificationLevel::Success,
-            }));
+        let notification = Notification::new(s, NotificationLevel::Success, Self::SOURCE);
+        self.notify(notification);
     }
 
     fn notif_info<T: ToString>(&mut self, text: T) {
         let s = text.to_string();
         info!("Info: {:?}", &s);
-        self.notification_bus()
-            .send(NotificationRequest::New(Notification {
-                text: s,
-                level: NotificationLevel::Info,
-            }));
+        let notification = Notification::new(s, NotificationLevel::Info, Self::SOURCE);
+        self.notify(notification);
     }
 
     fn notif_warn<T: ToString>(&mut self, text: T) {
         let s = text.to_string();
         warn!("Warning: {:?}", &s);
-        self.notification_bus()
-            .send(NotificationRequest::New(Notification {
-                text: s,
-                level: NotificationLevel::Warning,
-            }));
+        let notification = Notification::new(s, NotificationLevel::Warning, Self::SOURCE);
+        self.notify(notification);
     }
 
     fn notif_error<T: ToString>(&mut self, text: T) {
         let s = text.to_string();
         error!("Error: {:?}", &s);
-        self.notification_bus()
-            .send(NotificationRequest::New(Notification {
-                text: s,
-                level: NotificationLevel::Error,
-            }));
+        let notification = Notification::new(s, NotificationLevel::Error, Self::SOURCE);
+        self.notify(notification);
+    }
+
+    /// Notifies an error with its context as title, and its causes as details.
+    fn notif_error_chain(&mut self, error: anyhow::Error) {
+        error!("Error: {:?}", error);
+        let notification = Notification::from_error(&error, Self::SOURCE);
+        self.notify(notification);
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum NotificationRequest {
     New(Notification),
+    /// An action of a notification was clicked, to be handled by its sender.
+    Clicked(NotificationAction),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Notification {
-    pub text: String,
     pub level: NotificationLevel,
+    pub title: String,
+    /// Shown when the notification is expanded, eg. the causes of an error.
+    pub details: Vec<String>,
+    /// The component which sent the notification.
+    pub source: String,
+    /// Buttons of the notification, eg. to undo what was notified.
+    pub actions: Vec<NotificationAction>,
+    /// Delay before the notification is dismissed, unless hovered. It stays
+    /// until closed when `None`.
+    pub timeout_ms: Option<u64>,
+}
+
+impl Notification {
+    /// A notification dismissed after the default timeout of its level.
+    pub fn new(title: String, level: NotificationLevel, source: &str) -> Self {
+        Self {
+            title,
+            details: vec![],
+            source: source.to_owned(),
+            actions: vec![],
+            timeout_ms: level.default_timeout_ms(),
+            level,
+        }
+    }
+
+    pub fn with_action(mut self, action: NotificationAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    pub fn from_error(error: &anyhow::Error, source: &str) -> Self {
+        Self {
+            details: error.chain().skip(1).map(ToString::to_string).collect(),
+            ..Self::new(error.to_string(), NotificationLevel::Error, source)
+        }
+    }
+}
+
+/// A button of a notification. Its sender is told when it is clicked, and
+/// recognizes it by its `id`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct NotificationAction {
+    pub label: String,
+    pub id: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -70,6 +118,18 @@ pub enum NotificationLevel {
     Error,
 }
 
+impl NotificationLevel {
+    /// The errors stay until closed, so that they aren't missed.
+    pub fn default_timeout_ms(&self) -> Option<u64> {
+        match self {
+            NotificationLevel::Success => Some(4_000),
+            NotificationLevel::Info => Some(6_000),
+            NotificationLevel::Warning => Some(10_000),
+            NotificationLevel::Error => None,
+        }
+    }
+}
+
 impl Agent for NotificationBus {
     type Reach = Context;
     type Message = ();
@@ -106,3 +166,21 @@ impl Agent for NotificationBus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    #[test]
+    fn notification_from_error() {
+        let error = Err::<(), _>(anyhow!("Expected a JSON object."))
+            .context("Failed to paste the JSON.")
+            .unwrap_err();
+        let notification = Notification::from_error(&error, "App");
+        assert_eq!(notification.title, "Failed to paste the JSON.");
+        assert_eq!(notification.details, ["Expected a JSON object."]);
+        assert_eq!(notification.source, "App");
+        assert_eq!(notification.timeout_ms, None);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::inputs::SelectOption;
+use log::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use yew::{
+    format::{Json, Nothing},
+    services::fetch::{FetchService, FetchTask, Request, Response},
+    worker::*,
+};
+
+/// Fetches the options of the remote select inputs, only once per URL.
+pub struct OptionsFetcher {
+    link: AgentLink<Self>,
+    fetch_service: FetchService,
+    /// Options already fetched, by URL.
+    cache: HashMap<String, Vec<SelectOption>>,
+    /// Requests in progress, by URL, with who is waiting for them.
+    pending: HashMap<String, (FetchTask, Vec<HandlerId>)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OptionsRequest {
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FetchedOptions {
+    pub url: String,
+    pub options: Result<Vec<SelectOption>, String>,
+}
+
+pub enum Msg {
+    Fetched(String, Result<Vec<SelectOption>, String>),
+}
+
+impl Agent for OptionsFetcher {
+    type Reach = Context;
+    type Message = Msg;
+    type Input = OptionsRequest;
+    type Output = FetchedOptions;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Self {
+            link,
+            fetch_service: FetchService::new(),
+            cache: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::Fetched(url, options) => {
+                let waiting = self
+                    .pending
+                    .remove(&url)
+                    .map(|(_, waiting)| waiting)
+                    .unwrap_or_default();
+                for who in waiting {
+                    self.link.respond(
+                        who,
+                        FetchedOptions {
+                            url: url.clone(),
+                            options: options.clone(),
+                        },
+                    );
+                }
+
+                // Errors aren't cached, so that they can be retried
+                if let Ok(options) = options {
+                    self.cache.insert(url, options);
+                }
+            }
+        }
+    }
+
+    fn handle_input(&mut self, request: Self::Input, who: HandlerId) {
+        let url = request.url;
+        if let Some(options) = self.cache.get(&url) {
+            let options = Ok(options.clone());
+            self.link.respond(who, FetchedOptions { url, options });
+            return;
+        }
+        if let Some((_, waiting)) = self.pending.get_mut(&url) {
+            waiting.push(who);
+            return;
+        }
+
+        trace!("Fetching options from: {}", url);
+        let url_inner = url.clone();
+        let callback = self.link.callback(
+            move |response: Response<Json<anyhow::Result<Vec<JsonValue>>>>| {
+                let (meta, Json(body)) = response.into_parts();
+                let options = if meta.status.is_success() {
+                    body.map(parse_options).map_err(|e| e.to_string())
+                } else {
+                    Err(tr!("The server responded {}", meta.status))
+                };
+                Msg::Fetched(url_inner.clone(), options)
+            },
+        );
+        let task = Request::get(&url)
+            .body(Nothing)
+            .map_err(|e| e.to_string())
+            .and_then(|request| {
+                self.fetch_service
+                    .fetch(request, callback)
+                    .map_err(ToString::to_string)
+            });
+        match task {
+            Ok(task) => {
+                self.pending.insert(url, (task, vec![who]));
+            }
+            Err(e) => self.link.respond(
+                who,
+                FetchedOptions {
+                    url,
+                    options: Err(e),
+                },
+            ),
+        }
+    }
+}
+
+/// Options are either strings or `{"value": .., "label": ..}` objects.
+fn parse_options(values: Vec<JsonValue>) -> Vec<SelectOption> {
+    values
+        .into_iter()
+        .filter_map(|value| match value {
+            JsonValue::String(value) => Some(SelectOption {
+                value,
+                label: None,
+                for_values: vec![],
+            }),
+            value => serde_json::from_value(value).ok(),
+        })
+        .collect()
+}
@@ -0,0 +1,795 @@
+use super::{NotificationBus, NotificationSender};
+use crate::{
+    clipboard,
+    prelude::*,
+    scenario::{NewerScenarioError, Scenario},
+    session_store::{DecryptionError, LockedError, NewerSessionError, SessionStore, UnlockError},
+    state::{migrate_state, InvalidInput, Session, State},
+    AppError, InputsData, PatchOp, Path,
+};
+use anyhow::Context as _;
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    collections::HashMap,
+    hash::Hash,
+    rc::Rc,
+    time::Duration,
+};
+use yew::{
+    agent::{Dispatched, Dispatcher},
+    services::timeout::{TimeoutService, TimeoutTask},
+    worker::{Agent, AgentLink, Context, HandlerId},
+};
+
+/// Keeps the sessions opened in tabs, applies the edits sent by the
+/// components, and autosaves the sessions after them. It also restores them,
+/// and saves them under a name, see `SessionStore`.
+///
+/// The subscribers are sent the state after every change, which they can
+/// read but not change: every change is a `StoreRequest`. They can also
+/// watch paths of the inputs data, to be sent their values when they change.
+pub struct StateStore {
+    link: AgentLink<Self>,
+    subscribers: Vec<HandlerId>,
+    watches: Watches<HandlerId>,
+    state: SharedState,
+    session_store: SessionStore,
+    notification_bus: Dispatcher<NotificationBus>,
+    timeout: TimeoutService,
+    /// Delay without edits before autosaving, see
+    /// `Settings::debounce_delay_ms`.
+    debounce_delay: Duration,
+    /// Autosave batching the edits while the user is typing.
+    save_task: Option<TimeoutTask>,
+}
+
+/// The state of the `StateStore`, shared with its subscribers.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SharedState(Rc<RefCell<State>>);
+
+impl SharedState {
+    pub fn get(&self) -> Ref<'_, State> {
+        self.0.borrow()
+    }
+}
+
+/// The paths watched by each subscriber, with the values they were last sent.
+struct Watches<K>(HashMap<K, Vec<(Path, Option<JsonValue>)>>);
+
+impl<K: Eq + Hash + Copy> Watches<K> {
+    fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    fn watch(&mut self, key: K, path: Path, value: Option<JsonValue>) {
+        let watched = self.0.entry(key).or_default();
+        watched.retain(|(watched, _)| watched != &path);
+        watched.push((path, value));
+    }
+
+    fn unwatch(&mut self, key: K, path: &Path) {
+        if let Some(watched) = self.0.get_mut(&key) {
+            watched.retain(|(watched, _)| watched != path);
+        }
+    }
+
+    fn forget(&mut self, key: K) {
+        self.0.remove(&key);
+    }
+
+    /// Returns the watched paths whose values aren't the ones last sent, with
+    /// their new values. Only the watched values are compared, not the whole
+    /// data.
+    fn changed(&mut self, data: Option<&InputsData>) -> Vec<(K, Path, Option<JsonValue>)> {
+        let mut changed = vec![];
+        for (key, watched) in &mut self.0 {
+            for (path, last) in watched {
+                let value = data.and_then(|data| data.get_at(path));
+                if value != last.as_ref() {
+                    *last = value.cloned();
+                    changed.push((*key, path.clone(), last.clone()));
+                }
+            }
+        }
+        changed
+    }
+}
+
+impl fmt::Debug for SharedState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SharedState")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum StoreRequest {
+    EditedInput(Path, JsonValue),
+    /// A value was typed in an input but didn't validate.
+    InvalidInput(Path, InvalidInput),
+    ListInputSizeChanged(Path, usize),
+    RemoveAt(Path),
+    /// Puts back a value removed from the session at this index.
+    UndoRemove(usize, Path, JsonValue),
+    /// Moves a list element to another index of the same list.
+    MoveAt(Path, usize),
+    /// Swaps a list element with the one at another index of the same list.
+    SwapAt(Path, usize),
+    /// Inserts a copy of a list element after it.
+    DuplicateAt(Path),
+    /// Puts back the default value of an input.
+    ResetAt(Path),
+    /// Reads JSON from the clipboard to merge it in a group or a list.
+    PasteJsonAt(Path),
+    /// Changes of the inputs data received from somewhere else, see
+    /// `SyncAgent`.
+    ApplyPatch(Vec<PatchOp>),
+    ToggleGroup(Path),
+    /// Collapses all the groups, or expands them.
+    SetGroupsCollapsed(bool),
+    /// The unit of a quantity input was changed, which doesn't change the data.
+    SelectUnit(Path, String),
+    PreviousStep,
+    /// Goes to the next step, unless the inputs of the current one are
+    /// missing or invalid.
+    NextStep,
+    TakeSnapshot(String),
+    RestoreSnapshot(usize),
+    DeleteSnapshot(usize),
+    /// Puts back the default values of all the inputs.
+    ResetAll,
+    /// A template edited in the template editor, by name.
+    SetTemplate(String, String),
+    /// Replaces all the sessions, eg. by a saved one. They aren't saved
+    /// until the next change.
+    Replace(State),
+    /// Puts a session in the active tab if it is empty, or else in a new tab.
+    Open(Session),
+    /// Empties the active tab.
+    Unload,
+    NewSession,
+    SelectSession(usize),
+    CloseSession(usize),
+    /// A new version of the scenario of the active tab, from its URL.
+    Reload(Scenario, String),
+    /// Saves the sessions now.
+    Save,
+    /// Saves the sessions under a name, see `SessionManager`.
+    SaveAs(String),
+    /// Replaces the sessions by the ones saved under a name, see
+    /// `StoreOutput::Loaded`.
+    Load(String),
+    Rename(String, String),
+    Delete(String),
+    /// Replaces the sessions by the autosaved ones, see
+    /// `StoreOutput::RestoredAutosave`.
+    RestoreAutosave,
+    RemoveAutosave,
+    /// Enters the passphrase encrypting the sessions, see
+    /// `StoreOutput::Unlocked`.
+    Unlock(String),
+    /// Forgets the passphrase of the sessions encrypted before, see
+    /// `Crypto::forget`.
+    ForgetPassphrase,
+    /// Immediately saves the pending edits.
+    Flush,
+    /// See `Settings::encrypt_sessions`.
+    SetEncryption(bool),
+    /// See `Settings::max_autosave_kb`.
+    SetMaxAutosaveSize(u64),
+    /// See `Settings::debounce_delay_ms`.
+    SetDebounceDelay(u64),
+    /// Sends `StoreOutput::PathChanged` now, and whenever the value at this
+    /// path of the active inputs data changes, including the values under it.
+    Watch(Path),
+    Unwatch(Path),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum StoreOutput {
+    Changed(SharedState, Change),
+    /// The sessions were autosaved, or why they couldn't be.
+    Saved(Result<(), SaveError>),
+    /// The value at a path watched with `StoreRequest::Watch`, or `None` if
+    /// there is nothing there, eg. in an empty tab. It is sent before the
+    /// `Changed` which changed it.
+    PathChanged(Path, Option<JsonValue>),
+    /// Whether the sessions saved under this name were loaded. It is sent
+    /// before the `Changed` replacing the sessions.
+    Loaded(String, Result<(), String>),
+    /// Whether the autosaved sessions were restored. It is sent before the
+    /// `Changed` replacing the sessions.
+    RestoredAutosave(Result<(), RestoreError>),
+    /// Whether the passphrase of the sessions is the right one.
+    Unlocked(Result<(), UnlockError>),
+    /// The names of the saved sessions, sent on connection and whenever they
+    /// change.
+    SavedNames(Vec<String>),
+}
+
+/// Why the sessions weren't autosaved, see `StoreOutput::Saved`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SaveError {
+    /// The sessions are encrypted, and their passphrase wasn't entered.
+    Locked,
+    /// Eg. the storage is full.
+    Failed(String),
+}
+
+/// Why the autosaved sessions weren't restored, see
+/// `StoreRequest::RestoreAutosave`.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum RestoreError {
+    /// Nothing was saved, or the storage can't be read.
+    Missing,
+    /// Kept to be restored by a newer version, or with the passphrase.
+    Kept(String),
+    /// Kept until the user exports or discards it, eg. if it was edited. The
+    /// raw autosave is given for the export.
+    Invalid(String, JsonValue),
+    /// Discarded, since it can never be restored.
+    Corrupt(String),
+}
+
+impl RestoreError {
+    fn new(error: anyhow::Error, raw_state: Option<JsonValue>) -> Self {
+        let message = format!("{:#}", error);
+        if error.is::<NewerScenarioError>()
+            || error.is::<NewerSessionError>()
+            || error.is::<DecryptionError>()
+        {
+            Self::Kept(message)
+        } else if let Some(raw_state) = raw_state {
+            Self::Invalid(message, raw_state)
+        } else if matches!(error.downcast_ref(), Some(AppError::Storage(_))) {
+            Self::Corrupt(message)
+        } else {
+            Self::Missing
+        }
+    }
+}
+
+/// What changed in the state, for the subscribers to update what they show.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Change {
+    /// The inputs of the active session, whose values are being typed if
+    /// `typing`.
+    Inputs { typing: bool },
+    /// Another session is active, or the active one was replaced.
+    ActiveSession,
+    /// All the sessions were replaced, see `StoreRequest::Replace`.
+    Replaced,
+    /// A template of the active session was edited, see
+    /// `StoreRequest::SetTemplate`.
+    Template,
+    /// Anything else, eg. the tabs or the snapshots.
+    Other,
+    /// A value was removed from the session at this index, and can be put
+    /// back with `StoreRequest::UndoRemove`.
+    Removed(usize, Path, JsonValue),
+    /// The pending edits were saved, see `StoreRequest::Flush`.
+    Flushed,
+}
+
+pub enum StoreMsg {
+    Save,
+    SaveFailed(String),
+    Loaded(HandlerId, String, Result<JsonValue>),
+    RestoredAutosave(HandlerId, Result<JsonValue>),
+    Unlocked(HandlerId, Result<(), UnlockError>),
+    PastedJson(Path, Result<String, String>),
+}
+
+impl StateStore {
+    /// Creates a callback sending requests to the store, for the views which
+    /// can't keep a `Dispatcher`. The store must have a subscriber, eg. the
+    /// `App`, for the requests to be handled.
+    pub fn callback<F, IN>(function: F) -> Callback<IN>
+    where
+        F: Fn(IN) -> StoreRequest + 'static,
+    {
+        Callback::from(move |input| Self::dispatcher().send(function(input)))
+    }
+
+    /// Like `callback`, sending any number of requests.
+    pub fn batch_callback<F, IN>(function: F) -> Callback<IN>
+    where
+        F: Fn(IN) -> Vec<StoreRequest> + 'static,
+    {
+        Callback::from(move |input| {
+            let mut dispatcher = Self::dispatcher();
+            for request in function(input) {
+                dispatcher.send(request);
+            }
+        })
+    }
+
+    fn state_mut(&self) -> RefMut<'_, State> {
+        self.state.0.borrow_mut()
+    }
+
+    fn changed(&mut self, change: Change) {
+        let changed = self.watches.changed(active_inputs_data(&self.state.get()));
+        for (subscriber, path, value) in changed {
+            self.link
+                .respond(subscriber, StoreOutput::PathChanged(path, value));
+        }
+        for subscriber in &self.subscribers {
+            let output = StoreOutput::Changed(self.state.clone(), change.clone());
+            self.link.respond(*subscriber, output);
+        }
+    }
+
+    fn save(&mut self) {
+        self.save_task = None;
+        let result = self
+            .session_store
+            .autosave(&*self.state.get())
+            .map_err(|e| match e.downcast_ref() {
+                Some(LockedError) => SaveError::Locked,
+                None => SaveError::Failed(format!("{:#}", e)),
+            });
+        for subscriber in &self.subscribers {
+            self.link
+                .respond(*subscriber, StoreOutput::Saved(result.clone()));
+        }
+    }
+
+    fn replace(&mut self, state: State) {
+        self.state_mut().replace(state);
+        self.changed(Change::Replaced);
+    }
+
+    fn on_loaded(&mut self, who: HandlerId, name: String, state: Result<JsonValue>) {
+        match state.and_then(migrate_state) {
+            Ok(state) => {
+                self.link.respond(who, StoreOutput::Loaded(name, Ok(())));
+                self.replace(state);
+                self.save();
+            }
+            Err(e) => {
+                let output = StoreOutput::Loaded(name, Err(format!("{:#}", e)));
+                self.link.respond(who, output);
+            }
+        }
+    }
+
+    fn on_restored_autosave(&mut self, who: HandlerId, state: Result<JsonValue>) {
+        let raw_state = state.as_ref().ok().cloned();
+        match state.and_then(migrate_state) {
+            Ok(state) => {
+                self.link
+                    .respond(who, StoreOutput::RestoredAutosave(Ok(())));
+                self.replace(state);
+            }
+            Err(e) => {
+                let error = RestoreError::new(e, raw_state);
+                if let RestoreError::Corrupt(_) = error {
+                    self.session_store.remove_autosave();
+                }
+                self.link
+                    .respond(who, StoreOutput::RestoredAutosave(Err(error)));
+            }
+        }
+    }
+
+    fn send_saved_names(&mut self) {
+        let names = self.session_store.names();
+        for subscriber in &self.subscribers {
+            self.link
+                .respond(*subscriber, StoreOutput::SavedNames(names.clone()));
+        }
+    }
+
+    /// Saves once the user stops typing.
+    fn debounce_save(&mut self) {
+        let callback = self.link.callback(|_| StoreMsg::Save);
+        self.save_task = Some(self.timeout.spawn(self.debounce_delay, callback));
+    }
+
+    fn merge_pasted_json(&mut self, path: &Path, text: Result<String, String>) {
+        let merged = text
+            .map_err(|e| anyhow!(tr!("Failed to read the clipboard: {}", e)))
+            .and_then(|text| serde_json::from_str(&text).context(tr("The clipboard isn't JSON.")))
+            .and_then(|value| self.state_mut().merge_at(path, value));
+        let conflicts = match merged {
+            Ok(conflicts) => conflicts,
+            Err(e) => {
+                self.notif_error_chain(e.context(tr("Failed to paste the JSON.")));
+                return;
+            }
+        };
+        if !conflicts.is_empty() {
+            let replaced: Vec<_> = conflicts.iter().map(ToString::to_string).collect();
+            self.notif_info(tr!(
+                "The pasted JSON replaced the values of: {}.",
+                replaced.join(", ")
+            ));
+        }
+        self.changed(Change::Inputs { typing: false });
+        self.save();
+    }
+}
+
+fn active_inputs_data(state: &State) -> Option<&InputsData> {
+    match state.active() {
+        Session::Loaded { inputs_data, .. } => Some(inputs_data),
+        Session::Init => None,
+    }
+}
+
+impl NotificationSender for StateStore {
+    const SOURCE: &'static str = "StateStore";
+
+    fn notification_bus(&mut self) -> &mut Dispatcher<NotificationBus> {
+        &mut self.notification_bus
+    }
+}
+
+impl Agent for StateStore {
+    type Reach = Context;
+    type Message = StoreMsg;
+    type Input = StoreRequest;
+    type Output = StoreOutput;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        let session_store = SessionStore::new(false, link.callback(StoreMsg::SaveFailed));
+        Self {
+            link,
+            subscribers: Vec::with_capacity(1),
+            watches: Watches::new(),
+            state: SharedState::default(),
+            session_store,
+            notification_bus: NotificationBus::dispatcher(),
+            timeout: TimeoutService::new(),
+            debounce_delay: Duration::from_millis(0),
+            save_task: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            StoreMsg::Save => self.save(),
+            StoreMsg::SaveFailed(error) => {
+                for subscriber in &self.subscribers {
+                    self.link.respond(
+                        *subscriber,
+                        StoreOutput::Saved(Err(SaveError::Failed(error.clone()))),
+                    );
+                }
+            }
+            StoreMsg::PastedJson(path, text) => self.merge_pasted_json(&path, text),
+            StoreMsg::Loaded(who, name, state) => self.on_loaded(who, name, state),
+            StoreMsg::RestoredAutosave(who, state) => self.on_restored_autosave(who, state),
+            StoreMsg::Unlocked(who, result) => {
+                self.link.respond(who, StoreOutput::Unlocked(result));
+            }
+        }
+    }
+
+    fn handle_input(&mut self, request: Self::Input, who: HandlerId) {
+        trace!("Store request: {:?}", request);
+        let change = match request {
+            StoreRequest::EditedInput(path, value) => {
+                let edited = self.state_mut().edit(path.clone(), value);
+                if let Err(e) = edited {
+                    let message = tr!("Failed to save the value of '{}'.", path);
+                    self.notif_error_chain(e.context(message));
+                }
+                self.changed(Change::Inputs { typing: true });
+                self.debounce_save();
+                return;
+            }
+            StoreRequest::InvalidInput(path, invalid) => {
+                self.state_mut().mark_invalid(path, invalid);
+                self.changed(Change::Inputs { typing: true });
+                self.debounce_save();
+                return;
+            }
+            StoreRequest::ListInputSizeChanged(path, new_size) => {
+                self.state_mut().resize_at(&path, new_size);
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::RemoveAt(path) => {
+                let removed = self.state_mut().remove_at(&path);
+                match removed {
+                    Some(value) => Change::Removed(self.state.get().active, path, value),
+                    None => Change::Inputs { typing: false },
+                }
+            }
+            StoreRequest::UndoRemove(session, path, value) => {
+                let closed = !matches!(
+                    self.state.get().sessions.get(session),
+                    Some(Session::Loaded { .. })
+                );
+                if closed {
+                    self.notif_warn(tr("The tab of the removed element was closed."));
+                    return;
+                }
+                let restored = self.state_mut().restore_at(session, &path, value);
+                if let Err(e) = restored {
+                    self.notif_error_chain(e.context(tr("Failed to undo the removal.")));
+                    return;
+                }
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::MoveAt(path, new_index) => {
+                self.state_mut().move_at(&path, new_index);
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::SwapAt(path, other_index) => {
+                self.state_mut().swap_at(&path, other_index);
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::DuplicateAt(path) => {
+                self.state_mut().duplicate_at(&path);
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::ResetAt(path) => {
+                self.state_mut().reset_at(&path);
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::PasteJsonAt(path) => {
+                clipboard::read_text(
+                    self.link
+                        .callback(move |text| StoreMsg::PastedJson(path.clone(), text)),
+                );
+                return;
+            }
+            StoreRequest::ApplyPatch(patch) => {
+                let applied = self.state_mut().apply_patch(&patch);
+                if let Err(e) = applied {
+                    self.notif_error_chain(e.context(tr("Failed to apply the received changes.")));
+                    return;
+                }
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::ToggleGroup(path) => {
+                self.state_mut().toggle_group(path);
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::SetGroupsCollapsed(collapsed) => {
+                self.state_mut().set_groups_collapsed(collapsed);
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::SelectUnit(path, unit) => {
+                self.state_mut().select_unit(path, unit);
+                // Nothing to save
+                self.changed(Change::Inputs { typing: false });
+                return;
+            }
+            StoreRequest::PreviousStep => {
+                self.state_mut().previous_step();
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::NextStep => {
+                let went = self.state_mut().next_step();
+                if !went {
+                    self.notif_warn(tr(
+                        "Fix the inputs of this step before going to the next one."
+                    ));
+                    return;
+                }
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::TakeSnapshot(name) => {
+                self.state_mut().take_snapshot(name);
+                Change::Other
+            }
+            StoreRequest::RestoreSnapshot(index) => {
+                if !self.state_mut().restore_snapshot(index) {
+                    return;
+                }
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::DeleteSnapshot(index) => {
+                self.state_mut().delete_snapshot(index);
+                Change::Other
+            }
+            StoreRequest::ResetAll => {
+                let reset = self.state_mut().reset_all();
+                if let Err(e) = reset {
+                    self.notif_error_chain(e.context(tr("Failed to reset the inputs.")));
+                    return;
+                }
+                Change::Inputs { typing: false }
+            }
+            StoreRequest::SetTemplate(name, template) => {
+                self.state_mut().set_template(&name, template);
+                Change::Template
+            }
+            StoreRequest::Replace(state) => {
+                self.replace(state);
+                return;
+            }
+            StoreRequest::Open(session) => {
+                self.state_mut().open(session);
+                Change::ActiveSession
+            }
+            StoreRequest::Unload => {
+                self.state_mut().unload();
+                Change::ActiveSession
+            }
+            StoreRequest::NewSession => {
+                self.state_mut().new_session();
+                Change::ActiveSession
+            }
+            StoreRequest::SelectSession(index) => {
+                if !self.state_mut().select_session(index) {
+                    return;
+                }
+                Change::ActiveSession
+            }
+            StoreRequest::CloseSession(index) => {
+                let closed = self.state_mut().close_session(index);
+                match closed {
+                    Some(true) => Change::ActiveSession,
+                    Some(false) => Change::Other,
+                    None => return,
+                }
+            }
+            StoreRequest::Reload(scenario, url) => {
+                let reloaded = self.state_mut().reload(scenario, &url);
+                match reloaded {
+                    Ok(true) => {
+                        self.notif_info(tr!("Reloaded the changes of the scenario at {}.", url));
+                        Change::ActiveSession
+                    }
+                    Ok(false) => return,
+                    Err(e) => {
+                        self.notif_error_chain(e.context(tr("Failed to reload the scenario.")));
+                        return;
+                    }
+                }
+            }
+            StoreRequest::Save => {
+                self.save();
+                return;
+            }
+            StoreRequest::SaveAs(name) => {
+                let saved = self.session_store.save(&name, &*self.state.get());
+                match saved {
+                    Ok(()) => self.notif_success(tr!("Saved the session as '{}'.", name)),
+                    Err(e) => self.notif_error_chain(e),
+                }
+                self.send_saved_names();
+                return;
+            }
+            StoreRequest::Load(name) => {
+                let name_inner = name.clone();
+                let callback = self
+                    .link
+                    .callback(move |state| StoreMsg::Loaded(who, name_inner.clone(), state));
+                self.session_store.load(&name, callback);
+                return;
+            }
+            StoreRequest::Rename(name, new_name) => {
+                match self.session_store.rename(&name, &new_name) {
+                    Ok(()) => {
+                        self.notif_success(tr!("Renamed the session '{}' to '{}'.", name, new_name))
+                    }
+                    Err(e) => self.notif_error_chain(e),
+                }
+                self.send_saved_names();
+                return;
+            }
+            StoreRequest::Delete(name) => {
+                match self.session_store.delete(&name) {
+                    Ok(()) => self.notif_success(tr!("Deleted the session '{}'.", name)),
+                    Err(e) => self.notif_error_chain(e),
+                }
+                self.send_saved_names();
+                return;
+            }
+            StoreRequest::RestoreAutosave => {
+                let callback = self
+                    .link
+                    .callback(move |state| StoreMsg::RestoredAutosave(who, state));
+                self.session_store.restore_autosave(callback);
+                return;
+            }
+            StoreRequest::RemoveAutosave => {
+                self.session_store.remove_autosave();
+                return;
+            }
+            StoreRequest::Unlock(passphrase) => {
+                let callback = self
+                    .link
+                    .callback(move |result| StoreMsg::Unlocked(who, result));
+                self.session_store.unlock(&passphrase, callback);
+                return;
+            }
+            StoreRequest::ForgetPassphrase => {
+                self.session_store.forget_passphrase();
+                return;
+            }
+            StoreRequest::Flush => {
+                if self.save_task.is_some() {
+                    self.save();
+                }
+                self.changed(Change::Flushed);
+                return;
+            }
+            StoreRequest::SetEncryption(encrypt) => {
+                self.session_store.set_encryption(encrypt);
+                return;
+            }
+            StoreRequest::SetMaxAutosaveSize(max_kb) => {
+                self.session_store.set_max_autosave_kb(max_kb);
+                return;
+            }
+            StoreRequest::SetDebounceDelay(delay) => {
+                self.debounce_delay = Duration::from_millis(delay);
+                return;
+            }
+            StoreRequest::Watch(path) => {
+                let value = active_inputs_data(&self.state.get())
+                    .and_then(|data| data.get_at(&path))
+                    .cloned();
+                self.watches.watch(who, path.clone(), value.clone());
+                self.link
+                    .respond(who, StoreOutput::PathChanged(path, value));
+                return;
+            }
+            StoreRequest::Unwatch(path) => {
+                self.watches.unwatch(who, &path);
+                return;
+            }
+        };
+        self.changed(change);
+        self.save();
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        if !self.subscribers.contains(&id) {
+            self.subscribers.push(id);
+        }
+        let names = self.session_store.names();
+        self.link.respond(id, StoreOutput::SavedNames(names));
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        if let Some(pos) = self.subscribers.iter().position(|x| *x == id) {
+            self.subscribers.swap_remove(pos);
+        }
+        self.watches.forget(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn watched_paths() {
+        let mut data = InputsData::from(json!({"server": {"ip": "10.0.0.1"}, "name": "web"}));
+        let mut watches = Watches::new();
+        watches.watch(
+            1,
+            Path::from("server"),
+            data.get_at(&"server".into()).cloned(),
+        );
+        watches.watch(2, Path::from("server.ip"), Some(json!("10.0.0.1")));
+        watches.watch(2, Path::from("port"), None);
+
+        data.insert_at(&Path::from("name"), json!("db")).unwrap();
+        assert!(watches.changed(Some(&data)).is_empty());
+
+        data.insert_at(&Path::from("server.ip"), json!("10.0.0.2"))
+            .unwrap();
+        let mut changed = watches.changed(Some(&data));
+        changed.sort_by_key(|(key, _, _)| *key);
+        assert_eq!(
+            changed,
+            [
+                (1, Path::from("server"), Some(json!({"ip": "10.0.0.2"}))),
+                (2, Path::from("server.ip"), Some(json!("10.0.0.2"))),
+            ]
+        );
+        assert!(watches.changed(Some(&data)).is_empty());
+
+        watches.unwatch(1, &Path::from("server"));
+        watches.forget(2);
+        assert!(watches.changed(None).is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+use super::{
+    Change, NotificationBus, NotificationSender, SharedState, StateStore, StoreOutput, StoreRequest,
+};
+use crate::{
+    crdt::{Edit, Replica},
+    prelude::*,
+    state::Session,
+    InputsData, PatchOp, Path,
+};
+use anyhow::Context as _;
+use std::{collections::HashMap, time::Duration};
+use stdweb::{js, unstable::TryInto};
+use yew::{
+    agent::{Bridge, Bridged, Dispatched, Dispatcher},
+    format::Text,
+    services::{
+        interval::{IntervalService, IntervalTask},
+        websocket::{WebSocketService, WebSocketStatus, WebSocketTask},
+    },
+    worker::{Agent, AgentLink, Context, HandlerId},
+};
+
+/// How long a peer is shown on the input it edited, see `SyncOutput`.
+const PRESENCE_TIMEOUT_MS: f64 = 60_000.;
+
+/// Keeps the inputs data of the active tab in sync with a WebSocket server,
+/// see `Settings::sync_url`, so that several people (or bots) can edit and
+/// watch the same inputs live.
+///
+/// The edits are sent as `Edits`, merged with the ones of the peers made at
+/// the same time, see `crdt`. The JSON Patches received, eg.
+/// `[{"op": "replace", "path": "/name", "value": "web"}]`, are applied as
+/// they are, for the bots. The server is expected to relay the messages to
+/// the other peers, which show the same scenario.
+pub struct SyncAgent {
+    link: AgentLink<Self>,
+    store: Box<dyn Bridge<StateStore>>,
+    notification_bus: Dispatcher<NotificationBus>,
+    websocket: WebSocketService,
+    socket: Option<WebSocketTask>,
+    /// Whether `socket` is opened.
+    connected: bool,
+    url: String,
+    /// The last state sent by the `StateStore`.
+    state: SharedState,
+    /// The inputs data as the peers know it, which the sent edits are
+    /// computed from. `None` until connected.
+    synced: Option<InputsData>,
+    replica: Replica,
+    /// The input each peer edited last, by site, and when.
+    peers: HashMap<String, (Path, f64)>,
+    subscribers: Vec<HandlerId>,
+    interval: IntervalService,
+    /// Forgets the peers which stopped editing, see `PRESENCE_TIMEOUT_MS`.
+    presence_task: Option<IntervalTask>,
+}
+
+/// What the peers send each other.
+#[derive(Serialize, Deserialize)]
+struct Edits {
+    site: String,
+    edits: Vec<Edit>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum SyncRequest {
+    /// Syncs with the WebSocket server at this URL, instead of the previous
+    /// one, or stops syncing if it is empty.
+    Connect(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum SyncOutput {
+    /// The peers editing each input, to show them next to it.
+    Presence(HashMap<Path, Vec<String>>),
+}
+
+pub enum SyncMsg {
+    Store(StoreOutput),
+    Received(Text),
+    Status(WebSocketStatus),
+    ForgetIdlePeers,
+}
+
+impl SyncAgent {
+    fn active_inputs_data(&self) -> Option<InputsData> {
+        match self.state.get().active() {
+            Session::Loaded { inputs_data, .. } => Some(inputs_data.clone()),
+            Session::Init => None,
+        }
+    }
+
+    /// Starts syncing from the inputs data of the active tab.
+    fn reset(&mut self) {
+        self.synced = self.active_inputs_data();
+        self.replica = Replica::new(self.replica.site());
+        self.peers.clear();
+        self.send_presence();
+    }
+
+    fn connect(&mut self, url: String) {
+        self.socket = None;
+        self.connected = false;
+        self.synced = None;
+        self.presence_task = None;
+        self.peers.clear();
+        self.send_presence();
+        self.url = url.trim().to_owned();
+        if self.url.is_empty() {
+            return;
+        }
+        let socket = self
+            .websocket
+            .connect_text(
+                &self.url,
+                self.link.callback(SyncMsg::Received),
+                self.link.callback(SyncMsg::Status),
+            )
+            .map_err(str::to_owned);
+        match socket {
+            Ok(socket) => self.socket = Some(socket),
+            Err(e) => self.notif_error(tr!("Failed to sync the inputs with {}: {}", self.url, e)),
+        }
+    }
+
+    /// Sends the changes made since the last sync.
+    fn send_changes(&mut self) {
+        let (socket, synced) = match (&mut self.socket, &self.synced) {
+            (Some(socket), Some(synced)) => (socket, synced),
+            _ => return,
+        };
+        let current = match self.state.get().active() {
+            Session::Loaded { inputs_data, .. } => inputs_data.clone(),
+            Session::Init => return,
+        };
+        let edits = Edits {
+            site: self.replica.site().to_owned(),
+            edits: self.replica.edits_between(synced, &current),
+        };
+        if !edits.edits.is_empty() {
+            socket.send(serde_json::to_string(&edits).map_err(anyhow::Error::from));
+        }
+        self.synced = Some(current);
+    }
+
+    fn receive(&mut self, text: Text) {
+        let text = match text {
+            Ok(text) => text,
+            Err(e) => {
+                self.notif_error_chain(e.context(tr!("Ignored a message of {}.", self.url)));
+                return;
+            }
+        };
+        if let Ok(edits) = serde_json::from_str::<Edits>(&text) {
+            self.merge(edits);
+            return;
+        }
+        match serde_json::from_str::<Vec<PatchOp>>(&text).context(tr("Expected a JSON Patch.")) {
+            Ok(patch) => self.apply_patch(patch),
+            Err(e) => self.notif_error_chain(e.context(tr!("Ignored a message of {}.", self.url))),
+        }
+    }
+
+    /// Merges the edits of a peer with the ones made since they were made.
+    fn merge(&mut self, Edits { site, edits }: Edits) {
+        // Sent back by the server
+        if site == self.replica.site() {
+            return;
+        }
+        let synced = match &mut self.synced {
+            Some(synced) => synced,
+            None => return,
+        };
+        let mut merged = synced.clone();
+        self.replica.merge(&edits, &mut merged);
+        let patch = synced.patch_to(&merged);
+        *synced = merged;
+        if !patch.is_empty() {
+            self.store.send(StoreRequest::ApplyPatch(patch));
+        }
+        if let Some(edit) = edits.last() {
+            self.peers.insert(site, (edit.path().clone(), now_ms()));
+            self.send_presence();
+        }
+    }
+
+    /// Applies a patch sent by a bot, if it applies to what was synced.
+    fn apply_patch(&mut self, patch: Vec<PatchOp>) {
+        // So that the applied changes aren't sent back
+        let applied = match &mut self.synced {
+            Some(synced) => synced.apply_patch(&patch),
+            None => return,
+        };
+        if let Err(e) = applied {
+            let message = tr("The received changes don't apply to the inputs of this tab.");
+            self.notif_error_chain(anyhow::Error::from(e).context(message));
+            return;
+        }
+        self.store.send(StoreRequest::ApplyPatch(patch));
+    }
+
+    fn forget_idle_peers(&mut self) {
+        let now = now_ms();
+        let count = self.peers.len();
+        self.peers
+            .retain(|_, (_, seen)| now - *seen < PRESENCE_TIMEOUT_MS);
+        if self.peers.len() != count {
+            self.send_presence();
+        }
+    }
+
+    fn send_presence(&self) {
+        let mut presence: HashMap<Path, Vec<String>> = HashMap::new();
+        for (site, (path, _)) in &self.peers {
+            presence.entry(path.clone()).or_default().push(site.clone());
+        }
+        for names in presence.values_mut() {
+            names.sort();
+        }
+        for subscriber in &self.subscribers {
+            self.link
+                .respond(*subscriber, SyncOutput::Presence(presence.clone()));
+        }
+    }
+}
+
+/// Identifies this peer, among the ones syncing with the same server.
+fn random_site() -> String {
+    js! { return Math.random().toString(36).slice(2, 8); }
+        .into_string()
+        .unwrap_or_default()
+}
+
+fn now_ms() -> f64 {
+    js! { return Date.now(); }.try_into().unwrap_or_default()
+}
+
+impl NotificationSender for SyncAgent {
+    const SOURCE: &'static str = "SyncAgent";
+
+    fn notification_bus(&mut self) -> &mut Dispatcher<NotificationBus> {
+        &mut self.notification_bus
+    }
+}
+
+impl Agent for SyncAgent {
+    type Reach = Context;
+    type Message = SyncMsg;
+    type Input = SyncRequest;
+    type Output = SyncOutput;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Self {
+            store: StateStore::bridge(link.callback(SyncMsg::Store)),
+            link,
+            notification_bus: NotificationBus::dispatcher(),
+            websocket: WebSocketService::new(),
+            socket: None,
+            connected: false,
+            url: String::new(),
+            state: SharedState::default(),
+            synced: None,
+            replica: Replica::new(random_site()),
+            peers: HashMap::new(),
+            subscribers: vec![],
+            interval: IntervalService::new(),
+            presence_task: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            SyncMsg::Store(StoreOutput::Changed(state, change)) => {
+                self.state = state;
+                match change {
+                    Change::Inputs { .. } | Change::Removed(..) => self.send_changes(),
+                    // The peers show the tab they want
+                    Change::ActiveSession | Change::Replaced if self.connected => self.reset(),
+                    _ => {}
+                }
+            }
+            SyncMsg::Store(_) => {}
+            SyncMsg::Received(text) => self.receive(text),
+            SyncMsg::Status(WebSocketStatus::Opened) => {
+                self.connected = true;
+                self.reset();
+                let tick = self.link.callback(|_| SyncMsg::ForgetIdlePeers);
+                self.presence_task = Some(self.interval.spawn(Duration::from_secs(10), tick));
+                self.notif_success(tr!("Syncing the inputs live with {}.", self.url));
+            }
+            SyncMsg::Status(WebSocketStatus::Closed) => {
+                self.socket = None;
+                self.connected = false;
+                self.synced = None;
+                self.presence_task = None;
+                self.peers.clear();
+                self.send_presence();
+                self.notif_warn(tr!("Stopped syncing the inputs with {}.", self.url));
+            }
+            SyncMsg::Status(WebSocketStatus::Error) => {
+                self.notif_error(tr!("Failed to sync the inputs with {}.", self.url));
+            }
+            SyncMsg::ForgetIdlePeers => self.forget_idle_peers(),
+        }
+    }
+
+    fn handle_input(&mut self, request: Self::Input, _: HandlerId) {
+        match request {
+            SyncRequest::Connect(url) => self.connect(url),
+        }
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        if !self.subscribers.contains(&id) {
+            self.subscribers.push(id);
+        }
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        self.subscribers.retain(|subscriber| *subscriber != id);
+    }
+}
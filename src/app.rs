@@ -1,58 +1,361 @@
 use crate::{
-    agents::{NotificationBus, NotificationSender},
-    components::{Navbar, Notifications},
+    agents::{
+        Change, ChangesWebhook, ChangesWebhookRequest, Notification, NotificationAction,
+        NotificationBus, NotificationLevel, NotificationRequest, NotificationSender, RestoreError,
+        SaveError, SharedState, StateStore, StoreOutput, StoreRequest, SyncAgent, SyncOutput,
+        SyncRequest,
+    },
+    clipboard,
+    components::{
+        find_example, Navbar, Notifications, ScenarioGallery, SessionEvent, SessionManager,
+    },
+    i18n::{self, Language},
+    js_api,
     prelude::*,
-    scenario::Scenario,
-    template_engine::{HandlebarsEngine, TemplateEngine},
-    InputsData, Path,
+    route::Route,
+    scenario::{
+        self, initial_inputs_data, Diagnostic, Format, RenderEach, Scenario, Severity, Step,
+        MAIN_TEMPLATE, MAX_BASES,
+    },
+    session_store::UnlockError,
+    settings::{Layout, Settings, Theme},
+    share,
+    state::{migrate_session, Session, State},
+    template_engine::{render_each_element, Engine, Rendered, TemplateEngine, TemplateError},
+    views::{
+        find_matches, highlight, is_known_language, render_markdown, render_matches,
+        render_output_diff, render_search_box, render_snapshots, scroll_to_current_match,
+        InputsContext,
+    },
+    webhook::Webhook,
+    AppError, ArrayMerge, InputsData, Path,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap, HashSet},
+    rc::Rc,
+    time::Duration,
+};
+use stdweb::{
+    js,
+    unstable::TryInto,
+    web::{
+        event::{BeforeUnloadEvent, IDragEvent, IEvent},
+        window, EventListenerHandle, IEventTarget,
+    },
 };
-use lazy_static::lazy_static;
-use serde::{Deserialize, Serialize};
 use yew::{
     agent::{Dispatched, Dispatcher},
-    format::Json as YewJson,
-    services::storage::{Area, StorageService},
+    format::{Nothing, Text},
+    services::{
+        fetch::{FetchService, FetchTask, Request, Response},
+        interval::{IntervalService, IntervalTask},
+        reader::{File, FileData, ReaderService, ReaderTask},
+        storage::{Area, StorageService},
+        timeout::{TimeoutService, TimeoutTask},
+    },
     Component, ComponentLink, Html, ShouldRender,
 };
+use yew_router::agent::{RouteAgentBridge, RouteRequest};
 
 use crate::inputs::*;
 
-lazy_static! {
-    static ref LOCAL_STORAGE_KEY: String =
-        { format!("totorigolo.{}.state", env!("CARGO_PKG_NAME")) };
+/// Encodes a text as a `data:` URL, to download it without a server.
+fn data_url(text: &str) -> String {
+    let mut url = "data:text/plain;charset=utf-8,".to_owned();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+/// Makes the browser download a text file.
+fn download(file_name: &str, text: &str) {
+    let url = data_url(text);
+    js! {
+        var link = document.createElement("a");
+        link.href = @{url};
+        link.download = @{file_name};
+        document.body.appendChild(link);
+        link.click();
+        link.remove();
+    }
+}
+
+/// Swaps the `theme-dark` class of the root element, which restyles all the
+/// components. The `Auto` theme follows the changes of the system preference.
+fn apply_theme(theme: Theme) {
+    js! { @(no_return)
+        var root = document.documentElement;
+        root.dataset.theme = @{theme.name()};
+        if (!window.applyTheme) {
+            var query = matchMedia("(prefers-color-scheme: dark)");
+            window.applyTheme = function() {
+                var theme = root.dataset.theme;
+                root.classList.toggle("theme-dark", theme === "dark" || (theme === "auto" && query.matches));
+            };
+            query.addListener(window.applyTheme);
+        }
+        window.applyTheme();
+    }
+}
+
+/// Returns the value of a parameter of the query string of the page.
+fn query_param(name: &str) -> Option<String> {
+    js! { return new URLSearchParams(window.location.search).get(@{name}); }.into_string()
+}
+
+/// Decodes JSON encoded in base64, with either the standard or the URL-safe
+/// alphabet.
+fn decode_base64_json(encoded: &str) -> Result<JsonValue> {
+    let encoded: String = encoded
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            // The `+` of unescaped query strings are read as spaces
+            '+' | ' ' => '-',
+            '/' => '_',
+            c => c,
+        })
+        .collect();
+    let bytes =
+        base64::decode_config(&encoded, base64::URL_SAFE_NO_PAD).context(tr("Invalid base64."))?;
+    serde_json::from_slice(&bytes).context(tr("Invalid JSON."))
 }
 
 const JSON_INPUT: &str = include_str!("input_data.json");
 const INPUT_TEMPLATE: &str = include_str!("input_template.hbs");
 
+/// Delay without typing in the template editor before re-compiling.
+const TEMPLATE_COMPILE_DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Renders slower than this are reported, see `render_statistics`.
+const SLOW_RENDER_MS: f64 = 200.0;
+/// Outputs bigger than this are reported, see `render_statistics`.
+const BIG_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// How many actions of notifications are kept, the older notifications being
+/// likely dismissed.
+const MAX_NOTIFICATION_ACTIONS: usize = 20;
+
 pub struct App {
     link: ComponentLink<Self>,
-    template_engine: HandlebarsEngine,
+    template_engine: Engine,
     storage: StorageService,
+    /// Whether a save failed, so as to only report it once.
+    save_failed: bool,
+    /// Whether the `SessionManager` is open.
+    managing_sessions: bool,
     notification_bus: Dispatcher<NotificationBus>,
-    state: State,
+    /// Tells which actions of the notifications are clicked.
+    _notification_actions: Box<dyn Bridge<NotificationBus>>,
+    /// Messages sent when the actions of the notifications are clicked, by
+    /// identifier, see `App::notification_action`.
+    notification_actions: BTreeMap<usize, Msg>,
+    next_action_id: usize,
+    /// Whether the autosave couldn't be restored because it is invalid, until
+    /// the tabs are saved again.
+    invalid_autosave: bool,
+    /// The sessions, changed by sending requests to the store.
+    state: SharedState,
+    store: Box<dyn Bridge<StateStore>>,
+    sync: Box<dyn Bridge<SyncAgent>>,
+    /// The peers editing each input, see `SyncOutput::Presence`.
+    presence: HashMap<Path, Vec<String>>,
+    changes_webhook: Dispatcher<ChangesWebhook>,
+    settings: Settings,
     on_navevent: Callback<NavEvent>,
+    timeout: TimeoutService,
+    fetch_service: FetchService,
+    /// Download of a scenario, see `Msg::FetchScenario`.
+    fetch_task: Option<FetchTask>,
+    /// Post of the output to the webhook, see `Msg::SendOutput`.
+    send_task: Option<FetchTask>,
+    reader: ReaderService,
+    /// Reading of a dropped scenario file, see `Msg::DroppedFile`.
+    reader_task: Option<ReaderTask>,
+    /// Whether a file is dragged over the page, to show where to drop it.
+    dragging_file: bool,
+    /// Whether the `?scenario=` parameter of the page was handled, since
+    /// `Msg::Init` is also sent when unloading the scenario.
+    query_handled: bool,
+    /// Whether the tabs of the previous visit are being restored on startup,
+    /// after which the scenario of the link is loaded.
+    starting: bool,
+    /// Whether the scenario of the link is loaded once the restored sessions
+    /// replaced the open ones, see `Change::Replaced`.
+    link_after_restore: bool,
+    /// Inputs data given by `?data=`, waiting for the scenario to be loaded.
+    prefilled_data: Option<JsonValue>,
+    /// URL of inputs data given by `?data_url=`, see `Scenario::data_url`.
+    prefilled_data_url: Option<String>,
+    /// A scenario waiting for the download of its inputs data, with its URL
+    /// and the example it comes from, if any.
+    awaiting_data: Option<(Scenario, Option<String>, Option<String>)>,
+    /// A scenario waiting for the download of the scenario it extends, with
+    /// the number of bases already merged in it. See `scenario::extend`.
+    extending: Option<(JsonValue, usize)>,
+    loading: Loading,
+    /// Whether sessions are being read from the storage, which can take a
+    /// while when they are encrypted.
+    restoring: bool,
+    /// The names of the saved sessions, see `SessionManager`.
+    saved_names: Vec<String>,
+    interval: IntervalService,
+    /// Polling of the URL of the scenario, see `Settings::reload_interval_s`.
+    reload_task: Option<IntervalTask>,
+    /// Problems found in the last scenario loaded, see `scenario::validate`.
+    diagnostics: Vec<Diagnostic>,
+    template_editor: TemplateEditor,
+    /// Name of the template shown in the editor and the output.
+    active_template: String,
+    /// The paths of the inputs data the active template reads, watched to
+    /// render it again when they change, see `App::watch_read_paths`.
+    read_paths: Vec<Path>,
+    /// Text filtering the inputs shown by their name or key.
+    inputs_filter: String,
+    /// Index of the snapshot whose differences with the inputs data are
+    /// shown.
+    compared_snapshot: Option<usize>,
+    /// Output of the active template when it was pinned, to show how the
+    /// inputs changed it since.
+    pinned_output: Option<String>,
+    /// Whether the differences with the pinned output are shown, instead of
+    /// the output.
+    showing_output_diff: bool,
+    /// Index of the document shown, when the template is rendered once per
+    /// element of a list, or `None` to show all of them.
+    active_document: Option<usize>,
+    /// Text searched in the output, and the index of the current match.
+    output_search: (String, usize),
+    debounce: Debounce,
+    /// The page shown, see `App::apply_route`.
+    route: Route,
+    router: RouteAgentBridge,
+    _unload_listener: EventListenerHandle,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-enum State {
-    Init,
-    Loaded {
-        scenario: Scenario,
-        #[serde(default)]
-        inputs_data: InputsData,
-    },
+/// Batches the re-renders while the user is typing. The saves are batched by
+/// the `StateStore`.
+#[derive(Default)]
+struct Debounce {
+    render_task: Option<TimeoutTask>,
+    /// Output of the last render, shown while a render is pending. It is
+    /// shared with `js_api::expose`.
+    rendered: Rc<RefCell<Option<Rendered>>>,
+    /// Whether the inputs data the template reads changed since the last
+    /// render, see `App::watch_read_paths`. The template isn't rendered
+    /// again until it does.
+    stale: Cell<bool>,
+}
+
+/// Where the scenario being loaded comes from, which is remembered while the
+/// scenarios it extends are downloaded.
+#[derive(Debug)]
+enum Loading {
+    /// A file or a text, opened in a tab.
+    Local,
+    /// A URL, opened in a tab from which it can be reloaded.
+    Remote(String),
+    /// A new version of the scenario of the active tab, from its URL.
+    Reload(String),
+    /// An example, opened in a tab from its route, see `Route::Scenario`.
+    Example(String),
+}
+
+/// State of the template editor, which isn't persisted.
+#[derive(Default)]
+struct TemplateEditor {
+    /// Text typed in the editor but not compiled yet.
+    pending: Option<String>,
+    /// Error of the last compilation, if it failed.
+    error: Option<TemplateError>,
+    compile_task: Option<TimeoutTask>,
 }
 
 #[derive(Debug)]
 pub enum Msg {
     Init,
     NavEvent(NavEvent),
-    FetchedJsonData(String),
-    SaveToLocalStorage,
-    EditedInput(Path, JsonValue),
-    ListInputSizeChanged(Path, usize),
-    RemoveAt(Path),
+    /// Downloads a scenario from a URL.
+    FetchScenario(String),
+    FetchScenarioFailed(String),
+    DraggingFile(bool),
+    /// Loads the scenario of a file dropped on the page.
+    DroppedFile(File),
+    ReadFile(FileData),
+    /// A scenario to load, with the name of its file or its URL if any.
+    FetchedScenario(String, Option<String>),
+    /// The base of the scenario waiting in `App::extending`, with its URL.
+    FetchedBase(String, Option<String>),
+    /// The inputs data of the scenario waiting in `App::awaiting_data`, or
+    /// why it couldn't be downloaded.
+    FetchedData(Result<JsonValue, String>),
+    /// Downloads the scenario of the active tab again, see
+    /// `Settings::reload_interval_s`.
+    PollScenario,
+    /// A new version of the scenario of the active tab, with its URL.
+    ReloadedScenario(String, Option<String>),
+    DismissDiagnostics,
+    CopiedShareLink(Result<(), String>),
+    /// Copies the output of the last render to the clipboard.
+    CopyOutput,
+    CopiedOutput(Result<(), String>),
+    /// Renders the template, see `Settings::manual_render`.
+    Render,
+    /// Shows a document, see `Scenario::render_each`, or all of them.
+    SelectDocument(Option<usize>),
+    /// Posts the output of the last render to the webhook, see `Webhook`.
+    SendOutput,
+    /// The response of the webhook, or why it failed.
+    SentOutput(Result<String, String>),
+    /// Keeps the output of the last render, see `App::pinned_output`.
+    PinOutput,
+    UnpinOutput,
+    ToggleOutputDiff,
+    /// Shows or hides the inputs data, see `Settings::show_data`.
+    ToggleData,
+    SearchOutput(String),
+    NextMatch,
+    PreviousMatch,
+    SessionEvent(SessionEvent),
+    /// Downloads an autosave which couldn't be restored.
+    ExportAutosave(JsonValue),
+    /// Removes the autosave which couldn't be restored, unless the tabs were
+    /// saved since.
+    DiscardAutosave,
+    /// The sessions changed, or were saved, see `StateStore`.
+    Store(StoreOutput),
+    Sync(SyncOutput),
+    /// Puts back a value removed from the session at this index.
+    UndoRemove(usize, Path, JsonValue),
+    /// Asks for a name, and saves the inputs data under it.
+    TakeSnapshot,
+    /// Asks for a confirmation before replacing the inputs data.
+    RestoreSnapshot(usize),
+    CompareSnapshot(Option<usize>),
+    DeleteSnapshot(usize),
+    FilterInputs(String),
+    EditedTemplate(String),
+    CompileTemplate,
+    SelectTemplate(String),
+    /// Re-renders the template after edits, see `Debounce`.
+    DebouncedRender,
+    /// Immediately renders and saves the pending edits, eg. when the page is
+    /// closed.
+    Flush,
+    /// Goes to a page, adding it to the history of the browser.
+    Navigate(Route),
+    /// The page changed, eg. because the user went back in the history of
+    /// the browser, see `RouteAgent`.
+    RouteChanged(Route),
+    /// An action of a notification was clicked, see `App::notification_action`.
+    NotificationAction(usize),
 }
 
 #[derive(Debug)]
@@ -60,9 +363,48 @@ pub enum NavEvent {
     LoadDebugScenario,
     LoadFromLocalStorage,
     UnloadScenario,
+    /// Loads the scenario at the given URL.
+    LoadFromUrl(String),
+    /// Downloads the scenario of the active tab again, and applies its
+    /// changes.
+    ReloadScenario,
+    /// Loads the scenario or the session of a file chosen by the user.
+    OpenScenarioFile(File),
+    /// Downloads the scenario along with the inputs data.
+    ExportSession,
+    /// Opens the `SessionManager`.
+    ManageSessions,
+    /// Copies a link which loads the scenario along with the inputs data.
+    CopyShareLink,
+    /// Opens an empty tab, to load another scenario.
+    NewSession,
+    SelectSession(usize),
+    CloseSession(usize),
+    /// Puts back the default values of all the inputs.
+    ResetAll,
+    ToggleStrictMode,
+    ToggleRestoreOnStartup,
+    ToggleManualRender,
+    SetLayout(Layout),
+    SetTheme(Theme),
+    SetLanguage(Language),
+    SetWebhook(Webhook),
+    /// Syncs the inputs with a WebSocket server, see `Settings::sync_url`.
+    SetSyncUrl(String),
+    /// Posts the changes of the inputs to a URL, see
+    /// `Settings::changes_webhook_url`.
+    SetChangesWebhookUrl(String),
+    /// Asks for a passphrase to encrypt the saved sessions, or stops
+    /// encrypting them.
+    ToggleEncryption,
+    SetDebounceDelay(u64),
+    SetReloadInterval(u64),
+    SetMaxAutosaveSize(u64),
 }
 
 impl NotificationSender for App {
+    const SOURCE: &'static str = "App";
+
     fn notification_bus(&mut self) -> &mut Dispatcher<NotificationBus> {
         &mut self.notification_bus
     }
@@ -75,129 +417,565 @@ impl Component for App {
     fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
         link.send_message(Msg::Init);
         let on_navevent = link.callback(Msg::NavEvent);
+        let storage = StorageService::new(Area::Local).expect("Failed to get localStorage.");
+        let settings = Settings::restore(&storage);
+        apply_theme(settings.theme);
+        i18n::set_language(settings.language);
+
+        // Don't lose the debounced save when the page is closed
+        let on_unload = link.callback(|_| Msg::Flush);
+        let unload_listener =
+            window().add_event_listener(move |_: BeforeUnloadEvent| on_unload.emit(()));
+        let router =
+            RouteAgentBridge::new(link.callback(|route| Msg::RouteChanged(Route::from(route))));
+        let mut store = StateStore::bridge(link.callback(Msg::Store));
+        store.send(StoreRequest::SetEncryption(settings.encrypt_sessions));
+        store.send(StoreRequest::SetMaxAutosaveSize(settings.max_autosave_kb));
+        store.send(StoreRequest::SetDebounceDelay(settings.debounce_delay_ms));
+        let mut sync = SyncAgent::bridge(link.callback(Msg::Sync));
+        if !settings.sync_url.is_empty() {
+            sync.send(SyncRequest::Connect(settings.sync_url.clone()));
+        }
+        let mut changes_webhook = ChangesWebhook::dispatcher();
+        if !settings.changes_webhook_url.is_empty() {
+            let request = ChangesWebhookRequest::SetUrl(settings.changes_webhook_url.clone());
+            changes_webhook.send(request);
+        }
+        let notification_actions =
+            NotificationBus::bridge(link.batch_callback(|request| match request {
+                NotificationRequest::Clicked(action) => vec![Msg::NotificationAction(action.id)],
+                NotificationRequest::New(_) => vec![],
+            }));
+        let debounce = Debounce::default();
+        js_api::expose(&link, &debounce.rendered);
 
-        Self {
+        let mut app = Self {
             link,
-            template_engine: HandlebarsEngine::new_uninit(),
-            storage: StorageService::new(Area::Local).expect("Failed to get localStorage."),
+            template_engine: Engine::new_uninit(),
+            storage,
+            save_failed: false,
+            managing_sessions: false,
             notification_bus: NotificationBus::dispatcher(),
-            state: State::Init,
+            _notification_actions: notification_actions,
+            notification_actions: BTreeMap::new(),
+            next_action_id: 0,
+            invalid_autosave: false,
+            state: SharedState::default(),
+            store,
+            sync,
+            presence: HashMap::new(),
+            changes_webhook,
+            settings,
             on_navevent,
-        }
+            timeout: TimeoutService::new(),
+            fetch_service: FetchService::new(),
+            fetch_task: None,
+            send_task: None,
+            reader: ReaderService::new(),
+            reader_task: None,
+            dragging_file: false,
+            query_handled: false,
+            starting: false,
+            link_after_restore: false,
+            prefilled_data: None,
+            prefilled_data_url: None,
+            awaiting_data: None,
+            extending: None,
+            loading: Loading::Local,
+            restoring: false,
+            saved_names: vec![],
+            interval: IntervalService::new(),
+            reload_task: None,
+            diagnostics: vec![],
+            template_editor: TemplateEditor::default(),
+            active_template: MAIN_TEMPLATE.to_owned(),
+            read_paths: vec![],
+            inputs_filter: String::new(),
+            compared_snapshot: None,
+            pinned_output: None,
+            showing_output_diff: false,
+            active_document: None,
+            output_search: (String::new(), 0),
+            debounce,
+            route: Route::Home,
+            _unload_listener: unload_listener,
+            router,
+        };
+        app.schedule_reload();
+        app
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         trace!("Received: {:?}", msg);
         match msg {
             Msg::Init => {
-                self.state = State::Init;
-                true
-            }
-            Msg::NavEvent(nav_event) => {
-                match nav_event {
-                    NavEvent::LoadDebugScenario => self.load_debug_scenario(),
-                    NavEvent::LoadFromLocalStorage => self.load_from_local_storage(),
-                    NavEvent::UnloadScenario => self.unload_scenario(),
+                self.store.send(StoreRequest::Unload);
+                if !self.query_handled {
+                    self.query_handled = true;
+                    self.starting = true;
+                    // The sessions are restored once unlocked
+                    if !self.settings.encrypt_sessions || !self.ask_passphrase() {
+                        self.restore_on_startup();
+                    }
                 }
+                true
             }
-            Msg::FetchedJsonData(json_str) => match self.load_from_json(&json_str) {
-                Ok(should_render) => should_render,
-                Err(e) => {
-                    // TODO: Better log when the log will be an enum --v
-                    let error = e.context("Failed to load the received scenario.");
-                    self.notif_error(format!("{:?}", error));
+            Msg::NavEvent(nav_event) => match nav_event {
+                NavEvent::LoadDebugScenario => self.load_debug_scenario(),
+                NavEvent::LoadFromLocalStorage => self.load_from_local_storage(),
+                NavEvent::UnloadScenario => self.unload_scenario(),
+                NavEvent::CopyShareLink => {
+                    self.copy_share_link();
                     false
                 }
-            },
-            Msg::SaveToLocalStorage => {
-                self.storage
-                    .store(LOCAL_STORAGE_KEY.as_ref(), YewJson(&self.state));
-                false
-            }
-            Msg::EditedInput(path, value) => match &mut self.state {
-                State::Loaded { inputs_data, .. } => {
-                    match inputs_data.insert_at(&path, value) {
-                        Ok(()) => self.link.send_message(Msg::SaveToLocalStorage),
-                        Err(e) => {
-                            // TODO: Show the error
-                            error!("Failed to save value of '{}': {:?}", path, e);
-                        }
-                    }
+                NavEvent::ManageSessions => {
+                    self.managing_sessions = true;
                     true
                 }
-                _ => {
-                    warn!(
-                        "Shouldn't have received a Msg::EditedInput message in state: {:?}.",
-                        self.state
-                    );
+                NavEvent::ExportSession => {
+                    self.export_session();
                     false
                 }
-            },
-            Msg::ListInputSizeChanged(path, new_size) => match &mut self.state {
-                State::Loaded { inputs_data, .. } => {
-                    if let Err(e) = inputs_data.resize_array_at(&path, new_size) {
-                        warn!("Failed to access array at '{}': {:?}", path, e);
+                NavEvent::OpenScenarioFile(file) => {
+                    self.read_scenario_file(file);
+                    false
+                }
+                NavEvent::ReloadScenario => {
+                    self.reload_scenario(true);
+                    false
+                }
+                NavEvent::LoadFromUrl(url) => {
+                    self.link.send_message(Msg::FetchScenario(url));
+                    false
+                }
+                NavEvent::NewSession => self.new_session(),
+                NavEvent::SelectSession(index) => self.select_session(index),
+                NavEvent::CloseSession(index) => self.close_session(index),
+                NavEvent::ResetAll => self.reset_all(),
+                NavEvent::ToggleStrictMode => {
+                    self.settings.strict_mode = !self.settings.strict_mode;
+                    self.settings.store(&mut self.storage);
+                    self.apply_strict_mode();
+                    true
+                }
+                NavEvent::SetWebhook(webhook) => {
+                    self.settings.webhook = webhook;
+                    self.settings.store(&mut self.storage);
+                    true
+                }
+                NavEvent::SetSyncUrl(url) => {
+                    self.sync.send(SyncRequest::Connect(url.clone()));
+                    self.settings.sync_url = url;
+                    self.settings.store(&mut self.storage);
+                    true
+                }
+                NavEvent::SetChangesWebhookUrl(url) => {
+                    let request = ChangesWebhookRequest::SetUrl(url.clone());
+                    self.changes_webhook.send(request);
+                    self.settings.changes_webhook_url = url;
+                    self.settings.store(&mut self.storage);
+                    true
+                }
+                NavEvent::SetLayout(layout) => {
+                    self.settings.layout = layout;
+                    self.settings.store(&mut self.storage);
+                    true
+                }
+                NavEvent::SetTheme(theme) => {
+                    self.settings.theme = theme;
+                    self.settings.store(&mut self.storage);
+                    apply_theme(theme);
+                    true
+                }
+                NavEvent::SetLanguage(language) => {
+                    self.settings.language = language;
+                    self.settings.store(&mut self.storage);
+                    i18n::set_language(language);
+                    // The render errors are translated
+                    self.debounce.rendered.replace(None);
+                    true
+                }
+                NavEvent::ToggleManualRender => {
+                    self.settings.manual_render = !self.settings.manual_render;
+                    self.settings.store(&mut self.storage);
+                    // Catches up with the edits made meanwhile
+                    self.debounce.rendered.replace(None);
+                    true
+                }
+                NavEvent::ToggleRestoreOnStartup => {
+                    self.settings.restore_on_startup = !self.settings.restore_on_startup;
+                    self.settings.store(&mut self.storage);
+                    true
+                }
+                NavEvent::ToggleEncryption => {
+                    if self.settings.encrypt_sessions {
+                        self.settings.encrypt_sessions = false;
+                        self.settings.store(&mut self.storage);
+                        self.store.send(StoreRequest::SetEncryption(false));
+                        self.store.send(StoreRequest::Save);
+                        self.notif_warn(tr("The open tabs are now saved unencrypted. The \
+                                            sessions saved under a name stay encrypted until \
+                                            they are saved again."));
+                    } else {
+                        self.ask_passphrase();
                     }
-
-                    self.link.send_message(Msg::SaveToLocalStorage);
                     true
                 }
-                _ => {
-                    warn!(
-                        "Shouldn't have received a Msg::ListInputSizeChanged message in state: \
-                         {:?}.",
-                        self.state
-                    );
-                    false
+                NavEvent::SetDebounceDelay(delay) => {
+                    self.settings.debounce_delay_ms = delay;
+                    self.settings.store(&mut self.storage);
+                    self.store.send(StoreRequest::SetDebounceDelay(delay));
+                    true
+                }
+                NavEvent::SetMaxAutosaveSize(max_kb) => {
+                    self.settings.max_autosave_kb = max_kb;
+                    self.settings.store(&mut self.storage);
+                    self.store.send(StoreRequest::SetMaxAutosaveSize(max_kb));
+                    // Reports the next failures
+                    self.save_failed = false;
+                    true
+                }
+                NavEvent::SetReloadInterval(interval) => {
+                    self.settings.reload_interval_s = interval;
+                    self.settings.store(&mut self.storage);
+                    self.schedule_reload();
+                    true
                 }
             },
-            Msg::RemoveAt(path) => match &mut self.state {
-                State::Loaded { inputs_data, .. } => {
-                    if let Err(e) = inputs_data.remove_at(&path) {
-                        warn!("Failed to remove at '{}': {:?}", path, e);
+            Msg::FetchScenario(url) => {
+                self.loading = Loading::Local;
+                if self.fetch_scenario(url.clone(), Msg::FetchedScenario) {
+                    self.notif_info(tr!("Loading the scenario at {}...", url));
+                }
+                true
+            }
+            Msg::FetchScenarioFailed(error) => {
+                self.fetch_task = None;
+                self.prefilled_data = None;
+                self.prefilled_data_url = None;
+                self.extending = None;
+                self.awaiting_data = None;
+                if let Loading::Reload(_) = std::mem::replace(&mut self.loading, Loading::Local) {
+                    // Don't repeat the error at every interval
+                    if self.reload_task.take().is_some() {
+                        self.notif_warn(tr("Stopped reloading the scenario, change the reload \
+                                            interval to restart."));
                     }
-
-                    self.link.send_message(Msg::SaveToLocalStorage);
-                    true
                 }
-                _ => {
-                    warn!(
-                        "Shouldn't have received a Msg::RemoveAt message in state: {:?}.",
-                        self.state
-                    );
+                self.notif_error(error);
+                true
+            }
+            Msg::DraggingFile(dragging) => {
+                let changed = self.dragging_file != dragging;
+                self.dragging_file = dragging;
+                changed
+            }
+            Msg::DroppedFile(file) => {
+                self.dragging_file = false;
+                self.read_scenario_file(file);
+                true
+            }
+            Msg::ReadFile(file_data) => {
+                self.reader_task = None;
+                match String::from_utf8(file_data.content) {
+                    Ok(source) => self
+                        .link
+                        .send_message(Msg::FetchedScenario(source, Some(file_data.name))),
+                    Err(_) => self.notif_error(tr!("{} isn't a text file.", file_data.name)),
+                }
+                // Hides the progress, see `pending_activity`
+                true
+            }
+            Msg::CopiedShareLink(Ok(())) => {
+                self.notif_success(tr("Copied the share link to the clipboard."));
+                false
+            }
+            Msg::CopiedShareLink(Err(e)) => {
+                self.notif_error(tr!("Failed to copy the share link: {}", e));
+                false
+            }
+            Msg::CopyOutput => {
+                if let Some(output) = self.shown_output() {
+                    clipboard::write_text(&output, self.link.callback(Msg::CopiedOutput));
+                }
+                false
+            }
+            Msg::SelectDocument(index) => {
+                self.active_document = index;
+                true
+            }
+            Msg::Render => {
+                self.debounce.rendered.replace(None);
+                true
+            }
+            Msg::SendOutput => {
+                self.send_output();
+                true
+            }
+            Msg::SentOutput(result) => {
+                self.send_task = None;
+                match result {
+                    Ok(status) => self.notif_success(tr!("Sent the output: {}.", status)),
+                    Err(e) => self.notif_error(tr!("Failed to send the output: {}", e)),
+                }
+                true
+            }
+            Msg::PinOutput => {
+                self.pinned_output = self.shown_output();
+                self.showing_output_diff = true;
+                true
+            }
+            Msg::UnpinOutput => {
+                self.pinned_output = None;
+                self.showing_output_diff = false;
+                true
+            }
+            Msg::SearchOutput(query) => {
+                self.output_search = (query, 0);
+                scroll_to_current_match();
+                true
+            }
+            Msg::NextMatch | Msg::PreviousMatch => {
+                let count = match self.shown_output() {
+                    Some(output) => find_matches(&output, &self.output_search.0).len(),
+                    None => 0,
+                };
+                if count == 0 {
+                    return false;
+                }
+                let current = &mut self.output_search.1;
+                *current = match msg {
+                    Msg::NextMatch => (*current + 1) % count,
+                    _ => (*current + count - 1) % count,
+                };
+                scroll_to_current_match();
+                true
+            }
+            Msg::ToggleData => {
+                self.settings.show_data = !self.settings.show_data;
+                self.settings.store(&mut self.storage);
+                true
+            }
+            Msg::ToggleOutputDiff => {
+                self.showing_output_diff = !self.showing_output_diff;
+                true
+            }
+            Msg::CopiedOutput(Ok(())) => {
+                self.notif_success(tr("Copied the rendered template to the clipboard."));
+                false
+            }
+            Msg::CopiedOutput(Err(e)) => {
+                self.notif_error(tr!("Failed to copy the rendered template: {}", e));
+                false
+            }
+            Msg::SessionEvent(event) => self.on_session_event(event),
+            Msg::DismissDiagnostics => {
+                self.diagnostics.clear();
+                true
+            }
+            Msg::FetchedScenario(source, name) => {
+                let result = self.load_from_str(&source, name.as_deref());
+                self.on_scenario_loaded(result)
+            }
+            Msg::FetchedBase(source, url) => {
+                self.fetch_task = None;
+                let (extending, depth) = match self.extending.take() {
+                    Some(extending) => extending,
+                    None => return false,
+                };
+                let result = Format::detect(&source, url.as_deref())
+                    .parse(&source)
+                    .map_err(anyhow::Error::from)
+                    .and_then(scenario::migrate)
+                    .and_then(|base| scenario::extend(base, extending))
+                    .and_then(|merged| self.load_scenario(merged, depth + 1));
+                self.on_scenario_loaded(result)
+            }
+            Msg::FetchedData(data) => {
+                self.fetch_task = None;
+                let (scenario, source_url, example) = match self.awaiting_data.take() {
+                    Some(awaiting) => awaiting,
+                    None => return false,
+                };
+                // The scenario is still opened, with its default values
+                let data = data.map_err(|error| self.notif_error(error)).ok();
+                let result = self.open_scenario(scenario, source_url, example, data);
+                self.on_scenario_loaded(result)
+            }
+            Msg::PollScenario => {
+                self.reload_scenario(false);
+                false
+            }
+            Msg::ReloadedScenario(source, url) => {
+                self.fetch_task = None;
+                self.extending = None;
+                self.awaiting_data = None;
+                let result = Format::detect(&source, url.as_deref())
+                    .parse(&source)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|json_data| self.load_scenario(json_data, 0));
+                self.on_scenario_loaded(result)
+            }
+            Msg::ExportAutosave(state) => {
+                match serde_json::to_string_pretty(&state) {
+                    Ok(json) => download("autosave.json", &json),
+                    Err(e) => self.notif_error(tr!("Failed to export the autosave: {}", e)),
+                }
+                false
+            }
+            Msg::DiscardAutosave => {
+                if self.invalid_autosave {
+                    self.invalid_autosave = false;
+                    self.store.send(StoreRequest::RemoveAutosave);
+                    self.notif_info(tr("Discarded the invalid autosave."));
+                }
+                false
+            }
+            Msg::Store(output) => self.on_store_output(output),
+            Msg::Sync(SyncOutput::Presence(presence)) => self.presence.neq_assign(presence),
+            Msg::EditedTemplate(template) => {
+                self.template_editor.pending = Some(template);
+                self.template_editor.compile_task = Some(self.timeout.spawn(
+                    TEMPLATE_COMPILE_DEBOUNCE,
+                    self.link.callback(|_| Msg::CompileTemplate),
+                ));
+                false
+            }
+            Msg::CompileTemplate => self.compile_edited_template(),
+            Msg::SelectTemplate(name) => {
+                // Don't lose what was typed for the previous template
+                self.compile_edited_template();
+                self.active_template = name;
+                self.debounce.rendered.replace(None);
+                self.watch_read_paths();
+                self.active_document = None;
+                self.pinned_output = None;
+                self.showing_output_diff = false;
+                true
+            }
+            Msg::DebouncedRender => {
+                self.debounce.render_task = None;
+                true
+            }
+            Msg::Flush => {
+                // The pending render is done once saved, see `Change::Flushed`
+                self.store.send(StoreRequest::Flush);
+                false
+            }
+            Msg::NotificationAction(id) => match self.notification_actions.remove(&id) {
+                Some(msg) => self.update(msg),
+                None => {
+                    debug!("Ignored the action {} of an old notification.", id);
                     false
                 }
             },
+            Msg::Navigate(route) => {
+                self.router.send(RouteRequest::ChangeRoute(route.into()));
+                false
+            }
+            Msg::RouteChanged(route) => self.apply_route(route),
+            Msg::FilterInputs(filter) => {
+                self.inputs_filter = filter;
+                true
+            }
+            Msg::UndoRemove(session, path, value) => {
+                self.store
+                    .send(StoreRequest::UndoRemove(session, path, value));
+                false
+            }
+            Msg::TakeSnapshot => self.take_snapshot(),
+            Msg::RestoreSnapshot(index) => self.restore_snapshot(index),
+            Msg::CompareSnapshot(index) => {
+                self.compared_snapshot = index;
+                true
+            }
+            Msg::DeleteSnapshot(index) => {
+                self.store.send(StoreRequest::DeleteSnapshot(index));
+                self.compared_snapshot = match self.compared_snapshot {
+                    Some(compared) if compared == index => None,
+                    Some(compared) if compared > index => Some(compared - 1),
+                    compared => compared,
+                };
+                true
+            }
         }
     }
 
     fn view(&self) -> Html {
-        let state_html = match &self.state {
-            State::Init => {
+        let state = self.state.get();
+        let state_html = match state.active() {
+            _ if self.route == Route::Gallery => {
+                html! {
+                    <div class="box">
+                        <h1 class="title">{ tr("Example scenarios") }</h1>
+                        <ScenarioGallery on_select=self.link.callback(|name| Msg::Navigate(Route::Scenario(name))) />
+                    </div>
+                }
+            }
+            Session::Init => {
                 html! {
                     <div class="box">
-                        <p>{ "Nothing loaded. Use a button above." }</p>
+                        <p class="block">{ tr("Nothing loaded. Start from an example, or open your own scenario from the menu above.") }</p>
+                        <ScenarioGallery on_select=self.link.callback(|name| Msg::Navigate(Route::Scenario(name))) />
                     </div>
                 }
             }
-            State::Loaded {
+            Session::Loaded {
                 scenario,
                 inputs_data,
+                step,
+                snapshots,
+                toggled_groups,
                 ..
             } => {
-                html! {
-                    <div class="columns is-desktop">
-                        <div class="column">
-                            { render_inputs(&scenario.inputs, inputs_data, &self.link) }
-                        </div>
-                        <div class="column">
-                            { render_code_column(inputs_data, &self.template_engine) }
-                        </div>
+                let inputs_column = html! {
+                    <div class="column">
+                        { render_scenario_header(scenario) }
+                        { self.render_inputs(&state, scenario, inputs_data, *step, toggled_groups) }
+                        { render_snapshots(snapshots, inputs_data, self.compared_snapshot, &self.link) }
                     </div>
+                };
+                let code_column = html! {
+                    <div class="column">
+                        { render_template_editor(scenario, &self.active_template, &self.template_editor, &self.link) }
+                        { self.render_code_column(&state, scenario, inputs_data) }
+                        { self.render_data(scenario, inputs_data) }
+                    </div>
+                };
+                match self.settings.layout {
+                    Layout::Split => html! {
+                        <div class="columns is-desktop">{ inputs_column }{ code_column }</div>
+                    },
+                    Layout::InputsOnly => html! { <div class="columns">{ inputs_column }</div> },
+                    Layout::OutputOnly => html! { <div class="columns">{ code_column }</div> },
                 }
             }
         };
 
+        let on_drag_over = self.link.batch_callback(|event: DragOverEvent| {
+            if is_file_drag(&event) {
+                // Allows the drop
+                event.prevent_default();
+                vec![Msg::DraggingFile(true)]
+            } else {
+                vec![]
+            }
+        });
+        let on_drop = self.link.batch_callback(|event: DragDropEvent| {
+            let file = match event.data_transfer() {
+                Some(data_transfer) => data_transfer.files().iter().next(),
+                None => None,
+            };
+            match file {
+                Some(file) => {
+                    // Prevents the browser from opening the file
+                    event.prevent_default();
+                    vec![Msg::DroppedFile(file)]
+                }
+                None => vec![Msg::DraggingFile(false)],
+            }
+        });
+
         html! {
             <>
                 <Notifications />
@@ -205,20 +983,30 @@ impl Component for App {
                 <div class="section">
                     <div class="container navbar-container">
                         <div class="box">
-                            <Navbar on_navevent=&self.on_navevent />
+                            <Navbar
+                                on_navevent=&self.on_navevent
+                                settings=&self.settings
+                                sessions=state.titles()
+                                active_session=state.active
+                                settings_open=self.route == Route::Settings
+                                always_strict=self.template_engine.always_strict() />
                         </div>
                     </div>
                 </div>
 
                 <div class="section site-content">
-                    <div class="container">
+                    <div class="container" ondragover=on_drag_over ondrop=on_drop>
+                        { self.render_progress() }
+                        { self.render_diagnostics() }
                         { state_html }
+                        { self.render_drop_overlay() }
+                        { self.render_session_manager() }
                     </div>
                 </div>
 
                 <footer class="footer">
                     <div class="content has-text-centered">
-                        <p>{ "Wonderful footer" }</p>
+                        <p>{ tr("Wonderful footer") }</p>
                     </div>
                 </footer>
             </>
@@ -227,104 +1015,1771 @@ impl Component for App {
 }
 
 impl App {
-    fn load_from_json(&mut self, json_str: &str) -> Result<ShouldRender> {
-        let mut json_data: JsonValue = serde_json::from_str(&json_str).context("Invalid JSON.")?;
-        let template = serde_json::from_value(json_data["template"].take())
-            .context("JSON input must have a template.")?;
+    /// Loads a scenario written in JSON or in YAML, see `Format::detect`.
+    fn load_from_str(&mut self, source: &str, name: Option<&str>) -> Result<ShouldRender> {
+        // Cancels the download of another scenario, if any
+        self.fetch_task = None;
 
-        let inputs = serde_json::from_value(json_data["inputs"].take())
-            .context("Failed to deserialize inputs")?;
+        let json_data = Format::detect(source, name).parse(source)?;
+        // A session exported by `export_session`
+        if json_data.get("Loaded").is_some() {
+            self.open_session(migrate_session(json_data)?);
+            self.diagnostics.clear();
+            self.notif_success(tr("Imported the session."));
+            return Ok(true);
+        }
+        self.extending = None;
+        self.awaiting_data = None;
+        self.loading = match name {
+            Some(url) if url.contains("://") => Loading::Remote(url.to_owned()),
+            _ => Loading::Local,
+        };
+        self.load_scenario(json_data, 0)
+    }
 
-        self.template_engine
-            .set_template(&template)
-            .map_err(|e| e.context("Failed to load the template"))?;
+    /// Loads a scenario once the scenarios it extends are merged in it,
+    /// which may need to download them first. `depth` is the number of bases
+    /// already merged.
+    fn load_scenario(&mut self, json_data: JsonValue, mut depth: usize) -> Result<ShouldRender> {
+        let mut json_data = scenario::migrate(json_data)?;
+        while let Some(base) = scenario::base_of(&json_data).map(str::to_owned) {
+            if depth >= MAX_BASES {
+                bail!(tr(
+                    "Too many scenarios extend each other, maybe one of them extends itself."
+                ));
+            }
+            if base.contains("://") {
+                // Loading continues on `Msg::FetchedBase`
+                self.extending = Some((json_data, depth));
+                if self.fetch_scenario(base.clone(), Msg::FetchedBase) {
+                    self.notif_info(tr!("Loading the base scenario at {}...", base));
+                }
+                return Ok(false);
+            }
+
+            let example = find_example(&base).ok_or_else(|| {
+                anyhow!(
+                    "Unknown base scenario '{}', expected a URL or the name of an example.",
+                    base
+                )
+            })?;
+            let base = Format::detect(example.source, None).parse(example.source)?;
+            json_data = scenario::extend(scenario::migrate(base)?, json_data)?;
+            depth += 1;
+        }
+        if json_data.get("template").is_none() {
+            let error =
+                AppError::ScenarioParse(tr("The scenario must have a template.").to_owned());
+            return Err(error.into());
+        }
+
+        self.diagnostics = scenario::validate(&json_data);
+        if self.diagnostics.iter().any(Diagnostic::is_error) {
+            self.notif_error(tr(
+                "The scenario has errors, see their details above the inputs."
+            ));
+            return Ok(true);
+        }
 
-        self.state = State::Loaded {
-            scenario: Scenario { template, inputs },
-            inputs_data: InputsData::default(),
+        let mut scenario: Scenario = serde_json::from_value(json_data).map_err(|e| {
+            AppError::ScenarioParse(format!(
+                "{}: {}",
+                tr("Failed to deserialize the scenario"),
+                e
+            ))
+        })?;
+        scenario.generate_inputs()?;
+
+        let (source_url, example) = match std::mem::replace(&mut self.loading, Loading::Local) {
+            Loading::Reload(url) => {
+                self.store.send(StoreRequest::Reload(scenario, url));
+                return Ok(false);
+            }
+            Loading::Remote(url) => (Some(url), None),
+            Loading::Example(name) => (None, Some(name)),
+            Loading::Local => (None, None),
         };
-        self.link.send_message(Msg::SaveToLocalStorage);
+
+        let data_url = self.prefilled_data_url.take();
+        match data_url.or_else(|| scenario.data_url.clone()) {
+            Some(data_url) => {
+                // Loading continues on `Msg::FetchedData`
+                self.awaiting_data = Some((scenario, source_url, example));
+                if self.fetch_data(data_url.clone()) {
+                    self.notif_info(tr!("Loading the inputs data at {}...", data_url));
+                }
+                Ok(false)
+            }
+            None => self.open_scenario(scenario, source_url, example, None),
+        }
+    }
+
+    /// Opens a loaded scenario in a tab, with the inputs data downloaded
+    /// from its `data_url`, and then the one of the link, merged into its
+    /// default values.
+    fn open_scenario(
+        &mut self,
+        scenario: Scenario,
+        source_url: Option<String>,
+        example: Option<String>,
+        fetched_data: Option<JsonValue>,
+    ) -> Result<ShouldRender> {
+        let mut inputs_data = initial_inputs_data(&scenario)?;
+        let prefilled_data: Vec<_> = fetched_data
+            .into_iter()
+            .chain(self.prefilled_data.take())
+            .collect();
+        if !prefilled_data.is_empty() {
+            for data in prefilled_data {
+                inputs_data.merge_at(&Path::default(), data, ArrayMerge::Replace)?;
+            }
+            clear_hidden_inputs(&scenario.inputs, &mut inputs_data);
+            clear_unavailable_selections(&scenario.inputs, &mut inputs_data);
+            update_computed_inputs(&scenario.inputs, &mut inputs_data);
+        }
+        self.open_session(Session::Loaded {
+            scenario,
+            inputs_data,
+            step: 0,
+            source_url,
+            example,
+            snapshots: vec![],
+            toggled_groups: HashSet::new(),
+        });
 
         Ok(true)
     }
 
-    fn load_debug_scenario(&mut self) -> ShouldRender {
-        let json_str = JSON_INPUT.replace("%TEMPLATE%", &INPUT_TEMPLATE.replace("\n", "\\n"));
-        self.link.send_message(Msg::FetchedJsonData(json_str));
-        false
+    fn on_scenario_loaded(&mut self, result: Result<ShouldRender>) -> ShouldRender {
+        match result {
+            Ok(should_render) => should_render,
+            Err(e) => {
+                let error = e.context(tr("Failed to load the received scenario."));
+                self.notif_error_chain(error);
+                // Hides the progress, see `pending_activity`
+                true
+            }
+        }
     }
 
-    fn load_from_local_storage(&mut self) -> ShouldRender {
-        if let YewJson(Ok(restored_state)) = self.storage.restore(LOCAL_STORAGE_KEY.as_ref()) {
-            self.state = restored_state;
-
-            // Initialize the template engine with the deserialized template.
-            // This can fail if the restored state is somewhat invalid.
-            if let State::Loaded { scenario, .. } = &self.state {
-                if let Err(e) = self.template_engine.set_template(&scenario.template) {
-                    self.storage.remove(LOCAL_STORAGE_KEY.as_ref());
-                    self.state = State::Init;
-                    self.link.send_message(Msg::Init);
+    /// Loads the session of a share link, see `copy_share_link`. Returns
+    /// whether the page URL had one.
+    fn load_from_fragment(&mut self) -> bool {
+        let fragment = window()
+            .location()
+            .and_then(|location| location.hash().ok());
+        let encoded = match fragment
+            .as_deref()
+            .and_then(|fragment| fragment.strip_prefix(share::FRAGMENT_PREFIX))
+        {
+            Some(encoded) => encoded.to_owned(),
+            None => return false,
+        };
+
+        // Reloading the page mustn't replace the changes made since
+        js! { @(no_return)
+            history.replaceState(null, "", location.pathname + location.search);
+        }
+        match share::decode_session(&encoded) {
+            Ok(json) => self.link.send_message(Msg::FetchedScenario(json, None)),
+            Err(e) => {
+                let error = e.context(tr("Failed to load the shared session."));
+                self.notif_error_chain(error);
+            }
+        }
+        true
+    }
 
-                    self.notif_error(format!(
-                        "Invalid template fetched from local storage: {}",
-                        e
-                    ));
+    /// Loads the scenario given by `?scenario=<url>`, with the inputs data
+    /// given by `?data=<base64>` or downloaded from `?data_url=<url>`.
+    fn load_from_query(&mut self) {
+        let url = match query_param("scenario") {
+            Some(url) => url,
+            None => {
+                if query_param("data").is_some() || query_param("data_url").is_some() {
+                    self.notif_warn(tr("Ignored the data of the link, which has no scenario."));
                 }
+                return;
             }
+        };
 
-            if let State::Init = self.state {
-                // No notification
-            } else {
-                self.notif_success("Restored previous session.");
+        if let Some(data) = query_param("data") {
+            match decode_base64_json(&data) {
+                Ok(data) if data.is_object() => self.prefilled_data = Some(data),
+                Ok(_) => self.notif_error(tr("Ignored the data of the link: expected an object.")),
+                Err(e) => {
+                    let error = e.context(tr("Ignored the invalid data of the link."));
+                    self.notif_error_chain(error);
+                }
             }
+        }
+        self.prefilled_data_url = query_param("data_url").filter(|url| !url.trim().is_empty());
+        self.link.send_message(Msg::FetchScenario(url));
+    }
 
-            true
-        } else {
-            // If we're here, local storage is either absent or invalid
-            self.notif_warn("Nothing to restore from local storage.");
-            self.storage.remove(LOCAL_STORAGE_KEY.as_ref());
-            self.link.send_message(Msg::Init);
-            false
+    /// Downloads a scenario, which is sent to `on_fetched` along with its
+    /// URL. Returns whether the download started.
+    fn fetch_scenario(
+        &mut self,
+        url: String,
+        on_fetched: fn(String, Option<String>) -> Msg,
+    ) -> bool {
+        let url = scenario::github::raw_url(&url).into_owned();
+        trace!("Fetching a scenario from: {}", url);
+        let url_inner = url.clone();
+        let callback = self.link.callback(move |response: Response<Text>| {
+            let (meta, body) = response.into_parts();
+            match body {
+                Ok(source) if meta.status.is_success() => {
+                    on_fetched(source, Some(url_inner.clone()))
+                }
+                Ok(_) => Msg::FetchScenarioFailed(tr!(
+                    "Failed to load the scenario at {}: the server responded {}.",
+                    url_inner,
+                    meta.status
+                )),
+                // A request blocked by the browser doesn't have a status, which
+                // Yew reports as a timeout
+                Err(_) => Msg::FetchScenarioFailed(tr!(
+                    "Failed to load the scenario at {}: the server is unreachable or doesn't \
+                     allow cross-origin requests (CORS).",
+                    url_inner
+                )),
+            }
+        });
+        let task = Request::get(&url)
+            .body(Nothing)
+            .map_err(|e| e.to_string())
+            .and_then(|request| {
+                self.fetch_service
+                    .fetch(request, callback)
+                    .map_err(ToString::to_string)
+            });
+        match task {
+            Ok(task) => {
+                self.fetch_task = Some(task);
+                true
+            }
+            Err(e) => {
+                self.notif_error(tr!("Failed to load the scenario at {}: {}", url, e));
+                false
+            }
         }
     }
 
-    fn unload_scenario(&mut self) -> ShouldRender {
-        self.link.send_message(Msg::Init);
-        false
+    /// Downloads inputs data, which is sent as `Msg::FetchedData`. Returns
+    /// whether the download started.
+    fn fetch_data(&mut self, url: String) -> bool {
+        trace!("Fetching inputs data from: {}", url);
+        let url_inner = url.clone();
+        let callback = self.link.callback(move |response: Response<Text>| {
+            let (meta, body) = response.into_parts();
+            let data = match body {
+                Ok(source) if meta.status.is_success() => {
+                    match serde_json::from_str::<JsonValue>(&source) {
+                        Ok(data) if data.is_object() => Ok(data),
+                        Ok(_) => Err(tr!(
+                            "Ignored the inputs data at {}: expected an object.",
+                            url_inner
+                        )),
+                        Err(e) => Err(tr!(
+                            "Ignored the invalid inputs data at {}: {}",
+                            url_inner,
+                            e
+                        )),
+                    }
+                }
+                Ok(_) => Err(tr!(
+                    "Failed to load the inputs data at {}: the server responded {}.",
+                    url_inner,
+                    meta.status
+                )),
+                Err(_) => Err(tr!(
+                    "Failed to load the inputs data at {}: the server is unreachable or doesn't \
+                     allow cross-origin requests (CORS).",
+                    url_inner
+                )),
+            };
+            Msg::FetchedData(data)
+        });
+        let task = Request::get(&url)
+            .body(Nothing)
+            .map_err(|e| e.to_string())
+            .and_then(|request| {
+                self.fetch_service
+                    .fetch(request, callback)
+                    .map_err(ToString::to_string)
+            });
+        match task {
+            Ok(task) => {
+                self.fetch_task = Some(task);
+                true
+            }
+            Err(e) => {
+                self.link.send_message(Msg::FetchedData(Err(tr!(
+                    "Failed to load the inputs data at {}: {}",
+                    url,
+                    e
+                ))));
+                false
+            }
+        }
     }
-}
 
-fn render_inputs(
-    inputs: &[InputTypes],
-    inputs_data: &InputsData,
-    link: &ComponentLink<App>,
-) -> Html {
-    use crate::views::RenderableInput;
+    /// Downloads the scenario of the active tab again, if it was loaded from
+    /// a URL. See `State::reload`.
+    fn reload_scenario(&mut self, manual: bool) {
+        let source_url = match self.state.get().active() {
+            Session::Loaded { source_url, .. } => source_url.clone(),
+            Session::Init => None,
+        };
+        let url = match source_url {
+            Some(url) => url,
+            None => {
+                if manual {
+                    self.notif_warn(tr("Only a scenario loaded from a URL can be reloaded."));
+                }
+                return;
+            }
+        };
+        // Don't cancel the loading of another scenario
+        if self.fetch_task.is_some() {
+            return;
+        }
 
-    html! {
-        <div class="box">
-            <h1 class="title">{ "Inputs" }</h1>
-            { for inputs.iter().map(|input| input.render(&Path::default(), inputs_data, link)) }
-        </div>
+        self.loading = Loading::Reload(url.clone());
+        if self.fetch_scenario(url.clone(), Msg::ReloadedScenario) && manual {
+            self.notif_info(tr!("Reloading the scenario at {}...", url));
+        }
+    }
+
+    /// Starts polling the URL of the scenario, or stops it, according to the
+    /// settings.
+    fn schedule_reload(&mut self) {
+        self.reload_task = match self.settings.reload_interval_s {
+            0 => None,
+            interval => Some(self.interval.spawn(
+                Duration::from_secs(interval),
+                self.link.callback(|_| Msg::PollScenario),
+            )),
+        };
     }
-}
 
-fn render_code_column<T: TemplateEngine>(inputs_data: &InputsData, template_engine: &T) -> Html {
-    let rendered = template_engine
-        .render(inputs_data)
-        .unwrap_or_else(|e| e.context("Failed to render the data").to_string());
+    fn read_scenario_file(&mut self, file: File) {
+        let name = file.name();
+        let extension = name.rsplit('.').next().unwrap_or_default().to_lowercase();
+        if !matches!(extension.as_str(), "json" | "yaml" | "yml") {
+            self.notif_error(tr!("{} isn't a JSON or YAML scenario.", name));
+            return;
+        }
 
-    html! {
-        <>
-            <div class="box">
-                <h1 class="title">{ "Rendered template" }</h1>
-                <pre>{rendered}</pre>
-            </div>
-            <div class="box">
-                <h1 class="title">{ "Data" }</h1>
-                <pre>{ format!("{:#}", inputs_data) }</pre>
-            </div>
-        </>
+        let callback = self.link.callback(Msg::ReadFile);
+        match self
+            .reader
+            .read_file(file, callback)
+            .map_err(ToString::to_string)
+        {
+            Ok(task) => self.reader_task = Some(task),
+            Err(e) => self.notif_error(tr!("Failed to read {}: {}", name, e)),
+        }
+    }
+
+    fn load_debug_scenario(&mut self) -> ShouldRender {
+        let json_str = JSON_INPUT.replace("%TEMPLATE%", &INPUT_TEMPLATE.replace("\n", "\\n"));
+        self.link.send_message(Msg::FetchedScenario(json_str, None));
+        false
+    }
+
+    /// Asks for the passphrase encrypting the saved sessions. They aren't
+    /// saved while it isn't entered. Returns whether it was entered.
+    fn ask_passphrase(&mut self) -> bool {
+        let passphrase =
+            js! { return prompt(@{tr("Passphrase of the saved sessions:")}); }.into_string();
+        match passphrase {
+            Some(passphrase) if !passphrase.is_empty() => {
+                self.store.send(StoreRequest::Unlock(passphrase));
+                true
+            }
+            _ => {
+                if self.settings.encrypt_sessions {
+                    self.notif_warn(tr("The sessions are encrypted: they won't be saved nor \
+                                        restored until their passphrase is entered. Reload \
+                                        the page to enter it."));
+                    // Instead of reporting every autosave, see `SaveError::Locked`
+                    self.save_failed = true;
+                }
+                false
+            }
+        }
+    }
+
+    /// Restores the tabs of the previous visit if enabled, then loads the
+    /// scenario of the link the app was opened with, if any.
+    fn restore_on_startup(&mut self) {
+        if self.settings.restore_on_startup {
+            self.store.send(StoreRequest::RestoreAutosave);
+            self.restoring = true;
+        } else {
+            self.starting = false;
+            self.load_from_link();
+        }
+    }
+
+    fn load_from_link(&mut self) {
+        if !self.load_from_fragment() {
+            self.load_from_query();
+        }
+        // The page of a deep link, eg. `/scenario/nginx`
+        self.router.send(RouteRequest::GetCurrentRoute);
+    }
+
+    /// Shows the page of a route, opening the example of a `Route::Scenario`
+    /// unless a tab already shows it. The home page is the one of the active
+    /// tab.
+    fn apply_route(&mut self, route: Route) -> ShouldRender {
+        let route = match route {
+            Route::Home => {
+                let active_route = self.active_route();
+                if active_route != Route::Home {
+                    let route = active_route.clone().into();
+                    self.router
+                        .send(RouteRequest::ReplaceRouteNoBroadcast(route));
+                }
+                active_route
+            }
+            route => route,
+        };
+        self.route = route.clone();
+        let name = match route {
+            Route::Scenario(name) => name,
+            _ => return true,
+        };
+        let opened = self.state.get().sessions.iter().position(|session| {
+            matches!(session, Session::Loaded { example: Some(example), .. } if *example == name)
+        });
+        match opened {
+            Some(index) => {
+                self.select_session(index);
+            }
+            None => {
+                let result = self.load_example(&name);
+                if result.is_err() {
+                    self.route = Route::Home;
+                    let route = Route::Home.into();
+                    self.router
+                        .send(RouteRequest::ReplaceRouteNoBroadcast(route));
+                }
+                self.on_scenario_loaded(result);
+            }
+        }
+        true
+    }
+
+    /// The route of the active tab, so that it can be linked to.
+    fn active_route(&self) -> Route {
+        match self.state.get().active() {
+            Session::Loaded {
+                example: Some(name),
+                ..
+            } => Route::Scenario(name.clone()),
+            _ => Route::Home,
+        }
+    }
+
+    /// Changes the page URL to the route of the active tab, after the user
+    /// switched to another tab or opened a scenario.
+    fn sync_route(&mut self) {
+        let route = self.active_route();
+        if route != self.route {
+            self.router
+                .send(RouteRequest::ChangeRouteNoBroadcast(route.clone().into()));
+            self.route = route;
+        }
+    }
+
+    /// Opens an example scenario, see `find_example`.
+    fn load_example(&mut self, name: &str) -> Result<ShouldRender> {
+        let example = find_example(name)
+            .ok_or_else(|| anyhow!(tr!("Unknown example scenario '{}'.", name)))?;
+        self.fetch_task = None;
+        self.extending = None;
+        self.awaiting_data = None;
+        self.loading = Loading::Example(name.to_owned());
+        let json_data = Format::detect(example.source, None).parse(example.source)?;
+        self.load_scenario(json_data, 0)
+    }
+
+    fn load_from_local_storage(&mut self) -> ShouldRender {
+        self.store.send(StoreRequest::RestoreAutosave);
+        self.restoring = true;
+        true
+    }
+
+    /// Reports whether the autosaved tabs replaced the open ones. On
+    /// startup, nothing is reported when nothing was saved, and the scenario
+    /// of the link is then loaded in a new tab.
+    fn on_restored_autosave(&mut self, result: Result<(), RestoreError>) {
+        self.restoring = false;
+        let on_startup = std::mem::take(&mut self.starting);
+        match result {
+            Ok(()) => {
+                self.diagnostics.clear();
+                if on_startup {
+                    // The link may open a tab which is among the restored ones
+                    self.link_after_restore = true;
+                    return;
+                }
+                self.notif_success(tr("Restored previous session."));
+            }
+            // Kept in the storage, to be restored by a newer version or with
+            // the passphrase
+            Err(RestoreError::Kept(e)) => {
+                let error = anyhow!(e).context(tr("Failed to restore the previous session."));
+                self.notif_error_chain(error);
+            }
+            // Kept until the user exports or discards it, eg. if it was edited
+            Err(RestoreError::Invalid(e, raw_state)) => {
+                self.invalid_autosave = true;
+                let export = Msg::ExportAutosave(raw_state);
+                let export = self.notification_action(tr("Export raw"), export);
+                let discard = self.notification_action(tr("Discard"), Msg::DiscardAutosave);
+                let error =
+                    anyhow!(e).context(tr("The previous session is invalid, and wasn't restored."));
+                let notification = Notification::from_error(&error, Self::SOURCE);
+                self.notify(notification.with_action(export).with_action(discard));
+            }
+            Err(RestoreError::Corrupt(e)) => {
+                let error =
+                    anyhow!(e).context(tr("The previous session was corrupt, and was discarded."));
+                self.notif_error_chain(error);
+                if !on_startup {
+                    self.link.send_message(Msg::Init);
+                }
+            }
+            // If we're here, local storage is either absent or unreadable
+            Err(RestoreError::Missing) => {
+                if !on_startup {
+                    self.notif_warn(tr("Nothing to restore from local storage."));
+                    self.link.send_message(Msg::Init);
+                }
+            }
+        }
+        if on_startup {
+            self.load_from_link();
+        }
+    }
+
+    /// Reports whether the sessions saved under a name replaced the open
+    /// ones.
+    fn on_loaded_session(&mut self, name: String, result: Result<(), String>) {
+        self.restoring = false;
+        match result {
+            Ok(()) => {
+                self.diagnostics.clear();
+                self.managing_sessions = false;
+                self.notif_success(tr!("Loaded the session '{}'.", name));
+            }
+            Err(e) => self.notif_error(e),
+        }
+    }
+
+    /// The passphrase of the saved sessions was checked, see
+    /// `Settings::encrypt_sessions`. On startup, the sessions are then
+    /// restored.
+    fn on_unlocked(&mut self, result: Result<(), UnlockError>) {
+        if result.is_ok() {
+            // Reports the next failures
+            self.save_failed = false;
+        }
+        match result {
+            Ok(()) if !self.settings.encrypt_sessions => {
+                self.settings.encrypt_sessions = true;
+                self.settings.store(&mut self.storage);
+                self.store.send(StoreRequest::SetEncryption(true));
+                self.notif_success(tr("The saved sessions are now encrypted."));
+                // Replaces the unencrypted one
+                self.store.send(StoreRequest::Save);
+            }
+            Ok(()) => {}
+            // Turning the encryption on again with another passphrase
+            Err(UnlockError::WrongPassphrase)
+                if !self.settings.encrypt_sessions && !self.starting =>
+            {
+                let question = tr(
+                    "This isn't the passphrase of the sessions encrypted before. Forget it, and \
+                     choose a new one? The sessions still encrypted with it can't be loaded \
+                     anymore.",
+                );
+                if window().confirm(question) {
+                    self.store.send(StoreRequest::ForgetPassphrase);
+                    self.ask_passphrase();
+                }
+            }
+            Err(e) => self.notif_error(tr!("Failed to unlock the saved sessions: {}", e)),
+        }
+        if self.starting {
+            self.restore_on_startup();
+        }
+    }
+
+    /// Returns an action of a notification which sends `msg` when clicked.
+    fn notification_action(&mut self, label: &str, msg: Msg) -> NotificationAction {
+        let id = self.next_action_id;
+        self.next_action_id += 1;
+        self.notification_actions.insert(id, msg);
+        while self.notification_actions.len() > MAX_NOTIFICATION_ACTIONS {
+            let oldest = *self.notification_actions.keys().next().unwrap();
+            self.notification_actions.remove(&oldest);
+        }
+        NotificationAction {
+            label: label.to_owned(),
+            id,
+        }
+    }
+
+    /// Puts a session in the active tab if it is empty, or else in a new tab.
+    fn open_session(&mut self, session: Session) {
+        // Don't lose what was typed in the template of the previous tab
+        self.compile_edited_template();
+        self.store.send(StoreRequest::Open(session));
+    }
+
+    /// Updates what is shown after the sessions changed.
+    fn on_store_output(&mut self, output: StoreOutput) -> ShouldRender {
+        let change = match output {
+            StoreOutput::Changed(state, change) => {
+                self.state = state;
+                change
+            }
+            StoreOutput::Saved(Ok(())) => {
+                self.invalid_autosave = false;
+                return false;
+            }
+            StoreOutput::Saved(Err(SaveError::Locked)) => {
+                // Reported once, the next saves failing the same way
+                if !std::mem::replace(&mut self.save_failed, true) {
+                    self.notif_warn(tr("The sessions are encrypted: they won't be saved nor \
+                                        restored until their passphrase is entered. Reload \
+                                        the page to enter it."));
+                }
+                return false;
+            }
+            StoreOutput::Saved(Err(SaveError::Failed(error))) => {
+                self.on_save_failed(anyhow!(error));
+                return false;
+            }
+            // Sent before the change, which renders the template again
+            StoreOutput::PathChanged(..) => {
+                self.debounce.stale.set(true);
+                return false;
+            }
+            StoreOutput::Loaded(name, result) => {
+                self.on_loaded_session(name, result);
+                return true;
+            }
+            StoreOutput::RestoredAutosave(result) => {
+                self.on_restored_autosave(result);
+                return true;
+            }
+            StoreOutput::Unlocked(result) => {
+                self.on_unlocked(result);
+                return true;
+            }
+            StoreOutput::SavedNames(names) => {
+                self.saved_names = names;
+                return self.managing_sessions;
+            }
+        };
+        match change {
+            Change::Inputs { typing: true } => self.debounce_render(),
+            Change::Inputs { typing: false } | Change::Other => {}
+            Change::Template => {
+                if let Session::Loaded { scenario, .. } = self.state.get().active() {
+                    self.template_editor.error = self.template_engine.set_template(scenario).err();
+                }
+                self.debounce.rendered.replace(None);
+                self.watch_read_paths();
+            }
+            Change::ActiveSession => {
+                self.sync_route();
+                self.show_active_session();
+            }
+            Change::Replaced => {
+                self.show_active_session();
+                if std::mem::take(&mut self.link_after_restore) {
+                    self.load_from_link();
+                }
+            }
+            Change::Removed(session, path, value) => {
+                let message = Msg::UndoRemove(session, path.clone(), value);
+                let undo = self.notification_action(tr("Undo"), message);
+                let text = tr!("Removed the element at '{}'.", path);
+                let notification = Notification::new(text, NotificationLevel::Info, Self::SOURCE);
+                self.notify(notification.with_action(undo));
+            }
+            Change::Flushed => return self.debounce.render_task.take().is_some(),
+        }
+        true
+    }
+
+    /// Forgets what is shown of the previous session, and compiles the
+    /// template of the active one.
+    fn show_active_session(&mut self) {
+        // This can fail if the template is invalid, in which case the error
+        // is shown in the template editor.
+        self.template_editor = TemplateEditor::default();
+        self.inputs_filter.clear();
+        self.compared_snapshot = None;
+        self.active_document = None;
+        self.pinned_output = None;
+        self.showing_output_diff = false;
+        self.debounce.render_task = None;
+        self.debounce.rendered.replace(None);
+        self.apply_strict_mode();
+        let compiled = match self.state.get().active() {
+            Session::Loaded { scenario, .. } => {
+                self.active_template = scenario.template.names()[0].to_owned();
+                self.template_engine.set_template(scenario)
+            }
+            Session::Init => Ok(()),
+        };
+        if let Err(e) = compiled {
+            self.on_template_error(e);
+        }
+        self.watch_read_paths();
+    }
+
+    /// Watches the paths of the inputs data the active template reads, so
+    /// that it is only rendered again when they change.
+    fn watch_read_paths(&mut self) {
+        let read_paths = match self.state.get().active() {
+            Session::Loaded { scenario, .. } => read_paths(
+                &self.active_template,
+                scenario.render_each.as_ref(),
+                &self.template_engine,
+            ),
+            Session::Init => vec![],
+        };
+        if read_paths == self.read_paths {
+            return;
+        }
+        for path in self.read_paths.drain(..) {
+            self.store.send(StoreRequest::Unwatch(path));
+        }
+        for path in &read_paths {
+            self.store.send(StoreRequest::Watch(path.clone()));
+        }
+        self.read_paths = read_paths;
+    }
+
+    fn new_session(&mut self) -> ShouldRender {
+        self.compile_edited_template();
+        self.store.send(StoreRequest::NewSession);
+        self.diagnostics.clear();
+        true
+    }
+
+    fn select_session(&mut self, index: usize) -> ShouldRender {
+        if index == self.state.get().active {
+            return false;
+        }
+        self.compile_edited_template();
+        self.store.send(StoreRequest::SelectSession(index));
+        self.diagnostics.clear();
+        true
+    }
+
+    fn close_session(&mut self, index: usize) -> ShouldRender {
+        if index == self.state.get().active {
+            self.diagnostics.clear();
+        }
+        self.store.send(StoreRequest::CloseSession(index));
+        true
+    }
+
+    fn on_session_event(&mut self, event: SessionEvent) -> ShouldRender {
+        match event {
+            SessionEvent::Save(name) => {
+                // Don't lose what was typed in the template
+                self.compile_edited_template();
+                self.store.send(StoreRequest::SaveAs(name));
+                false
+            }
+            SessionEvent::Load(name) => {
+                self.store.send(StoreRequest::Load(name));
+                self.restoring = true;
+                true
+            }
+            SessionEvent::Rename(name, new_name) => {
+                self.store.send(StoreRequest::Rename(name, new_name));
+                false
+            }
+            SessionEvent::Delete(name) => {
+                self.store.send(StoreRequest::Delete(name));
+                false
+            }
+            SessionEvent::Close => {
+                self.managing_sessions = false;
+                true
+            }
+        }
+    }
+
+    /// The output of the last render, or of its document being shown.
+    fn shown_output(&self) -> Option<String> {
+        let rendered = self.debounce.rendered.borrow();
+        let rendered = rendered.as_ref()?;
+        match self.active_document.and_then(|i| rendered.documents.get(i)) {
+            Some((_, output)) => Some(output.clone()),
+            None => Some(rendered.output.clone()),
+        }
+    }
+
+    /// The webhook of the settings, or else the scenario's.
+    fn webhook(&self) -> Option<Webhook> {
+        if self.settings.webhook.is_configured() {
+            return Some(self.settings.webhook.clone());
+        }
+        let webhook = match self.state.get().active() {
+            Session::Loaded { scenario, .. } => scenario.webhook.clone(),
+            Session::Init => None,
+        };
+        webhook.filter(Webhook::is_configured)
+    }
+
+    /// Posts the output of the last render to the webhook, after a
+    /// confirmation since the scenario could choose it.
+    fn send_output(&mut self) {
+        let webhook = match self.webhook() {
+            Some(webhook) => webhook,
+            None => return,
+        };
+        let output = self.shown_output().unwrap_or_default();
+        let body = match self.state.get().active() {
+            Session::Loaded { inputs_data, .. } => webhook.body(&output, inputs_data),
+            Session::Init => return,
+        };
+        let (content_type, body) = match body {
+            Ok(body) => body,
+            Err(e) => {
+                self.notif_error(tr!("Failed to send the output: {}", e));
+                return;
+            }
+        };
+        if !window().confirm(&tr!("Send the rendered template to {}?", webhook.url)) {
+            return;
+        }
+
+        let callback = self.link.callback(|response: Response<Text>| {
+            let (meta, body) = response.into_parts();
+            match body {
+                Ok(_) if meta.status.is_success() => Msg::SentOutput(Ok(meta.status.to_string())),
+                Ok(_) => Msg::SentOutput(Err(tr!("the server responded {}.", meta.status))),
+                Err(_) => Msg::SentOutput(Err(tr(
+                    "the server is unreachable or doesn't allow cross-origin requests (CORS)."
+                )
+                .to_owned())),
+            }
+        });
+        let mut request = Request::post(&webhook.url).header("Content-Type", content_type);
+        for (name, value) in &webhook.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let task = request
+            .body(Ok(body))
+            .map_err(|e| e.to_string())
+            .and_then(|request| {
+                self.fetch_service
+                    .fetch(request, callback)
+                    .map_err(ToString::to_string)
+            });
+        match task {
+            Ok(task) => self.send_task = Some(task),
+            Err(e) => self.notif_error(tr!("Failed to send the output: {}", e)),
+        }
+    }
+
+    /// Reports that the open tabs couldn't be saved, once until the size
+    /// limit is changed, and offers to download the active one instead.
+    fn on_save_failed(&mut self, error: anyhow::Error) {
+        if self.save_failed {
+            debug!("Failed to save the session again: {:?}", error);
+            return;
+        }
+        self.save_failed = true;
+        self.notif_error_chain(error.context(tr(
+            "Failed to save the session, the storage of the browser may be full."
+        )));
+
+        let question = tr(
+            "The open tabs can't be saved in the browser, probably because they are too big. \
+             Download the active one, so as to load it later?\n\nSetting a maximum saved size in \
+             the settings stops trying to save them.",
+        );
+        let loaded = !matches!(self.state.get().active(), Session::Init);
+        if loaded && window().confirm(question) {
+            self.export_session();
+        }
+    }
+
+    fn export_session(&mut self) {
+        let json = match self.state.get().active() {
+            Session::Init => None,
+            session => Some(serde_json::to_string_pretty(session)),
+        };
+        match json {
+            None => self.notif_warn(tr("Nothing to export, load a scenario first.")),
+            Some(Ok(json)) => download("session.json", &json),
+            Some(Err(e)) => self.notif_error(tr!("Failed to export the session: {}", e)),
+        }
+    }
+
+    /// Copies a link to the page with the whole session encoded in its
+    /// fragment, which isn't sent to the server.
+    fn copy_share_link(&mut self) {
+        let json = match self.state.get().active() {
+            Session::Init => None,
+            session => Some(serde_json::to_string(session)),
+        };
+        let json = match json {
+            None => {
+                self.notif_warn(tr("Nothing to share, load a scenario first."));
+                return;
+            }
+            Some(Ok(json)) => json,
+            Some(Err(e)) => {
+                self.notif_error(tr!("Failed to share the session: {}", e));
+                return;
+            }
+        };
+
+        let href = window()
+            .location()
+            .and_then(|location| location.href().ok())
+            .unwrap_or_default();
+        let page = href.split('#').next().unwrap_or_default();
+        let link = format!(
+            "{}{}{}",
+            page,
+            share::FRAGMENT_PREFIX,
+            share::encode_session(&json)
+        );
+        clipboard::write_text(&link, self.link.callback(Msg::CopiedShareLink));
+    }
+
+    /// Re-renders once the user stops typing, see `Debounce`.
+    fn debounce_render(&mut self) {
+        if self.settings.manual_render {
+            return;
+        }
+        let delay = Duration::from_millis(self.settings.debounce_delay_ms);
+        self.debounce.render_task = Some(
+            self.timeout
+                .spawn(delay, self.link.callback(|_| Msg::DebouncedRender)),
+        );
+    }
+
+    /// Describes what the app is waiting for, if anything, so that it
+    /// doesn't seem frozen meanwhile.
+    fn pending_activity(&self) -> Option<&'static str> {
+        if self.restoring {
+            Some(tr("Restoring the sessions..."))
+        } else if self.reader_task.is_some() {
+            Some(tr("Reading the file..."))
+        } else if self.fetch_task.is_some() && !matches!(self.loading, Loading::Reload(_)) {
+            // The reloads happen in the background
+            Some(tr("Loading the scenario..."))
+        } else {
+            None
+        }
+    }
+
+    fn render_progress(&self) -> Html {
+        match self.pending_activity() {
+            Some(activity) => html! {
+                <div class="app-progress" role="status">
+                    <progress class="progress is-small is-primary" aria-label=activity></progress>
+                    <p class="help">{ activity }</p>
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_diagnostics(&self) -> Html {
+        if self.diagnostics.is_empty() {
+            return html! {};
+        }
+
+        let has_errors = self.diagnostics.iter().any(Diagnostic::is_error);
+        let render_diagnostic = |diagnostic: &Diagnostic| {
+            let icon = match diagnostic.severity {
+                Severity::Error => "fas fa-times-circle has-text-danger",
+                Severity::Warning => "fas fa-exclamation-triangle has-text-warning",
+            };
+            html! {
+                <li>
+                    <span class="icon"><i class=icon></i></span>
+                    { for diagnostic.path.iter().map(|path| html! { <code>{ path }</code> }) }
+                    { " " }{ &diagnostic.message }
+                </li>
+            }
+        };
+
+        html! {
+            <article class=if has_errors { "message is-danger" } else { "message is-warning" }>
+                <div class="message-header">
+                    <p>{ if has_errors { tr("The scenario can't be loaded") } else { tr("The scenario may have mistakes") } }</p>
+                    <button
+                        class="delete"
+                        aria-label="delete"
+                        onclick=self.link.callback(|_| Msg::DismissDiagnostics)>
+                    </button>
+                </div>
+                <div class="message-body">
+                    <ul>{ for self.diagnostics.iter().map(render_diagnostic) }</ul>
+                </div>
+            </article>
+        }
+    }
+
+    fn render_session_manager(&self) -> Html {
+        if !self.managing_sessions {
+            return html! {};
+        }
+        html! {
+            <SessionManager
+                names=self.saved_names.clone()
+                on_event=self.link.callback(Msg::SessionEvent) />
+        }
+    }
+
+    fn render_drop_overlay(&self) -> Html {
+        if !self.dragging_file {
+            return html! {};
+        }
+        html! {
+            <div
+                class="drop-overlay"
+                ondragleave=self.link.callback(|_: DragLeaveEvent| Msg::DraggingFile(false))>
+                <p>
+                    <span class="icon is-large"><i class="fas fa-2x fa-file-import"></i></span>
+                    { tr("Drop a JSON or YAML scenario to load it") }
+                </p>
+            </div>
+        }
+    }
+
+    /// Renders the field filtering the inputs, with a message when none of
+    /// them matches, and the buttons collapsing or expanding the groups.
+    fn render_inputs_toolbar(
+        &self,
+        scenario: &Scenario,
+        inputs_data: &InputsData,
+        step: usize,
+        filter: &str,
+    ) -> Html {
+        let no_match = !filter.is_empty()
+            && !scenario
+                .step_inputs(step)
+                .into_iter()
+                .any(|input| input.matches_filter(filter));
+        let has_groups = !group_paths(&scenario.inputs, &Path::default(), inputs_data).is_empty();
+        let groups_buttons = if has_groups {
+            html! {
+                <div class="control">
+                    <div class="buttons has-addons">
+                        <button
+                            class="button is-small"
+                            title=tr("Collapse all the groups")
+                            onclick=StateStore::callback(|_| StoreRequest::SetGroupsCollapsed(true))>
+                            <span class="icon is-small"><i class="fas fa-compress-alt"></i></span>
+                        </button>
+                        <button
+                            class="button is-small"
+                            title=tr("Expand all the groups")
+                            onclick=StateStore::callback(|_| StoreRequest::SetGroupsCollapsed(false))>
+                            <span class="icon is-small"><i class="fas fa-expand-alt"></i></span>
+                        </button>
+                    </div>
+                </div>
+            }
+        } else {
+            html! {}
+        };
+        html! {
+            <>
+                <div class="field is-grouped inputs-toolbar">
+                    <div class="control is-expanded has-icons-left">
+                        <input
+                            class="input is-small"
+                            type="search"
+                            placeholder=tr("Filter the inputs")
+                            value=&self.inputs_filter
+                            oninput=self.link.callback(|input: InputData| Msg::FilterInputs(input.value))
+                            />
+                        <span class="icon is-small is-left"><i class="fas fa-filter"></i></span>
+                    </div>
+                    { groups_buttons }
+                </div>
+                { if no_match {
+                    html! { <p class="has-text-grey">{ tr("No input matches the filter.") }</p> }
+                } else {
+                    html! {}
+                } }
+            </>
+        }
+    }
+
+    fn render_inputs(
+        &self,
+        state: &State,
+        scenario: &Scenario,
+        inputs_data: &InputsData,
+        step: usize,
+        toggled_groups: &HashSet<Path>,
+    ) -> Html {
+        use crate::views::RenderableInput;
+
+        let filter = self.inputs_filter.trim().to_lowercase();
+        let ctx = InputsContext {
+            data: inputs_data,
+            invalid: &state.invalid_inputs,
+            toggled_groups,
+            quantity_units: &state.quantity_units,
+            missing_required: &state.missing_required,
+            filter: &filter,
+            presence: &self.presence,
+        };
+        let (steps, navigation) = if scenario.steps.is_empty() {
+            (html! {}, html! {})
+        } else {
+            let is_last = step + 1 >= scenario.steps.len();
+            let render_step = |(i, current): (usize, &Step)| {
+                html! {
+                    <li class=if i == step { "is-active" } else { "" }>
+                        <a>{ format!("{}. {}", i + 1, current.name) }</a>
+                    </li>
+                }
+            };
+            (
+                html! {
+                    <div class="tabs is-small">
+                        <ul>
+                            { for scenario.steps.iter().enumerate().map(render_step) }
+                        </ul>
+                    </div>
+                },
+                html! {
+                    <div class="buttons is-right">
+                        <button
+                            class="button"
+                            onclick=StateStore::callback(|_| StoreRequest::PreviousStep)
+                            disabled=(step == 0)>
+                            { tr("Previous") }
+                        </button>
+                        <button
+                            class="button is-primary"
+                            onclick=StateStore::callback(|_| StoreRequest::NextStep)
+                            disabled=is_last>
+                            { tr("Next") }
+                        </button>
+                    </div>
+                },
+            )
+        };
+
+        html! {
+            <div class="box">
+                <h1 class="title">{ tr("Inputs") }</h1>
+                { steps }
+                { self.render_inputs_toolbar(scenario, inputs_data, step, &filter) }
+                { for scenario
+                    .step_inputs(step)
+                    .into_iter()
+                    .map(|input| input.render(&Path::default(), &ctx)) }
+                { navigation }
+            </div>
+        }
+    }
+
+    /// Renders the template, unless a debounced render is pending or the
+    /// renders are manual, in which case the previous output is reused. It
+    /// is also reused when the data the template reads didn't change, eg.
+    /// when the edited inputs aren't used by the template, see
+    /// `App::watch_read_paths`.
+    fn render_code_column(
+        &self,
+        state: &State,
+        scenario: &Scenario,
+        inputs_data: &InputsData,
+    ) -> Html {
+        let mut cache = self.debounce.rendered.borrow_mut();
+        let reuse = self.debounce.render_task.is_some()
+            || self.settings.manual_render
+            || !self.debounce.stale.get();
+        let rendered = match cache.as_ref() {
+            Some(rendered) if reuse => rendered.clone(),
+            _ => {
+                let rendered = render_template(
+                    &self.active_template,
+                    inputs_data,
+                    scenario.render_each.as_ref(),
+                    &self.template_engine,
+                );
+                *cache = Some(rendered.clone());
+                self.debounce.stale.set(false);
+                rendered
+            }
+        };
+        let document_tabs = self.render_document_tabs(&rendered);
+        // Only the document being shown, see `shown_output`
+        let shown = self
+            .active_document
+            .and_then(|i| rendered.documents.get(i))
+            .map(|(_, output)| output.clone());
+        let rendered = match shown {
+            Some(output) => Rendered { output, ..rendered },
+            None => rendered,
+        };
+        // The extension of the template, eg. `values.yaml`, or else the scenario's
+        let language = self
+            .active_template
+            .rfind('.')
+            .map(|dot| &self.active_template[dot + 1..])
+            .filter(|extension| is_known_language(extension))
+            .or(scenario.output_language.as_deref());
+        let (query, current) = &self.output_search;
+        let matches = find_matches(&rendered.output, query);
+        let current = (*current).min(matches.len().saturating_sub(1));
+        let output = match &self.pinned_output {
+            Some(pinned) if self.showing_output_diff => {
+                render_output_diff(pinned, &rendered.output)
+            }
+            // Not highlighted, so as not to split the matches
+            _ if !query.is_empty() => {
+                html! { <pre>{ render_matches(&rendered.output, &matches, current) }</pre> }
+            }
+            _ => html! { <pre>{ highlight(&rendered.output, language) }</pre> },
+        };
+        let search_box = render_search_box(query, current, matches.len(), &self.link);
+        // The output is outdated until the debounced render
+        let pending_render = if self.debounce.render_task.is_some() {
+            html! {
+                <span class="icon has-text-grey" title=tr("Rendering...") role="status">
+                    <i class="fas fa-spinner fa-pulse"></i>
+                </span>
+            }
+        } else {
+            html! {}
+        };
+        let render_button = if self.settings.manual_render {
+            html! {
+                <button class="button is-primary" onclick=self.link.callback(|_| Msg::Render)>
+                    { tr("Render") }
+                </button>
+            }
+        } else {
+            html! {}
+        };
+        let send_button = match self.webhook() {
+            Some(webhook) => html! {
+                <button
+                    class=if self.send_task.is_some() { "button is-loading" } else { "button" }
+                    title=tr!("Post the output to {}", webhook.url)
+                    disabled=state.export_blocker().is_some()
+                    onclick=self.link.callback(|_| Msg::SendOutput)>
+                    { tr("Send") }
+                </button>
+            },
+            None => html! {},
+        };
+        let output_buttons = if self.pinned_output.is_some() {
+            html! {
+                <div class="buttons are-small">
+                    { render_button }
+                    { send_button }
+                    <button class="button" onclick=self.link.callback(|_| Msg::ToggleOutputDiff)>
+                        { if self.showing_output_diff { tr("Show the output") } else { tr("Show the changes") } }
+                    </button>
+                    <button class="button" onclick=self.link.callback(|_| Msg::UnpinOutput)>
+                        { tr("Unpin") }
+                    </button>
+                </div>
+            }
+        } else {
+            html! {
+                <div class="buttons are-small">
+                    { render_button }
+                    { send_button }
+                    <button
+                        class="button"
+                        title=tr("Keep this output, to see how the next changes of the inputs alter it")
+                        onclick=self.link.callback(|_| Msg::PinOutput)>
+                        { tr("Pin") }
+                    </button>
+                </div>
+            }
+        };
+        render_code_column(
+            &rendered,
+            html! {
+                <>
+                    <div class="level output-toolbar">
+                        <div class="level-left">{ output_buttons }{ pending_render }</div>
+                        <div class="level-right">{ search_box }</div>
+                    </div>
+                    { document_tabs }
+                    { output }
+                </>
+            },
+            self.template_engine.outputs(),
+            &self.active_template,
+            state,
+            &self.link,
+        )
+    }
+
+    /// Renders a tab per document, see `Scenario::render_each`, and one
+    /// showing all of them.
+    fn render_document_tabs(&self, rendered: &Rendered) -> Html {
+        if rendered.documents.is_empty() {
+            return html! {};
+        }
+        let render_tab = |index: Option<usize>, label: &str| {
+            html! {
+                <li class=if self.active_document == index { "is-active" } else { "" }>
+                    <a onclick=self.link.callback(move |_| Msg::SelectDocument(index))>{ label }</a>
+                </li>
+            }
+        };
+        html! {
+            <div class="tabs is-small">
+                <ul>
+                    { render_tab(None, "All") }
+                    { for rendered.documents.iter().enumerate().map(|(i, (label, _))| render_tab(Some(i), label)) }
+                </ul>
+            </div>
+        }
+    }
+
+    /// Renders the inputs data, with the secrets redacted, or only its title
+    /// if it is collapsed.
+    fn render_data(&self, scenario: &Scenario, inputs_data: &InputsData) -> Html {
+        let (icon, data) = if self.settings.show_data {
+            let data = redact_secrets(&scenario.inputs, inputs_data);
+            (
+                "fa-chevron-up",
+                html! { <pre>{ format!("{:#}", data) }</pre> },
+            )
+        } else {
+            ("fa-chevron-down", html! {})
+        };
+        html! {
+            <div class="box">
+                <div class="level">
+                    <div class="level-left">
+                        <h1 class="title">{ tr("Data") }</h1>
+                    </div>
+                    <div class="level-right">
+                        <button
+                            class="button is-small is-white"
+                            title=if self.settings.show_data { tr("Hide the data") } else { tr("Show the data") }
+                            onclick=self.link.callback(|_| Msg::ToggleData)>
+                            <span class="icon is-small"><i class=format!("fas {}", icon)></i></span>
+                        </button>
+                    </div>
+                </div>
+                { data }
+            </div>
+        }
+    }
+
+    /// Strict mode is enabled either by the settings or by the scenario.
+    fn apply_strict_mode(&mut self) {
+        let scenario_strict_mode = match self.state.get().active() {
+            Session::Loaded { scenario, .. } => scenario.strict_mode,
+            Session::Init => false,
+        };
+        self.template_engine
+            .set_strict_mode(self.settings.strict_mode || scenario_strict_mode);
+        self.debounce.rendered.replace(None);
+    }
+
+    /// Shows the error in the template editor, instead of rendering with
+    /// the template of the previous scenario.
+    fn on_template_error(&mut self, error: TemplateError) {
+        self.template_engine = Engine::new_uninit();
+        self.debounce.rendered.replace(None);
+        self.template_editor.error = Some(error);
+        self.notif_warn(tr(
+            "The template failed to compile, see the template editor."
+        ));
+    }
+
+    fn compile_edited_template(&mut self) -> ShouldRender {
+        self.template_editor.compile_task = None;
+        let template = match self.template_editor.pending.take() {
+            Some(template) => template,
+            None => return false,
+        };
+
+        // Compiled once changed, see `Change::Template`
+        self.store.send(StoreRequest::SetTemplate(
+            self.active_template.clone(),
+            template,
+        ));
+        false
+    }
+
+    fn unload_scenario(&mut self) -> ShouldRender {
+        self.link.send_message(Msg::Init);
+        false
+    }
+
+    fn take_snapshot(&mut self) -> ShouldRender {
+        let count = match self.state.get().active() {
+            Session::Loaded { snapshots, .. } => snapshots.len(),
+            Session::Init => return false,
+        };
+        let suggestion = tr!("Snapshot {}", count + 1);
+        let name =
+            js! { return prompt(@{tr("Name of the snapshot:")}, @{suggestion}); }.into_string();
+        match name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => {
+                self.store.send(StoreRequest::TakeSnapshot(name.to_owned()));
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn restore_snapshot(&mut self, index: usize) -> ShouldRender {
+        let name = match self.state.get().active() {
+            Session::Loaded { snapshots, .. } => match snapshots.get(index) {
+                Some(snapshot) => snapshot.name.clone(),
+                None => return false,
+            },
+            Session::Init => return false,
+        };
+        let question = tr!("Replace the inputs by the snapshot '{}'?", name);
+        if window().confirm(&question) {
+            self.store.send(StoreRequest::RestoreSnapshot(index));
+        }
+        false
+    }
+
+    fn reset_all(&mut self) -> ShouldRender {
+        self.store.send(StoreRequest::ResetAll);
+        false
+    }
+}
+
+fn render_template_editor(
+    scenario: &Scenario,
+    active_template: &str,
+    editor: &TemplateEditor,
+    link: &ComponentLink<App>,
+) -> Html {
+    // Don't overwrite what is being typed if something else triggers a render
+    let source = match &editor.pending {
+        Some(pending) => pending.clone(),
+        None => scenario
+            .template
+            .get(active_template)
+            .unwrap_or_default()
+            .into_owned(),
+    };
+    let error = match &editor.error {
+        Some(error) => html! {
+            <>
+                <p class="help is-danger">{ error }</p>
+                { render_error_location(&source, error, active_template) }
+            </>
+        },
+        None => html! {},
+    };
+    let title = if active_template == MAIN_TEMPLATE {
+        tr("Template").to_owned()
+    } else {
+        tr!("Template: {}", active_template)
+    };
+
+    html! {
+        <div class="box">
+            <h1 class="title">{ title }</h1>
+            <textarea
+                class=("textarea", "template-editor", if editor.error.is_some() { "is-danger" } else { "" })
+                rows=10
+                spellcheck="false"
+                value=source
+                oninput=link.callback(|input: InputData| Msg::EditedTemplate(input.value))
+                />
+            { error }
+        </div>
+    }
+}
+
+/// Shows the lines around the error in the edited template, with the
+/// offending one highlighted.
+fn render_error_location(source: &str, error: &TemplateError, active_template: &str) -> Html {
+    let line = match error.line {
+        Some(line) if error.template.as_deref().unwrap_or(MAIN_TEMPLATE) == active_template => line,
+        _ => return html! {},
+    };
+    let first = line.saturating_sub(3).max(1);
+
+    html! {
+        <pre class="template-error-location">
+            { for source
+                .lines()
+                .enumerate()
+                .map(|(i, content)| (i + 1, content))
+                .skip(first - 1)
+                .take(line - first + 3)
+                .map(|(i, content)| html! {
+                    <div class=if i == line { "template-line is-error" } else { "template-line" }>
+                        <span class="template-line-number">{ i }</span>
+                        { content }
+                    </div>
+                })
+            }
+        </pre>
+    }
+}
+
+/// The paths of the inputs data a template reads, see
+/// `TemplateEngine::read_keys`. The templates rendered once per element of a
+/// list read all of it.
+fn read_paths<T: TemplateEngine>(
+    name: &str,
+    render_each: Option<&RenderEach>,
+    template_engine: &T,
+) -> Vec<Path> {
+    let keys = match render_each {
+        Some(_) => None,
+        None => template_engine.read_keys(name),
+    };
+    match keys {
+        Some(keys) => keys.iter().map(|key| Path::from_segments(&[key])).collect(),
+        None => vec![Path::default()],
+    }
+}
+
+fn render_template<T: TemplateEngine>(
+    name: &str,
+    inputs_data: &InputsData,
+    render_each: Option<&RenderEach>,
+    template_engine: &T,
+) -> Rendered {
+    let start = now_ms();
+    let rendered = match render_each {
+        Some(render_each) => render_each_element(name, inputs_data, render_each, template_engine),
+        None => template_engine.render_named(name, inputs_data),
+    };
+    // Only the output shows the error, the inputs data are kept as they are
+    let mut rendered = rendered.unwrap_or_else(|e| Rendered {
+        output: format!("{}: {}", tr("Failed to render the data"), e),
+        ..Rendered::default()
+    });
+    rendered.duration_ms = now_ms() - start;
+    rendered
+}
+
+/// Milliseconds since the page was opened, with a sub-millisecond precision.
+fn now_ms() -> f64 {
+    js! { return performance.now(); }
+        .try_into()
+        .unwrap_or_default()
+}
+
+/// Renders the duration and the size of a render, with a warning if they
+/// are above `SLOW_RENDER_MS` or `BIG_OUTPUT_BYTES`.
+fn render_statistics(rendered: &Rendered) -> Html {
+    let size = rendered.output.len();
+    let size_text = if size < 1024 {
+        format!("{} B", size)
+    } else {
+        format!("{:.1} KB", size as f64 / 1024.0)
+    };
+    let text = tr!(
+        "Rendered in {} ms, {} in {} lines.",
+        format!("{:.0}", rendered.duration_ms),
+        size_text,
+        rendered.output.lines().count()
+    );
+    if rendered.duration_ms > SLOW_RENDER_MS || size > BIG_OUTPUT_BYTES {
+        html! {
+            <p class="help is-warning">
+                { text }
+                { " " }{ tr("The template may loop too much, eg. with nested #each, or try the manual renders in the settings.") }
+            </p>
+        }
+    } else {
+        html! { <p class="help has-text-grey">{ text }</p> }
+    }
+}
+
+/// Hides the values of the secret inputs, which are only shown in the
+/// rendered template.
+fn redact_secrets(inputs: &[InputTypes], inputs_data: &InputsData) -> InputsData {
+    let mut redacted = inputs_data.clone();
+    for path in secret_paths(inputs, &Path::default(), inputs_data) {
+        if let Some(value) = redacted.get_at_mut(&path).filter(|value| !value.is_null()) {
+            *value = JsonValue::String("•••".to_owned());
+        }
+    }
+    redacted
+}
+
+fn render_code_column(
+    rendered: &Rendered,
+    output: Html,
+    templates: &[String],
+    active_template: &str,
+    state: &State,
+    link: &ComponentLink<App>,
+) -> Html {
+    let render_tab = |name: &String| {
+        let name_inner = name.clone();
+        html! {
+            <li class=if name == active_template { "is-active" } else { "" }>
+                <a onclick=link.callback(move |_| Msg::SelectTemplate(name_inner.clone()))>
+                    { name }
+                </a>
+            </li>
+        }
+    };
+    let tabs = if templates.len() > 1 {
+        html! {
+            <div class="tabs">
+                <ul>
+                    { for templates.iter().map(render_tab) }
+                </ul>
+            </div>
+        }
+    } else {
+        html! {}
+    };
+    let unresolved = if rendered.unresolved.is_empty() {
+        html! {}
+    } else {
+        html! {
+            <article class="message is-warning">
+                <div class="message-body">
+                    <p>{ tr("These variables resolved to nothing:") }</p>
+                    <div class="tags">
+                        { for rendered.unresolved.iter().map(|variable| html! {
+                            <span class="tag is-warning">{ variable }</span>
+                        }) }
+                    </div>
+                </div>
+            </article>
+        }
+    };
+
+    let missing = if state.missing_required.is_empty() {
+        html! {}
+    } else {
+        html! {
+            <article class="message is-danger">
+                <div class="message-body">
+                    <p>{ tr("These required inputs are empty:") }</p>
+                    <div class="tags">
+                        { for state.missing_required.iter().map(|path| html! {
+                            <span class="tag is-danger">{ path }</span>
+                        }) }
+                    </div>
+                </div>
+            </article>
+        }
+    };
+    let invalid = if state.invalid_inputs.is_empty() {
+        html! {}
+    } else {
+        let mut invalid: Vec<_> = state.invalid_inputs.iter().collect();
+        invalid.sort_by_key(|(path, _)| path.to_string());
+        html! {
+            <article class="message is-danger">
+                <div class="message-body">
+                    <p>{ tr("These inputs are invalid:") }</p>
+                    <div class="tags">
+                        { for invalid.into_iter().map(|(path, invalid)| html! {
+                            <span class="tag is-danger" title=&invalid.error>{ path }</span>
+                        }) }
+                    </div>
+                </div>
+            </article>
+        }
+    };
+    let actions = if let Some(blocker) = state.export_blocker() {
+        html! {
+            <div class="buttons">
+                <button class="button is-small" disabled=true title=blocker>
+                    { tr("Copy") }
+                </button>
+                <button class="button is-small" disabled=true title=blocker>
+                    { tr("Download") }
+                </button>
+            </div>
+        }
+    } else {
+        let file_name = if active_template == MAIN_TEMPLATE {
+            "output.txt"
+        } else {
+            active_template
+        };
+        html! {
+            <div class="buttons">
+                <button class="button is-small" onclick=link.callback(|_| Msg::CopyOutput)>
+                    { tr("Copy") }
+                </button>
+                <a class="button is-small" href=data_url(&rendered.output) download=file_name>
+                    { tr("Download") }
+                </a>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="box">
+            <div class="level">
+                <div class="level-left">
+                    <h1 class="title">{ tr("Rendered template") }</h1>
+                </div>
+                <div class="level-right">{ actions }</div>
+            </div>
+            { tabs }
+            { missing }
+            { invalid }
+            { output }
+            { render_statistics(rendered) }
+            { unresolved }
+        </div>
+    }
+}
+
+/// Whether files are dragged, and not eg. the elements of a list.
+fn is_file_drag(event: &impl IDragEvent) -> bool {
+    match event.data_transfer() {
+        Some(data_transfer) => data_transfer.types().iter().any(|t| t == "Files"),
+        None => false,
+    }
+}
+
+/// Renders the title, the author and the description of the scenario, if it
+/// has some.
+fn render_scenario_header(scenario: &Scenario) -> Html {
+    if scenario.title.is_none() && scenario.description.is_none() {
+        return html! {};
+    }
+    html! {
+        <div class="box">
+            { for scenario.title.iter().map(|title| html! { <h1 class="title is-4">{ title }</h1> }) }
+            { for scenario.author.iter().map(|author| html! {
+                <p class="subtitle is-6">{ tr!("By {}", author) }</p>
+            }) }
+            { for scenario.description.iter().map(|description| render_markdown(description)) }
+        </div>
     }
 }
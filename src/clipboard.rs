@@ -0,0 +1,48 @@
+use stdweb::js;
+use yew::Callback;
+
+/// Reads the text of the clipboard with the asynchronous Clipboard API,
+/// which asks the user for the permission.
+pub fn read_text(callback: Callback<Result<String, String>>) {
+    let on_success = {
+        let callback = callback.clone();
+        move |text: String| callback.emit(Ok(text))
+    };
+    let on_error = move |error: String| callback.emit(Err(error));
+    js! { @(no_return)
+        var on_success = @{stdweb::Once(on_success)};
+        var on_error = @{stdweb::Once(on_error)};
+        if (!navigator.clipboard || !navigator.clipboard.readText) {
+            on_success.drop();
+            on_error("The clipboard can't be read by this browser.");
+            return;
+        }
+        navigator.clipboard.readText().then(
+            function(text) { on_error.drop(); on_success(text); },
+            function(error) { on_success.drop(); on_error(String(error)); }
+        );
+    }
+}
+
+/// Writes a text to the clipboard, which only works in response to an action
+/// of the user, eg. a click.
+pub fn write_text(text: &str, callback: Callback<Result<(), String>>) {
+    let on_success = {
+        let callback = callback.clone();
+        move || callback.emit(Ok(()))
+    };
+    let on_error = move |error: String| callback.emit(Err(error));
+    js! { @(no_return)
+        var on_success = @{stdweb::Once(on_success)};
+        var on_error = @{stdweb::Once(on_error)};
+        if (!navigator.clipboard || !navigator.clipboard.writeText) {
+            on_success.drop();
+            on_error("The clipboard can't be written by this browser.");
+            return;
+        }
+        navigator.clipboard.writeText(@{text}).then(
+            function() { on_error.drop(); on_success(); },
+            function(error) { on_success.drop(); on_error(String(error)); }
+        );
+    }
+}
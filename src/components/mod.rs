@@ -1,7 +1,13 @@
 mod navbar;
 mod not_equal_assign;
 mod notifications;
+mod remote_select;
+mod scenario_gallery;
+mod session_manager;
 
 pub use navbar::Navbar;
 pub use not_equal_assign::NeqAssign;
 pub use notifications::Notifications;
+pub use remote_select::RemoteSelect;
+pub use scenario_gallery::{find_example, ScenarioGallery};
+pub use session_manager::{SessionEvent, SessionManager};
@@ -2,11 +2,21 @@ use crate::{
     agents::{NotificationBus, NotificationSender},
     app,
     components::NeqAssign,
+    i18n::{tr, Language},
+    route::Route,
+    settings::{Layout, Settings, Theme},
+    webhook::Webhook,
+};
+use log::*;
+use stdweb::{
+    js,
+    web::{event::IEvent, window},
 };
 use yew::{
     agent::{Dispatched, Dispatcher},
     prelude::*,
 };
+use yew_router::components::RouterAnchor;
 
 pub struct Navbar {
     link: ComponentLink<Self>,
@@ -17,21 +27,38 @@ pub struct Navbar {
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct Props {
     pub on_navevent: Callback<app::NavEvent>,
+    pub settings: Settings,
+    /// Titles of the tabs, see `app::NavEvent::SelectSession`.
+    #[prop_or_default]
+    pub sessions: Vec<String>,
+    #[prop_or_default]
+    pub active_session: usize,
+    /// Whether the route of the settings is shown, see `Route::Settings`.
+    #[prop_or_default]
+    pub settings_open: bool,
+    /// Whether the template engine of the active tab always fails on missing
+    /// variables, see `TemplateEngine::always_strict`.
+    #[prop_or_default]
+    pub always_strict: bool,
 }
 
 #[derive(Debug)]
 pub enum Msg {
     NavEvent(app::NavEvent),
-    RestorePreviousScenario,
+    /// Asks for a confirmation before resetting all the inputs.
+    ResetAll,
+    /// Asks for the URL of a scenario to load.
     LoadFromUrl,
+    OpenScenarioFile(ChangeData),
     UserGuide,
     About,
     ReportIssue,
-    Share,
-    Settings,
+    InvalidSetting(String),
 }
 
 impl NotificationSender for Navbar {
+    const SOURCE: &'static str = "Navbar";
+
     fn notification_bus(&mut self) -> &mut Dispatcher<NotificationBus> {
         &mut self.notification_bus
     }
@@ -59,8 +86,36 @@ impl Component for Navbar {
                 self.props.on_navevent.emit(nav_event);
                 false
             }
+            Msg::ResetAll => {
+                if window().confirm(tr("Reset all the inputs to their default value?")) {
+                    self.props.on_navevent.emit(app::NavEvent::ResetAll);
+                }
+                false
+            }
+            Msg::LoadFromUrl => {
+                let url = js! { return prompt(@{tr("URL of the scenario to load, which can be a GitHub file or a gist:")}); }.into_string();
+                match url.as_deref().map(str::trim) {
+                    Some(url) if !url.is_empty() => {
+                        let nav_event = app::NavEvent::LoadFromUrl(url.to_owned());
+                        self.props.on_navevent.emit(nav_event);
+                    }
+                    _ => {}
+                }
+                false
+            }
+            Msg::OpenScenarioFile(ChangeData::Files(files)) => {
+                if let Some(file) = files.iter().next() {
+                    let nav_event = app::NavEvent::OpenScenarioFile(file);
+                    self.props.on_navevent.emit(nav_event);
+                }
+                false
+            }
+            Msg::InvalidSetting(value) => {
+                debug!("Ignored invalid setting value: {:?}", value);
+                false
+            }
             unhandled => {
-                self.notif_error(format!("{:?} not implemented yet.", unhandled));
+                self.notif_error(tr!("{} not implemented yet.", format!("{:?}", unhandled)));
                 false
             }
         }
@@ -85,62 +140,429 @@ impl Component for Navbar {
                     <div class="navbar-start">
                         <div class="navbar-item has-dropdown is-hoverable">
                             <a class="navbar-link">
-                                { "Scenario" }
+                                { tr("Scenario") }
                             </a>
 
                             <div class="navbar-dropdown">
+                                // The label opens the file picker of its hidden input
+                                <label class="navbar-item">
+                                    { tr("Open a scenario or a session...") }
+                                    <input
+                                        class="is-hidden"
+                                        type="file"
+                                        accept=".json,.yaml,.yml,application/json"
+                                        onchange=self.link.callback(Msg::OpenScenarioFile)
+                                        />
+                                </label>
                                 <a class="navbar-item" onclick=self.link.callback(|_| Msg::LoadFromUrl)>
-                                    { "Load from URL" }
+                                    { tr("Load from URL") }
+                                </a>
+                                <RouterAnchor<Route> route=Route::Gallery classes="navbar-item">
+                                    { tr("Example scenarios") }
+                                </RouterAnchor<Route>>
+                                <a class="navbar-item" onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::ReloadScenario))>
+                                    { tr("Reload from its URL") }
                                 </a>
-                                <a class="navbar-item" onclick=self.link.callback(|_| Msg::RestorePreviousScenario)>
-                                    { "Restore a previous scenario" }
+                                <a class="navbar-item" onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::ExportSession))>
+                                    { tr("Export the session") }
+                                </a>
+                                <a class="navbar-item" onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::ManageSessions))>
+                                    { tr("Saved sessions...") }
+                                </a>
+                                <a class="navbar-item" onclick=self.link.callback(|_| Msg::ResetAll)>
+                                    { tr("Reset all the inputs") }
                                 </a>
                                 <a class="navbar-item" onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::UnloadScenario))>
-                                    { "Unload the workspace" }
+                                    { tr("Unload the workspace") }
                                 </a>
                                 <hr class="navbar-divider" />
                                 <a class="navbar-item" onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::LoadFromLocalStorage))>
-                                    { "Reload from local storage" }
+                                    { tr("Reload from local storage") }
                                 </a>
                                 <a class="navbar-item" onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::LoadDebugScenario))>
-                                    { "Load a debug scenario" }
+                                    { tr("Load a debug scenario") }
                                 </a>
                             </div>
                         </div>
 
                         <div class="navbar-item has-dropdown is-hoverable">
                             <a class="navbar-link">
-                                { "Help" }
+                                { tr("Help") }
                             </a>
 
                             <div class="navbar-dropdown">
                                 <a class="navbar-item" onclick=self.link.callback(|_| Msg::UserGuide)>
-                                    { "User guide" }
+                                    { tr("User guide") }
                                 </a>
                                 <a class="navbar-item" onclick=self.link.callback(|_| Msg::About)>
-                                    { "About" }
+                                    { tr("About") }
                                 </a>
                                 <a class="navbar-item" onclick=self.link.callback(|_| Msg::ReportIssue)>
-                                    { "Report an issue" }
+                                    { tr("Report an issue") }
                                 </a>
                             </div>
                         </div>
                     </div>
 
+                    <div class="navbar-item">
+                        <div class="tabs is-small is-toggle navbar-sessions">
+                            <ul>
+                                { for self.props.sessions.iter().enumerate().map(|(i, title)| self.render_session_tab(i, title)) }
+                                <li>
+                                    <a
+                                        title=tr("Open a new tab")
+                                        onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::NewSession))>
+                                        <span class="icon is-small"><i class="fas fa-plus"></i></span>
+                                    </a>
+                                </li>
+                            </ul>
+                        </div>
+                    </div>
+
                     <div class="navbar-end">
                         <div class="navbar-item">
                             <div class="buttons">
-                                <a class="button is-primary" onclick=self.link.callback(|_| Msg::Share)>
-                                    <strong>{ "Share" }</strong>
-                                </a>
-                                <a class="button is-light"  onclick=self.link.callback(|_| Msg::Settings)>
-                                    { "Settings" }
+                                <a
+                                    class="button is-primary"
+                                    title=tr("Copy a link to the scenario and its inputs")
+                                    onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::CopyShareLink))>
+                                    <strong>{ tr("Share") }</strong>
                                 </a>
                             </div>
                         </div>
+
+                        { self.render_settings() }
                     </div>
                 </div>
             </nav>
         }
     }
 }
+
+impl Navbar {
+    fn render_settings(&self) -> Html {
+        // The route keeps the settings open, and closing them goes back home
+        let (class, route) = if self.props.settings_open {
+            (
+                "navbar-item has-dropdown is-hoverable is-active",
+                Route::Home,
+            )
+        } else {
+            ("navbar-item has-dropdown is-hoverable", Route::Settings)
+        };
+        let strict_mode_hint = if self.props.always_strict {
+            tr("Tera templates always fail on undefined variables.")
+        } else {
+            ""
+        };
+        html! {
+            <div class=class>
+                <RouterAnchor<Route> route=route classes="navbar-link">
+                    { tr("Settings") }
+                </RouterAnchor<Route>>
+
+                <div class="navbar-dropdown is-right">
+                    <div class="navbar-item" title=strict_mode_hint>
+                        <input
+                            id="settings_strict_mode"
+                            type="checkbox"
+                            class="switch"
+                            checked=self.props.settings.strict_mode || self.props.always_strict
+                            disabled=self.props.always_strict
+                            onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::ToggleStrictMode))
+                            />
+                        <label for="settings_strict_mode">{ tr("Strict mode") }</label>
+                    </div>
+                    <div class="navbar-item">
+                        <div class="field">
+                            <label class="label is-small">{ tr("Max saved size (KB)") }</label>
+                            <div class="control">
+                                <input
+                                    class="input is-small"
+                                    type="number"
+                                    min=0
+                                    step=100
+                                    title=tr("The bigger tabs aren't saved in the browser, 0 for no limit")
+                                    value=self.props.settings.max_autosave_kb
+                                    oninput=self.link.callback(|input: InputData| match input.value.parse() {
+                                        Ok(max_kb) => Msg::NavEvent(app::NavEvent::SetMaxAutosaveSize(max_kb)),
+                                        Err(_) => Msg::InvalidSetting(input.value),
+                                    })
+                                    />
+                            </div>
+                        </div>
+                    </div>
+                    <div class="navbar-item">
+                        <input
+                            id="settings_restore_on_startup"
+                            type="checkbox"
+                            class="switch"
+                            checked=self.props.settings.restore_on_startup
+                            onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::ToggleRestoreOnStartup))
+                            />
+                        <label for="settings_restore_on_startup">{ tr("Restore the tabs on startup") }</label>
+                    </div>
+                    <div class="navbar-item">
+                        <input
+                            id="settings_encrypt_sessions"
+                            type="checkbox"
+                            class="switch"
+                            checked=self.props.settings.encrypt_sessions
+                            onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::ToggleEncryption))
+                            />
+                        <label for="settings_encrypt_sessions">{ tr("Encrypt the saved sessions") }</label>
+                    </div>
+                    { self.render_webhook_settings() }
+                    { self.render_live_settings() }
+                    <div class="navbar-item">
+                        <input
+                            id="settings_manual_render"
+                            type="checkbox"
+                            class="switch"
+                            checked=self.props.settings.manual_render
+                            onclick=self.link.callback(|_| Msg::NavEvent(app::NavEvent::ToggleManualRender))
+                            />
+                        <label for="settings_manual_render">{ tr("Render manually") }</label>
+                    </div>
+                    <div class="navbar-item">
+                        <div class="field">
+                            <label class="label is-small">{ tr("Layout") }</label>
+                            <div class="buttons has-addons are-small">
+                                { self.render_layout_button(Layout::Split, tr("Split")) }
+                                { self.render_layout_button(Layout::InputsOnly, tr("Inputs")) }
+                                { self.render_layout_button(Layout::OutputOnly, tr("Output")) }
+                            </div>
+                        </div>
+                    </div>
+                    <div class="navbar-item">
+                        <div class="field">
+                            <label class="label is-small">{ tr("Theme") }</label>
+                            <div class="buttons has-addons are-small">
+                                { self.render_theme_button(Theme::Light, tr("Light")) }
+                                { self.render_theme_button(Theme::Dark, tr("Dark")) }
+                                { self.render_theme_button(Theme::Auto, tr("Auto")) }
+                            </div>
+                        </div>
+                    </div>
+                    { self.render_language_select() }
+                    <div class="navbar-item">
+                        <div class="field">
+                            <label class="label is-small">{ tr("Render delay (ms)") }</label>
+                            <div class="control">
+                                <input
+                                    class="input is-small"
+                                    type="number"
+                                    min=0
+                                    step=50
+                                    value=self.props.settings.debounce_delay_ms
+                                    oninput=self.link.callback(|input: InputData| match input.value.parse() {
+                                        Ok(delay) => Msg::NavEvent(app::NavEvent::SetDebounceDelay(delay)),
+                                        Err(_) => Msg::InvalidSetting(input.value),
+                                    })
+                                    />
+                            </div>
+                        </div>
+                    </div>
+                    <div class="navbar-item">
+                        <div class="field">
+                            <label class="label is-small">{ tr("Reload remote scenarios (s)") }</label>
+                            <div class="control">
+                                <input
+                                    class="input is-small"
+                                    type="number"
+                                    min=0
+                                    title=tr("0 to never reload them automatically")
+                                    value=self.props.settings.reload_interval_s
+                                    oninput=self.link.callback(|input: InputData| match input.value.parse() {
+                                        Ok(interval) => Msg::NavEvent(app::NavEvent::SetReloadInterval(interval)),
+                                        Err(_) => Msg::InvalidSetting(input.value),
+                                    })
+                                    />
+                            </div>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    /// Renders the URLs the inputs are shared with while they are edited.
+    fn render_live_settings(&self) -> Html {
+        html! {
+            <>
+                <div class="navbar-item">
+                    <div class="field">
+                        <label class="label is-small">{ tr("Live sync URL") }</label>
+                        <div class="control">
+                            <input
+                                class="input is-small"
+                                type="url"
+                                placeholder="wss://..."
+                                title=tr("Edits the inputs along with the other pages connected to this WebSocket server")
+                                value=&self.props.settings.sync_url
+                                onchange=self.link.callback(|change: ChangeData| match change {
+                                    ChangeData::Value(url) => Msg::NavEvent(app::NavEvent::SetSyncUrl(url)),
+                                    _ => Msg::NavEvent(app::NavEvent::SetSyncUrl(String::new())),
+                                })
+                                />
+                        </div>
+                    </div>
+                </div>
+                <div class="navbar-item">
+                    <div class="field">
+                        <label class="label is-small">{ tr("Changes webhook URL") }</label>
+                        <div class="control">
+                            <input
+                                class="input is-small"
+                                type="url"
+                                placeholder="https://..."
+                                title=tr("Posts the changes of the inputs to this URL, as JSON Patches")
+                                value=&self.props.settings.changes_webhook_url
+                                onchange=self.link.callback(|change: ChangeData| match change {
+                                    ChangeData::Value(url) => Msg::NavEvent(app::NavEvent::SetChangesWebhookUrl(url)),
+                                    _ => Msg::NavEvent(app::NavEvent::SetChangesWebhookUrl(String::new())),
+                                })
+                                />
+                        </div>
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    fn render_webhook_settings(&self) -> Html {
+        let webhook = &self.props.settings.webhook;
+        let set_webhook = |edit: fn(&mut Webhook, String)| {
+            let webhook = webhook.clone();
+            self.link.callback(move |value: String| {
+                let mut webhook = webhook.clone();
+                edit(&mut webhook, value);
+                Msg::NavEvent(app::NavEvent::SetWebhook(webhook))
+            })
+        };
+        let on_url = set_webhook(|webhook, url| webhook.url = url);
+        let on_headers =
+            set_webhook(|webhook, text| webhook.headers = Webhook::parse_headers(&text));
+        let on_include_data =
+            set_webhook(|webhook, _| webhook.include_data = !webhook.include_data);
+        html! {
+            <>
+                <div class="navbar-item">
+                    <div class="field">
+                        <label class="label is-small">{ tr("Webhook URL") }</label>
+                        <div class="control">
+                            <input
+                                class="input is-small"
+                                type="url"
+                                placeholder=tr("Overrides the one of the scenario")
+                                value=&webhook.url
+                                oninput=on_url.reform(|input: InputData| input.value)
+                                />
+                        </div>
+                    </div>
+                </div>
+                <div class="navbar-item">
+                    <div class="field">
+                        <label class="label is-small">{ tr("Webhook headers") }</label>
+                        <div class="control">
+                            <textarea
+                                class="textarea is-small"
+                                rows=2
+                                placeholder="Authorization: Bearer ..."
+                                value=webhook.headers_text()
+                                onchange=on_headers.reform(|change: ChangeData| match change {
+                                    ChangeData::Value(text) => text,
+                                    _ => String::new(),
+                                })
+                                />
+                        </div>
+                    </div>
+                </div>
+                <div class="navbar-item">
+                    <input
+                        id="settings_webhook_data"
+                        type="checkbox"
+                        class="switch"
+                        checked=webhook.include_data
+                        onclick=on_include_data.reform(|_| String::new())
+                        />
+                    <label for="settings_webhook_data">{ tr("Send the data along") }</label>
+                </div>
+            </>
+        }
+    }
+
+    fn render_layout_button(&self, layout: Layout, label: &str) -> Html {
+        let class = if self.props.settings.layout == layout {
+            "button is-selected is-info"
+        } else {
+            "button"
+        };
+        html! {
+            <button class=class onclick=self.link.callback(move |_| Msg::NavEvent(app::NavEvent::SetLayout(layout)))>
+                { label }
+            </button>
+        }
+    }
+
+    fn render_theme_button(&self, theme: Theme, label: &str) -> Html {
+        let class = if self.props.settings.theme == theme {
+            "button is-selected is-info"
+        } else {
+            "button"
+        };
+        html! {
+            <button class=class onclick=self.link.callback(move |_| Msg::NavEvent(app::NavEvent::SetTheme(theme)))>
+                { label }
+            </button>
+        }
+    }
+
+    fn render_language_select(&self) -> Html {
+        let on_change = self.link.callback(|change: ChangeData| match change {
+            ChangeData::Select(select) => match select.selected_index() {
+                Some(index) => {
+                    let language = Language::ALL[index as usize];
+                    Msg::NavEvent(app::NavEvent::SetLanguage(language))
+                }
+                None => Msg::InvalidSetting(String::new()),
+            },
+            _ => Msg::InvalidSetting(String::new()),
+        });
+        let render_option = |language: &Language| {
+            html! {
+                <option selected=self.props.settings.language == *language>{ language.name() }</option>
+            }
+        };
+        html! {
+            <div class="navbar-item">
+                <div class="field">
+                    <label class="label is-small">{ tr("Language") }</label>
+                    <div class="control">
+                        <div class="select is-small">
+                            <select onchange=on_change>
+                                { for Language::ALL.iter().map(render_option) }
+                            </select>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_session_tab(&self, index: usize, title: &str) -> Html {
+        let on_close = self.link.callback(move |event: ClickEvent| {
+            // Doesn't select the tab being closed
+            event.stop_propagation();
+            Msg::NavEvent(app::NavEvent::CloseSession(index))
+        });
+        html! {
+            <li class=if index == self.props.active_session { "is-active" } else { "" }>
+                <a onclick=self.link.callback(move |_| Msg::NavEvent(app::NavEvent::SelectSession(index)))>
+                    <span>{ title }</span>
+                    <button class="delete is-small" aria-label="close" onclick=on_close></button>
+                </a>
+            </li>
+        }
+    }
+}
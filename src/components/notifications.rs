@@ -1,12 +1,37 @@
-use crate::agents::{Notification, NotificationBus, NotificationLevel, NotificationRequest};
+use crate::{
+    agents::{
+        Notification, NotificationAction, NotificationBus, NotificationLevel, NotificationRequest,
+    },
+    i18n::tr,
+};
 use log::*;
-use yew::prelude::*;
+use std::time::Duration;
+use yew::{
+    prelude::*,
+    services::{timeout::TimeoutTask, TimeoutService},
+};
 
 pub struct Notifications {
     //props: Props,
     link: ComponentLink<Self>,
-    _notification_bus: Box<dyn Bridge<NotificationBus>>,
-    notifications: Vec<Notification>,
+    notification_bus: Box<dyn Bridge<NotificationBus>>,
+    timeout: TimeoutService,
+    notifications: Vec<Shown>,
+    /// Identifier of the next notification.
+    next_id: usize,
+}
+
+/// A notification being shown, with its dismissal pending if it has a
+/// timeout and isn't hovered.
+struct Shown {
+    id: usize,
+    notification: Notification,
+    dismiss_task: Option<TimeoutTask>,
+    /// How many times the notification was received, shown as a badge.
+    count: usize,
+    hovered: bool,
+    /// Whether the details are shown.
+    expanded: bool,
 }
 
 //#[derive(Debug, Clone, PartialEq, Properties)]
@@ -15,7 +40,16 @@ pub struct Notifications {
 #[derive(Debug)]
 pub enum Msg {
     NewNotificationRequest(NotificationRequest),
+    /// Removes the notification with this identifier.
     DeleteNotification(usize),
+    /// Stops the timeout of a notification while it is hovered.
+    PauseDismiss(usize),
+    /// Restarts the timeout of a notification once it isn't hovered.
+    ResumeDismiss(usize),
+    ToggleDetails(usize),
+    /// Tells the sender of a notification that its action was clicked, and
+    /// removes the notification.
+    ClickAction(usize, NotificationAction),
 }
 
 impl Component for Notifications {
@@ -27,8 +61,10 @@ impl Component for Notifications {
         Self {
             //props,
             link,
-            _notification_bus: NotificationBus::bridge(callback),
+            notification_bus: NotificationBus::bridge(callback),
+            timeout: TimeoutService::new(),
             notifications: Vec::with_capacity(10),
+            next_id: 0,
         }
     }
 
@@ -39,18 +75,52 @@ impl Component for Notifications {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::NewNotificationRequest(request) => match request {
-                NotificationRequest::New(notification) => {
-                    self.notifications.push(notification);
-                    true
-                }
+                NotificationRequest::New(notification) => self.show(notification),
+                NotificationRequest::Clicked(_) => false,
             },
             Msg::DeleteNotification(id) => {
-                if id < self.notifications.len() {
-                    self.notifications.remove(id);
-                    true
-                } else {
-                    error!("Msg::DeleteNotification with invalid id.");
-                    false
+                match self.notifications.iter().position(|shown| shown.id == id) {
+                    Some(index) => {
+                        self.notifications.remove(index);
+                        true
+                    }
+                    None => {
+                        error!("Msg::DeleteNotification with invalid id.");
+                        false
+                    }
+                }
+            }
+            Msg::PauseDismiss(id) => {
+                if let Some(shown) = self.notifications.iter_mut().find(|shown| shown.id == id) {
+                    shown.dismiss_task = None;
+                    shown.hovered = true;
+                }
+                false
+            }
+            Msg::ResumeDismiss(id) => {
+                let timeout_ms = match self.notifications.iter().find(|shown| shown.id == id) {
+                    Some(shown) => shown.notification.timeout_ms,
+                    None => return false,
+                };
+                let dismiss_task = self.schedule_dismiss(id, timeout_ms);
+                if let Some(shown) = self.notifications.iter_mut().find(|shown| shown.id == id) {
+                    shown.dismiss_task = dismiss_task;
+                    shown.hovered = false;
+                }
+                false
+            }
+            Msg::ClickAction(id, action) => {
+                self.notification_bus
+                    .send(NotificationRequest::Clicked(action));
+                self.update(Msg::DeleteNotification(id))
+            }
+            Msg::ToggleDetails(id) => {
+                match self.notifications.iter_mut().find(|shown| shown.id == id) {
+                    Some(shown) => {
+                        shown.expanded = !shown.expanded;
+                        true
+                    }
+                    None => false,
                 }
             }
         }
@@ -62,19 +132,68 @@ impl Component for Notifications {
                 { for self
                     .notifications
                     .iter()
-                    .enumerate()
-                    .map(|(id, notif)| render_notification(id, notif, &self.link))
+                    .map(|shown| render_notification(shown, &self.link))
                 }
             </div>
         }
     }
 }
 
-fn render_notification(
-    id: usize,
-    notification: &Notification,
-    link: &ComponentLink<Notifications>,
-) -> Html {
+impl Notifications {
+    /// Shows a new notification, or counts it again if an identical one is
+    /// shown, eg. for errors repeated on each keystroke.
+    fn show(&mut self, notification: Notification) -> ShouldRender {
+        let repeated = self
+            .notifications
+            .iter()
+            .find(|shown| shown.notification == notification)
+            .map(|shown| (shown.id, shown.hovered));
+        match repeated {
+            Some((id, hovered)) => {
+                let dismiss_task = if hovered {
+                    None
+                } else {
+                    self.schedule_dismiss(id, notification.timeout_ms)
+                };
+                if let Some(shown) = self.notifications.iter_mut().find(|shown| shown.id == id) {
+                    shown.count += 1;
+                    shown.dismiss_task = dismiss_task;
+                }
+            }
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+                let dismiss_task = self.schedule_dismiss(id, notification.timeout_ms);
+                self.notifications.push(Shown {
+                    id,
+                    notification,
+                    dismiss_task,
+                    count: 1,
+                    hovered: false,
+                    expanded: false,
+                });
+            }
+        }
+        true
+    }
+
+    /// Deletes the notification after its timeout, if it has one.
+    fn schedule_dismiss(&mut self, id: usize, timeout_ms: Option<u64>) -> Option<TimeoutTask> {
+        let delay = Duration::from_millis(timeout_ms?);
+        let callback = self.link.callback(move |_| Msg::DeleteNotification(id));
+        Some(self.timeout.spawn(delay, callback))
+    }
+}
+
+fn render_notification(shown: &Shown, link: &ComponentLink<Notifications>) -> Html {
+    let Shown {
+        id,
+        notification,
+        count,
+        expanded,
+        ..
+    } = shown;
+    let id = *id;
     let color_class = match notification.level {
         NotificationLevel::Success => "is-success",
         NotificationLevel::Info => "is-info",
@@ -85,9 +204,76 @@ fn render_notification(
     let on_delete = link.callback(move |_: ClickEvent| Msg::DeleteNotification(id));
 
     html! {
-        <div class=("notification", color_class)>
+        <div
+            class=("notification", color_class)
+            onmouseenter=link.callback(move |_| Msg::PauseDismiss(id))
+            onmouseleave=link.callback(move |_| Msg::ResumeDismiss(id))>
             <button class="delete" onclick=&on_delete></button>
-            { for notification.text.lines().map(|l| html! { <p>{ l }</p> }) }
+            { if *count > 1 {
+                html! { <span class="tag is-rounded notification-count">{ format!("×{}", count) }</span> }
+            } else {
+                html! {}
+            } }
+            { for notification.title.lines().map(|l| html! { <p>{ l }</p> }) }
+            { render_actions(id, &notification.actions, link) }
+            { render_details(notification, *expanded, link.callback(move |_| Msg::ToggleDetails(id))) }
         </div>
     }
 }
+
+fn render_actions(
+    id: usize,
+    actions: &[NotificationAction],
+    link: &ComponentLink<Notifications>,
+) -> Html {
+    if actions.is_empty() {
+        return html! {};
+    }
+    let render_action = |action: &NotificationAction| {
+        let action_inner = action.clone();
+        let on_click =
+            link.callback(move |_: ClickEvent| Msg::ClickAction(id, action_inner.clone()));
+        html! {
+            <button class="button is-small is-light" onclick=on_click>{ &action.label }</button>
+        }
+    };
+    html! {
+        <div class="buttons notification-actions">
+            { for actions.iter().map(render_action) }
+        </div>
+    }
+}
+
+/// Renders the details of a notification and where it comes from, behind a
+/// toggle.
+fn render_details(
+    notification: &Notification,
+    expanded: bool,
+    on_toggle: Callback<ClickEvent>,
+) -> Html {
+    if notification.details.is_empty() {
+        return html! {};
+    }
+    let toggle_text = if expanded {
+        tr("Hide the details")
+    } else {
+        tr("Show the details")
+    };
+    html! {
+        <>
+            <a class="notification-toggle is-size-7" onclick=on_toggle>{ toggle_text }</a>
+            { if expanded {
+                html! {
+                    <div class="notification-details is-size-7">
+                        <ul>
+                            { for notification.details.iter().map(|detail| html! { <li>{ detail }</li> }) }
+                        </ul>
+                        <p class="has-text-right">{ tr!("From: {}", notification.source) }</p>
+                    </div>
+                }
+            } else {
+                html! {}
+            } }
+        </>
+    }
+}
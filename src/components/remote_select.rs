@@ -0,0 +1,238 @@
+use crate::{
+    agents::{FetchedOptions, OptionsFetcher, OptionsRequest},
+    components::NeqAssign,
+    i18n::tr,
+    inputs::SelectOption,
+};
+use serde_json::Value as JsonValue;
+use stdweb::web::event::{IEvent, IKeyboardEvent};
+use yew::prelude::*;
+
+/// A searchable dropdown whose options are fetched from a URL.
+pub struct RemoteSelect {
+    link: ComponentLink<Self>,
+    props: Props,
+    options_fetcher: Box<dyn Bridge<OptionsFetcher>>,
+    /// `None` while the options are being fetched.
+    options: Option<Result<Vec<SelectOption>, String>>,
+    /// Text typed to filter the options, `None` when the dropdown is closed.
+    filter: Option<String>,
+    /// Index of the option chosen with the arrow keys, among the filtered
+    /// ones.
+    highlighted: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    /// Id of the text field, for its label.
+    #[prop_or_default]
+    pub id: String,
+    pub url: String,
+    pub value: Option<String>,
+    #[prop_or_default]
+    pub placeholder: Option<String>,
+    pub on_select: Callback<JsonValue>,
+}
+
+#[derive(Debug)]
+pub enum Msg {
+    Fetched(FetchedOptions),
+    Filter(String),
+    Select(String),
+    /// Highlights the next option, or the previous one.
+    MoveHighlight(bool),
+    SelectHighlighted,
+    Close,
+}
+
+impl Component for RemoteSelect {
+    type Properties = Props;
+    type Message = Msg;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut options_fetcher = OptionsFetcher::bridge(link.callback(Msg::Fetched));
+        options_fetcher.send(OptionsRequest {
+            url: props.url.clone(),
+        });
+        Self {
+            link,
+            props,
+            options_fetcher,
+            options: None,
+            filter: None,
+            highlighted: 0,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let url_changed = self.props.url != props.url;
+        let changed = self.props.neq_assign(props);
+        if url_changed {
+            self.options = None;
+            self.options_fetcher.send(OptionsRequest {
+                url: self.props.url.clone(),
+            });
+        }
+        changed
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Fetched(fetched) => {
+                // Responses to a previous URL are ignored
+                if fetched.url != self.props.url {
+                    return false;
+                }
+                self.options = Some(fetched.options);
+                true
+            }
+            Msg::Filter(filter) => {
+                self.filter = Some(filter);
+                self.highlighted = 0;
+                true
+            }
+            Msg::Select(value) => {
+                self.filter = None;
+                self.props.on_select.emit(JsonValue::String(value));
+                true
+            }
+            Msg::MoveHighlight(down) => {
+                let count = self.filtered_options().len();
+                if self.filter.is_none() {
+                    self.filter = Some(String::new());
+                    self.highlighted = 0;
+                } else if down && self.highlighted + 1 < count {
+                    self.highlighted += 1;
+                } else if !down {
+                    self.highlighted = self.highlighted.saturating_sub(1);
+                }
+                true
+            }
+            Msg::SelectHighlighted => match self.filtered_options().get(self.highlighted) {
+                Some(option) => {
+                    let value = option.value.clone();
+                    self.update(Msg::Select(value))
+                }
+                None => false,
+            },
+            Msg::Close => {
+                self.filter = None;
+                true
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        let options = match &self.options {
+            None => return html! { <p class="help">{ tr("Loading the options...") }</p> },
+            Some(Err(e)) => {
+                return html! {
+                    <p class="help is-danger">
+                        { tr!("Failed to fetch the options from {}: {}", self.props.url, e) }
+                    </p>
+                }
+            }
+            Some(Ok(options)) => options,
+        };
+
+        let selected_label = options
+            .iter()
+            .find(|option| Some(&option.value) == self.props.value.as_ref())
+            .map(SelectOption::label)
+            .or(self.props.value.as_deref())
+            .unwrap_or_default();
+        let (text, is_open) = match &self.filter {
+            Some(filter) => (filter.as_str(), true),
+            None => (selected_label, false),
+        };
+
+        let placeholder = self
+            .props
+            .placeholder
+            .as_deref()
+            .unwrap_or_else(|| tr("Type to search..."));
+
+        let option_id = |i: usize| format!("{}-option-{}", self.props.id, i);
+        let render_option = |(i, option): (usize, &SelectOption)| {
+            let value = option.value.clone();
+            let is_selected = Some(&option.value) == self.props.value.as_ref();
+            let mut class = vec!["dropdown-item"];
+            if is_selected {
+                class.push("is-active");
+            }
+            if i == self.highlighted {
+                class.push("is-highlighted");
+            }
+            html! {
+                <a
+                    class=class
+                    id=option_id(i)
+                    role="option"
+                    aria-selected=is_selected.to_string()
+                    onclick=self.link.callback(move |_: ClickEvent| Msg::Select(value.clone()))>
+                    { option.label() }
+                </a>
+            }
+        };
+        // The options are chosen with the arrow keys, and Enter
+        let on_keydown =
+            self.link
+                .batch_callback(|event: KeyDownEvent| match event.key().as_str() {
+                    "ArrowDown" | "ArrowUp" => {
+                        event.prevent_default();
+                        vec![Msg::MoveHighlight(event.key() == "ArrowDown")]
+                    }
+                    "Enter" => {
+                        event.prevent_default();
+                        vec![Msg::SelectHighlighted]
+                    }
+                    "Escape" => vec![Msg::Close],
+                    _ => vec![],
+                });
+        let options_id = format!("{}-options", self.props.id);
+
+        html! {
+            <div class=if is_open { "dropdown is-active remote-select" } else { "dropdown remote-select" }>
+                <div class="dropdown-trigger control has-icons-right">
+                    <input
+                        class="input"
+                        type="text"
+                        id=&self.props.id
+                        role="combobox"
+                        aria-autocomplete="list"
+                        aria-expanded=is_open.to_string()
+                        aria-controls=&options_id
+                        aria-activedescendant=if is_open { option_id(self.highlighted) } else { String::new() }
+                        placeholder=placeholder
+                        value=text
+                        oninput=self.link.callback(|input: InputData| Msg::Filter(input.value))
+                        onfocus=self.link.callback(|_| Msg::Filter(String::new()))
+                        onkeydown=on_keydown
+                        />
+                    <span class="icon is-small is-right">
+                        <i class="fas fa-search"></i>
+                    </span>
+                </div>
+                <div class="dropdown-menu">
+                    <div class="dropdown-content" id=options_id role="listbox">
+                        { for self.filtered_options().into_iter().enumerate().map(render_option) }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl RemoteSelect {
+    /// Returns the fetched options matching the typed text.
+    fn filtered_options(&self) -> Vec<&SelectOption> {
+        let filter = self.filter.as_deref().unwrap_or_default().to_lowercase();
+        match &self.options {
+            Some(Ok(options)) => options
+                .iter()
+                .filter(|option| option.label().to_lowercase().contains(&filter))
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{components::NeqAssign, i18n::tr};
+use yew::prelude::*;
+
+/// A scenario shipped with the app.
+pub struct Example {
+    /// Used by the scenarios extending this one, see `scenario::extend`.
+    pub name: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// The scenario, in JSON or in YAML.
+    pub source: &'static str,
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "readme",
+        title: "README",
+        description: "The README of a project, with its installation steps and its license.",
+        source: include_str!("../scenarios/readme.json"),
+    },
+    Example {
+        name: "nginx",
+        title: "Nginx reverse proxy",
+        description: "A server block forwarding some paths of a domain to other servers.",
+        source: include_str!("../scenarios/nginx.json"),
+    },
+    Example {
+        name: "systemd",
+        title: "systemd service",
+        description: "The unit file running a program as a service.",
+        source: include_str!("../scenarios/systemd.yaml"),
+    },
+];
+
+/// Finds an example by its name.
+pub fn find_example(name: &str) -> Option<&'static Example> {
+    EXAMPLES.iter().find(|example| example.name == name)
+}
+
+/// Cards of the example scenarios, to start with one of them.
+pub struct ScenarioGallery {
+    link: ComponentLink<Self>,
+    props: Props,
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    /// Receives the name of the chosen scenario, see `find_example`.
+    pub on_select: Callback<String>,
+}
+
+#[derive(Debug)]
+pub enum Msg {
+    Select(usize),
+}
+
+impl Component for ScenarioGallery {
+    type Properties = Props;
+    type Message = Msg;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self { link, props }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props.neq_assign(props)
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Select(i) => {
+                self.props.on_select.emit(EXAMPLES[i].name.to_owned());
+                false
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        let render_card = |(i, example): (usize, &Example)| {
+            html! {
+                <div class="column is-one-third">
+                    <div class="card scenario-card">
+                        <div class="card-content">
+                            <p class="title is-5">{ example.title }</p>
+                            <p>{ example.description }</p>
+                        </div>
+                        <footer class="card-footer">
+                            <a class="card-footer-item" onclick=self.link.callback(move |_| Msg::Select(i))>
+                                { tr("Use this scenario") }
+                            </a>
+                        </footer>
+                    </div>
+                </div>
+            }
+        };
+
+        html! {
+            <div class="columns is-multiline">
+                { for EXAMPLES.iter().enumerate().map(render_card) }
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::{validate, Format};
+
+    #[test]
+    fn examples_are_valid() {
+        for example in EXAMPLES {
+            let value = Format::detect(example.source, None)
+                .parse(example.source)
+                .unwrap();
+            assert_eq!(validate(&value), [], "{}", example.title);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{components::NeqAssign, i18n::tr};
+use stdweb::{js, web::window};
+use yew::prelude::*;
+
+/// What the user asked for in the `SessionManager`.
+#[derive(Debug)]
+pub enum SessionEvent {
+    /// Saves the current sessions under a name.
+    Save(String),
+    Load(String),
+    /// Renames a saved session, from the first name to the second.
+    Rename(String, String),
+    Delete(String),
+    Close,
+}
+
+/// A modal listing the sessions saved under a name, see `SessionStore`.
+pub struct SessionManager {
+    link: ComponentLink<Self>,
+    props: Props,
+    /// Name typed to save the current sessions.
+    new_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    /// Names of the saved sessions.
+    pub names: Vec<String>,
+    pub on_event: Callback<SessionEvent>,
+}
+
+#[derive(Debug)]
+pub enum Msg {
+    EditName(String),
+    Save,
+    Load(usize),
+    /// Asks for the new name of a saved session.
+    Rename(usize),
+    /// Asks for a confirmation before deleting a saved session.
+    Delete(usize),
+    Close,
+}
+
+impl Component for SessionManager {
+    type Properties = Props;
+    type Message = Msg;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            props,
+            new_name: String::new(),
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props.neq_assign(props)
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::EditName(name) => {
+                self.new_name = name;
+                true
+            }
+            Msg::Save => {
+                let name = self.new_name.trim().to_owned();
+                let exists = self.props.names.contains(&name);
+                if !exists || window().confirm(&tr!("Replace the session saved as '{}'?", name)) {
+                    self.props.on_event.emit(SessionEvent::Save(name));
+                    self.new_name.clear();
+                }
+                true
+            }
+            Msg::Load(i) => {
+                self.props
+                    .on_event
+                    .emit(SessionEvent::Load(self.props.names[i].clone()));
+                false
+            }
+            Msg::Rename(i) => {
+                let name = self.props.names[i].clone();
+                let new_name = js! { return prompt(@{tr("New name of the session:")}, @{&name}); }
+                    .into_string();
+                match new_name.as_deref().map(str::trim) {
+                    Some(new_name) if !new_name.is_empty() && new_name != name => {
+                        let event = SessionEvent::Rename(name, new_name.to_owned());
+                        self.props.on_event.emit(event);
+                    }
+                    _ => {}
+                }
+                false
+            }
+            Msg::Delete(i) => {
+                let name = self.props.names[i].clone();
+                if window().confirm(&tr!("Delete the session saved as '{}'?", name)) {
+                    self.props.on_event.emit(SessionEvent::Delete(name));
+                }
+                false
+            }
+            Msg::Close => {
+                self.props.on_event.emit(SessionEvent::Close);
+                false
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        let render_row = |(i, name): (usize, &String)| {
+            html! {
+                <tr>
+                    <td>{ name }</td>
+                    <td>
+                        <div class="buttons are-small is-right">
+                            <button class="button is-primary" onclick=self.link.callback(move |_| Msg::Load(i))>
+                                { tr("Load") }
+                            </button>
+                            <button class="button" onclick=self.link.callback(move |_| Msg::Rename(i))>
+                                { tr("Rename") }
+                            </button>
+                            <button class="button is-danger is-outlined" onclick=self.link.callback(move |_| Msg::Delete(i))>
+                                { tr("Delete") }
+                            </button>
+                        </div>
+                    </td>
+                </tr>
+            }
+        };
+        let sessions = if self.props.names.is_empty() {
+            html! { <p class="has-text-grey">{ tr("No session saved yet.") }</p> }
+        } else {
+            html! {
+                <table class="table is-fullwidth is-hoverable">
+                    <tbody>
+                        { for self.props.names.iter().enumerate().map(render_row) }
+                    </tbody>
+                </table>
+            }
+        };
+
+        html! {
+            <div class="modal is-active">
+                <div class="modal-background" onclick=self.link.callback(|_| Msg::Close)></div>
+                <div class="modal-card">
+                    <header class="modal-card-head">
+                        <p class="modal-card-title">{ tr("Saved sessions") }</p>
+                        <button class="delete" aria-label="close" onclick=self.link.callback(|_| Msg::Close)></button>
+                    </header>
+                    <section class="modal-card-body">
+                        <div class="field has-addons">
+                            <div class="control is-expanded">
+                                <input
+                                    class="input"
+                                    type="text"
+                                    placeholder=tr("Name of the session")
+                                    value=&self.new_name
+                                    oninput=self.link.callback(|input: InputData| Msg::EditName(input.value))
+                                    />
+                            </div>
+                            <div class="control">
+                                <button
+                                    class="button is-primary"
+                                    disabled=self.new_name.trim().is_empty()
+                                    onclick=self.link.callback(|_| Msg::Save)>
+                                    { tr("Save the open tabs") }
+                                </button>
+                            </div>
+                        </div>
+                        { sessions }
+                    </section>
+                </div>
+            </div>
+        }
+    }
+}
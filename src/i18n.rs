@@ -0,0 +1,120 @@
+//! Translations of the user interface, selected in the settings.
+//!
+//! The English texts are the keys of the translations, so that a missing
+//! translation falls back to English. Use `tr("...")` for plain texts, and
+//! `tr!("... {} ...", arg)` for texts with arguments.
+
+use crate::prelude::*;
+use lazy_static::lazy_static;
+use std::{cell::Cell, collections::HashMap};
+
+mod fr;
+
+/// Translates a text with arguments, which replace its `{}` in order.
+macro_rules! tr {
+    ($text:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format($crate::i18n::tr($text), &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+
+thread_local! {
+    static LANGUAGE: Cell<Language> = const { Cell::new(Language::English) };
+}
+
+lazy_static! {
+    static ref FRENCH: HashMap<&'static str, &'static str> =
+        fr::TRANSLATIONS.iter().copied().collect();
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: &'static [Language] = &[Language::English, Language::French];
+
+    /// The name of the language, in this language.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    fn translations(self) -> Option<&'static HashMap<&'static str, &'static str>> {
+        match self {
+            Language::English => None,
+            Language::French => Some(&FRENCH),
+        }
+    }
+}
+
+/// Sets the language of the texts translated from now on.
+pub fn set_language(language: Language) {
+    LANGUAGE.with(|current| current.set(language));
+}
+
+/// Translates an English text to the current language, or returns it as is
+/// if it has no translation.
+pub fn tr(text: &'static str) -> &'static str {
+    LANGUAGE
+        .with(Cell::get)
+        .translations()
+        .and_then(|translations| translations.get(text).copied())
+        .unwrap_or(text)
+}
+
+/// Replaces the `{}` of a translated text by the arguments, in order.
+pub fn format(text: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut parts = text.split("{}");
+    let mut formatted = parts.next().unwrap_or_default().to_owned();
+    for (i, part) in parts.enumerate() {
+        match args.get(i) {
+            Some(arg) => formatted.push_str(&arg.to_string()),
+            None => formatted.push_str("{}"),
+        }
+        formatted.push_str(part);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translate() {
+        set_language(Language::French);
+        assert_eq!(tr("Settings"), "Paramètres");
+        assert_eq!(tr("Not translated"), "Not translated");
+        assert_eq!(
+            tr!("Loaded the session '{}'.", "web"),
+            "Session « web » chargée."
+        );
+        set_language(Language::English);
+        assert_eq!(tr("Settings"), "Settings");
+    }
+
+    #[test]
+    fn translations_keep_the_arguments() {
+        for (text, translation) in fr::TRANSLATIONS {
+            assert_eq!(
+                text.matches("{}").count(),
+                translation.matches("{}").count(),
+                "{:?} is translated as {:?}",
+                text,
+                translation
+            );
+        }
+        let keys: std::collections::HashSet<_> =
+            fr::TRANSLATIONS.iter().map(|(text, _)| text).collect();
+        assert_eq!(
+            keys.len(),
+            fr::TRANSLATIONS.len(),
+            "Duplicated translations"
+        );
+    }
+}
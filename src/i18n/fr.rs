@@ -0,0 +1,602 @@
+//! French translations, by English text.
+
+pub const TRANSLATIONS: &[(&str, &str)] = &[
+    // App
+    ("New tab", "Nouvel onglet"),
+    ("Untitled scenario", "Scénario sans titre"),
+    (
+        "Loading the scenario at {}...",
+        "Chargement du scénario de {}...",
+    ),
+    ("{} isn't a text file.", "{} n'est pas un fichier texte."),
+    (
+        "Copied the share link to the clipboard.",
+        "Lien de partage copié dans le presse-papiers.",
+    ),
+    (
+        "Failed to copy the share link: {}",
+        "Impossible de copier le lien de partage : {}",
+    ),
+    ("Sent the output: {}.", "Résultat envoyé : {}."),
+    (
+        "Failed to send the output: {}",
+        "Impossible d'envoyer le résultat : {}",
+    ),
+    ("the server responded {}.", "le serveur a répondu {}."),
+    (
+        "the server is unreachable or doesn't allow cross-origin requests (CORS).",
+        "le serveur est injoignable ou n'autorise pas les requêtes cross-origin (CORS).",
+    ),
+    (
+        "Failed to post the changes to {}: {}",
+        "Impossible d'envoyer les modifications à {} : {}",
+    ),
+    (
+        "Send the rendered template to {}?",
+        "Envoyer le modèle rendu à {} ?",
+    ),
+    ("Post the output to {}", "Envoyer le résultat à {}"),
+    (
+        "Copied the rendered template to the clipboard.",
+        "Modèle rendu copié dans le presse-papiers.",
+    ),
+    (
+        "Failed to copy the rendered template: {}",
+        "Impossible de copier le modèle rendu : {}",
+    ),
+    ("Loaded the session '{}'.", "Session « {} » chargée."),
+    (
+        "The saved sessions are now encrypted.",
+        "Les sessions enregistrées sont désormais chiffrées.",
+    ),
+    (
+        "Failed to unlock the saved sessions: {}",
+        "Impossible de déverrouiller les sessions enregistrées : {}",
+    ),
+    (
+        "The open tabs are now saved unencrypted. The sessions saved under a name stay encrypted \
+         until they are saved again.",
+        "Les onglets ouverts sont désormais enregistrés sans chiffrement. Les sessions \
+         enregistrées sous un nom restent chiffrées jusqu'à leur prochain enregistrement.",
+    ),
+    (
+        "0 to never reload them automatically",
+        "0 pour ne jamais les recharger automatiquement",
+    ),
+    (
+        "Stopped reloading the scenario, change the reload interval to restart.",
+        "Le scénario n'est plus rechargé, modifiez l'intervalle de rechargement pour reprendre.",
+    ),
+    (
+        "The sessions are encrypted, and their passphrase wasn't entered.",
+        "Les sessions sont chiffrées, et leur phrase secrète n'a pas été saisie.",
+    ),
+    (
+        "The sessions are encrypted: they won't be saved nor restored until their passphrase is \
+         entered. Reload the page to enter it.",
+        "Les sessions sont chiffrées : elles ne seront ni enregistrées ni restaurées tant que \
+         leur phrase secrète n'est pas saisie. Rechargez la page pour la saisir.",
+    ),
+    ("Wrong passphrase.", "Phrase secrète incorrecte."),
+    (
+        "This isn't the passphrase of the sessions encrypted before. Forget it, and choose a new \
+         one? The sessions still encrypted with it can't be loaded anymore.",
+        "Ce n'est pas la phrase secrète des sessions chiffrées auparavant. L'oublier, et en \
+         choisir une nouvelle ? Les sessions encore chiffrées avec elle ne pourront plus être \
+         chargées.",
+    ),
+    (
+        "Passphrase of the saved sessions:",
+        "Phrase secrète des sessions enregistrées :",
+    ),
+    (
+        "Failed to export the autosave: {}",
+        "Impossible d'exporter la sauvegarde automatique : {}",
+    ),
+    (
+        "Discarded the invalid autosave.",
+        "Sauvegarde automatique invalide supprimée.",
+    ),
+    (
+        "Failed to save the value of '{}'.",
+        "Impossible d'enregistrer la valeur de « {} ».",
+    ),
+    (
+        "Fix the inputs of this step before going to the next one.",
+        "Corrigez les champs de cette étape avant de passer à la suivante.",
+    ),
+    ("Undo", "Annuler"),
+    ("Removed the element at '{}'.", "Élément « {} » supprimé."),
+    (
+        "Failed to undo the removal.",
+        "Impossible d'annuler la suppression.",
+    ),
+    (
+        "The tab of the removed element was closed.",
+        "L'onglet de l'élément supprimé a été fermé.",
+    ),
+    (
+        "Nothing loaded. Start from an example, or open your own scenario from the menu above.",
+        "Rien n'est chargé. Partez d'un exemple, ou ouvrez votre propre scénario depuis le menu \
+         ci-dessus.",
+    ),
+    ("Wonderful footer", "Magnifique pied de page"),
+    ("Imported the session.", "Session importée."),
+    (
+        "Loading the base scenario at {}...",
+        "Chargement du scénario de base de {}...",
+    ),
+    (
+        "The scenario has errors, see their details above the inputs.",
+        "Le scénario contient des erreurs, voir leurs détails au-dessus des champs.",
+    ),
+    (
+        "Failed to deserialize the scenario",
+        "Impossible de lire le scénario",
+    ),
+    (
+        "Failed to load the received scenario.",
+        "Impossible de charger le scénario reçu.",
+    ),
+    (
+        "Failed to load the shared session.",
+        "Impossible de charger la session partagée.",
+    ),
+    (
+        "Ignored the data of the link, which has no scenario.",
+        "Données du lien ignorées, car il n'a pas de scénario.",
+    ),
+    (
+        "Ignored the data of the link: expected an object.",
+        "Données du lien ignorées : un objet était attendu.",
+    ),
+    (
+        "Ignored the invalid data of the link.",
+        "Données invalides du lien ignorées.",
+    ),
+    ("Invalid base64.", "Base64 invalide."),
+    ("Invalid JSON.", "JSON invalide."),
+    (
+        "Failed to load the scenario at {}: the server responded {}.",
+        "Impossible de charger le scénario de {} : le serveur a répondu {}.",
+    ),
+    (
+        "Failed to load the scenario at {}: the server is unreachable or doesn't allow \
+         cross-origin requests (CORS).",
+        "Impossible de charger le scénario de {} : le serveur est injoignable ou n'autorise pas \
+         les requêtes cross-origin (CORS).",
+    ),
+    (
+        "The scenario must have a template.",
+        "Le scénario doit avoir un modèle.",
+    ),
+    ("The server responded {}", "Le serveur a répondu {}"),
+    (
+        "Too many scenarios extend each other, maybe one of them extends itself.",
+        "Trop de scénarios s'étendent les uns les autres, peut-être que l'un d'eux s'étend \
+         lui-même.",
+    ),
+    (
+        "Unknown example scenario '{}'.",
+        "Scénario d'exemple inconnu '{}'.",
+    ),
+    (
+        "Failed to load the scenario at {}: {}",
+        "Impossible de charger le scénario de {} : {}",
+    ),
+    (
+        "Loading the inputs data at {}...",
+        "Chargement des données des champs de {}...",
+    ),
+    (
+        "Ignored the inputs data at {}: expected an object.",
+        "Données des champs de {} ignorées : un objet était attendu.",
+    ),
+    (
+        "Ignored the invalid inputs data at {}: {}",
+        "Données des champs invalides de {} ignorées : {}",
+    ),
+    (
+        "Failed to load the inputs data at {}: the server responded {}.",
+        "Impossible de charger les données des champs de {} : le serveur a répondu {}.",
+    ),
+    (
+        "Failed to load the inputs data at {}: the server is unreachable or doesn't allow \
+         cross-origin requests (CORS).",
+        "Impossible de charger les données des champs de {} : le serveur est injoignable ou \
+         n'autorise pas les requêtes cross-origin (CORS).",
+    ),
+    (
+        "Failed to load the inputs data at {}: {}",
+        "Impossible de charger les données des champs de {} : {}",
+    ),
+    (
+        "Only a scenario loaded from a URL can be reloaded.",
+        "Seul un scénario chargé depuis une URL peut être rechargé.",
+    ),
+    (
+        "Reloading the scenario at {}...",
+        "Rechargement du scénario de {}...",
+    ),
+    (
+        "Failed to reload the scenario.",
+        "Impossible de recharger le scénario.",
+    ),
+    (
+        "Reloaded the changes of the scenario at {}.",
+        "Modifications du scénario de {} rechargées.",
+    ),
+    (
+        "{} isn't a JSON or YAML scenario.",
+        "{} n'est pas un scénario JSON ou YAML.",
+    ),
+    ("Failed to read {}: {}", "Impossible de lire {} : {}"),
+    (
+        "Restored previous session.",
+        "Session précédente restaurée.",
+    ),
+    (
+        "Failed to restore the previous session.",
+        "Impossible de restaurer la session précédente.",
+    ),
+    (
+        "The previous session is invalid, and wasn't restored.",
+        "La session précédente est invalide, et n'a pas été restaurée.",
+    ),
+    (
+        "The previous session was corrupt, and was discarded.",
+        "La session précédente était corrompue, et a été supprimée.",
+    ),
+    ("Export raw", "Exporter telle quelle"),
+    ("Discard", "Supprimer"),
+    (
+        "Nothing to restore from local storage.",
+        "Rien à restaurer depuis le stockage local.",
+    ),
+    (
+        "Saved the session as '{}'.",
+        "Session enregistrée sous « {} ».",
+    ),
+    (
+        "Renamed the session '{}' to '{}'.",
+        "Session « {} » renommée en « {} ».",
+    ),
+    ("Deleted the session '{}'.", "Session « {} » supprimée."),
+    (
+        "Failed to save the session, the storage of the browser may be full.",
+        "Impossible d'enregistrer la session, le stockage du navigateur est peut-être plein.",
+    ),
+    (
+        "The open tabs can't be saved in the browser, probably because they are too big. Download \
+         the active one, so as to load it later?\n\nSetting a maximum saved size in the settings \
+         stops trying to save them.",
+        "Les onglets ouverts ne peuvent pas être enregistrés dans le navigateur, sans doute parce \
+         qu'ils sont trop gros. Télécharger l'onglet actif, pour le charger plus tard \
+         ?\n\nDéfinir une taille maximale d'enregistrement dans les paramètres arrête les \
+         tentatives d'enregistrement.",
+    ),
+    (
+        "Nothing to export, load a scenario first.",
+        "Rien à exporter, chargez d'abord un scénario.",
+    ),
+    (
+        "Failed to export the session: {}",
+        "Impossible d'exporter la session : {}",
+    ),
+    (
+        "Nothing to share, load a scenario first.",
+        "Rien à partager, chargez d'abord un scénario.",
+    ),
+    (
+        "Failed to share the session: {}",
+        "Impossible de partager la session : {}",
+    ),
+    (
+        "The scenario can't be loaded",
+        "Le scénario ne peut pas être chargé",
+    ),
+    (
+        "The scenario may have mistakes",
+        "Le scénario contient peut-être des erreurs",
+    ),
+    (
+        "Drop a JSON or YAML scenario to load it",
+        "Déposez un scénario JSON ou YAML pour le charger",
+    ),
+    ("Previous", "Précédent"),
+    ("Next", "Suivant"),
+    ("Restoring the sessions...", "Restauration des sessions..."),
+    ("Reading the file...", "Lecture du fichier..."),
+    ("Loading the scenario...", "Chargement du scénario..."),
+    ("Inputs", "Champs"),
+    ("Filter the inputs", "Filtrer les champs"),
+    (
+        "No input matches the filter.",
+        "Aucun champ ne correspond au filtre.",
+    ),
+    ("Collapse all the groups", "Replier tous les groupes"),
+    ("Expand all the groups", "Déplier tous les groupes"),
+    ("Render", "Rendre"),
+    ("Rendering...", "Rendu en cours..."),
+    ("Send", "Envoyer"),
+    ("Show the output", "Afficher le résultat"),
+    ("Show the changes", "Afficher les changements"),
+    ("Unpin", "Désépingler"),
+    (
+        "Keep this output, to see how the next changes of the inputs alter it",
+        "Garder ce résultat, pour voir comment les prochaines modifications des champs le changent",
+    ),
+    ("Pin", "Épingler"),
+    ("Data", "Données"),
+    ("Hide the data", "Masquer les données"),
+    ("Show the data", "Afficher les données"),
+    (
+        "The template failed to compile, see the template editor.",
+        "La compilation du modèle a échoué, voir l'éditeur de modèle.",
+    ),
+    (
+        "Failed to read the clipboard: {}",
+        "Impossible de lire le presse-papiers : {}",
+    ),
+    (
+        "The clipboard isn't JSON.",
+        "Le presse-papiers ne contient pas de JSON.",
+    ),
+    ("Failed to paste the JSON.", "Impossible de coller le JSON."),
+    (
+        "The pasted JSON replaced the values of: {}.",
+        "Le JSON collé a remplacé les valeurs de : {}.",
+    ),
+    (
+        "Failed to apply the received changes.",
+        "Impossible d'appliquer les modifications reçues.",
+    ),
+    // Live sync
+    (
+        "Failed to sync the inputs with {}: {}",
+        "Impossible de synchroniser les champs avec {} : {}",
+    ),
+    (
+        "Failed to sync the inputs with {}.",
+        "Impossible de synchroniser les champs avec {}.",
+    ),
+    ("Expected a JSON Patch.", "Un JSON Patch était attendu."),
+    ("Ignored a message of {}.", "Un message de {} a été ignoré."),
+    (
+        "The received changes don't apply to the inputs of this tab.",
+        "Les modifications reçues ne s'appliquent pas aux champs de cet onglet.",
+    ),
+    (
+        "Syncing the inputs live with {}.",
+        "Les champs sont synchronisés en direct avec {}.",
+    ),
+    (
+        "Stopped syncing the inputs with {}.",
+        "Les champs ne sont plus synchronisés avec {}.",
+    ),
+    ("Snapshot {}", "Instantané {}"),
+    ("Name of the snapshot:", "Nom de l'instantané :"),
+    (
+        "Replace the inputs by the snapshot '{}'?",
+        "Remplacer les champs par l'instantané « {} » ?",
+    ),
+    (
+        "Failed to reset the inputs.",
+        "Impossible de réinitialiser les champs.",
+    ),
+    ("Template", "Modèle"),
+    ("Template: {}", "Modèle : {}"),
+    (
+        "Failed to render the data",
+        "Impossible de rendre les données",
+    ),
+    (
+        "Rendered in {} ms, {} in {} lines.",
+        "Rendu en {} ms, {} en {} lignes.",
+    ),
+    (
+        "The template may loop too much, eg. with nested #each, or try the manual renders in the \
+         settings.",
+        "Le modèle boucle peut-être trop, par ex. avec des #each imbriqués, ou essayez les rendus \
+         manuels dans les paramètres.",
+    ),
+    (
+        "These variables resolved to nothing:",
+        "Ces variables ne correspondent à rien :",
+    ),
+    (
+        "These required inputs are empty:",
+        "Ces champs obligatoires sont vides :",
+    ),
+    ("Copy", "Copier"),
+    ("Download", "Télécharger"),
+    (
+        "Fill the required inputs first",
+        "Remplissez d'abord les champs obligatoires",
+    ),
+    ("These inputs are invalid:", "Ces champs sont invalides :"),
+    (
+        "Fix the invalid inputs first",
+        "Corrigez d'abord les champs invalides",
+    ),
+    ("Rendered template", "Modèle rendu"),
+    ("Example scenarios", "Scénarios d'exemple"),
+    ("By {}", "Par {}"),
+    // Navbar
+    (
+        "Reset all the inputs to their default value?",
+        "Remettre tous les champs à leur valeur par défaut ?",
+    ),
+    (
+        "URL of the scenario to load, which can be a GitHub file or a gist:",
+        "URL du scénario à charger, qui peut être un fichier GitHub ou un gist :",
+    ),
+    ("{} not implemented yet.", "{} n'est pas encore implémenté."),
+    ("Scenario", "Scénario"),
+    (
+        "Open a scenario or a session...",
+        "Ouvrir un scénario ou une session...",
+    ),
+    ("Load from URL", "Charger depuis une URL"),
+    ("Reload from its URL", "Recharger depuis son URL"),
+    ("Export the session", "Exporter la session"),
+    ("Saved sessions...", "Sessions enregistrées..."),
+    ("Reset all the inputs", "Réinitialiser tous les champs"),
+    ("Unload the workspace", "Décharger l'espace de travail"),
+    (
+        "Reload from local storage",
+        "Recharger depuis le stockage local",
+    ),
+    ("Load a debug scenario", "Charger un scénario de débogage"),
+    ("Help", "Aide"),
+    ("User guide", "Guide d'utilisation"),
+    ("About", "À propos"),
+    ("Report an issue", "Signaler un problème"),
+    ("Open a new tab", "Ouvrir un nouvel onglet"),
+    (
+        "Copy a link to the scenario and its inputs",
+        "Copier un lien vers le scénario et ses champs",
+    ),
+    ("Share", "Partager"),
+    ("Settings", "Paramètres"),
+    ("Strict mode", "Mode strict"),
+    (
+        "Tera templates always fail on undefined variables.",
+        "Les modèles Tera échouent toujours sur les variables non définies.",
+    ),
+    ("Max saved size (KB)", "Taille max. enregistrée (Ko)"),
+    (
+        "The bigger tabs aren't saved in the browser, 0 for no limit",
+        "Les onglets plus gros ne sont pas enregistrés dans le navigateur, 0 pour aucune limite",
+    ),
+    (
+        "Restore the tabs on startup",
+        "Restaurer les onglets au démarrage",
+    ),
+    (
+        "Encrypt the saved sessions",
+        "Chiffrer les sessions enregistrées",
+    ),
+    ("Render manually", "Rendre manuellement"),
+    ("Layout", "Disposition"),
+    ("Split", "Côte à côte"),
+    ("Output", "Résultat"),
+    ("Theme", "Thème"),
+    ("Light", "Clair"),
+    ("Dark", "Sombre"),
+    ("Auto", "Auto"),
+    ("Language", "Langue"),
+    ("Render delay (ms)", "Délai de rendu (ms)"),
+    (
+        "Reload remote scenarios (s)",
+        "Recharger les scénarios distants (s)",
+    ),
+    ("Webhook URL", "URL du webhook"),
+    (
+        "Overrides the one of the scenario",
+        "Remplace celle du scénario",
+    ),
+    ("Webhook headers", "En-têtes du webhook"),
+    ("Send the data along", "Envoyer aussi les données"),
+    ("Live sync URL", "URL de synchronisation en direct"),
+    ("{} is editing it", "{} est en train de le modifier"),
+    ("Changes webhook URL", "URL du webhook des modifications"),
+    (
+        "Posts the changes of the inputs to this URL, as JSON Patches",
+        "Envoie les modifications des champs à cette URL, en JSON Patch",
+    ),
+    (
+        "Edits the inputs along with the other pages connected to this WebSocket server",
+        "Modifie les champs avec les autres pages connectées à ce serveur WebSocket",
+    ),
+    // Notifications
+    ("Hide the details", "Masquer les détails"),
+    ("Show the details", "Afficher les détails"),
+    ("From: {}", "Source : {}"),
+    // Other components
+    ("Loading the options...", "Chargement des options..."),
+    (
+        "Failed to fetch the options from {}: {}",
+        "Impossible de récupérer les options de {} : {}",
+    ),
+    ("Type to search...", "Tapez pour rechercher..."),
+    ("Use this scenario", "Utiliser ce scénario"),
+    (
+        "Replace the session saved as '{}'?",
+        "Remplacer la session enregistrée sous « {} » ?",
+    ),
+    ("New name of the session:", "Nouveau nom de la session :"),
+    (
+        "Delete the session saved as '{}'?",
+        "Supprimer la session enregistrée sous « {} » ?",
+    ),
+    ("Load", "Charger"),
+    ("Rename", "Renommer"),
+    ("Delete", "Supprimer"),
+    (
+        "No session saved yet.",
+        "Aucune session enregistrée pour l'instant.",
+    ),
+    ("Saved sessions", "Sessions enregistrées"),
+    ("Name of the session", "Nom de la session"),
+    ("Save the open tabs", "Enregistrer les onglets ouverts"),
+    // Views
+    ("This value is required.", "Cette valeur est obligatoire."),
+    (
+        "No view is registered for the inputs of type '{}'.",
+        "Aucune vue n'est enregistrée pour les champs de type « {} ».",
+    ),
+    ("Unit", "Unité"),
+    ("Choose...", "Choisir..."),
+    ("Duplicate", "Dupliquer"),
+    ("Drag to reorder", "Glisser pour réordonner"),
+    (
+        "Drag to reorder, or use the up and down arrow keys",
+        "Glisser pour réordonner, ou utiliser les flèches haut et bas",
+    ),
+    ("Add an element", "Ajouter un élément"),
+    ("Remove the last element", "Retirer le dernier élément"),
+    ("Move up", "Monter"),
+    ("Move down", "Descendre"),
+    ("Remove", "Supprimer"),
+    ("Add a row", "Ajouter une ligne"),
+    (
+        "Type and press Enter to add",
+        "Tapez puis Entrée pour ajouter",
+    ),
+    ("Required", "Obligatoire"),
+    (
+        "Reset to the default value",
+        "Remettre la valeur par défaut",
+    ),
+    (
+        "Paste JSON from the clipboard",
+        "Coller du JSON depuis le presse-papiers",
+    ),
+    ("More information", "Plus d'informations"),
+    (
+        "The output didn't change since it was pinned.",
+        "Le résultat n'a pas changé depuis qu'il a été épinglé.",
+    ),
+    ("No match", "Aucun résultat"),
+    ("{} of {}", "{} sur {}"),
+    ("Find in the output", "Rechercher dans le résultat"),
+    ("Previous match", "Résultat précédent"),
+    ("Next match", "Résultat suivant"),
+    ("Restore", "Restaurer"),
+    ("Hide the differences", "Masquer les différences"),
+    ("Compare", "Comparer"),
+    (
+        "Save the current inputs to restore them, or to compare them, later.",
+        "Enregistrez les champs actuels pour les restaurer, ou les comparer, plus tard.",
+    ),
+    ("Snapshots", "Instantanés"),
+    ("Take a snapshot", "Prendre un instantané"),
+    (
+        "The inputs didn't change since '{}'.",
+        "Les champs n'ont pas changé depuis « {} ».",
+    ),
+    ("Input", "Champ"),
+    ("In '{}'", "Dans « {} »"),
+    ("Now", "Maintenant"),
+];
@@ -0,0 +1,68 @@
+//! Functions set on `window.liveHandlebars`, for the page embedding the app
+//! to drive it without simulating clicks, eg.
+//! `liveHandlebars.setInput("servers.0.name", "web")`.
+
+use crate::{
+    agents::{StateStore, StoreRequest},
+    app::{App, Msg},
+    template_engine::Rendered,
+    Path,
+};
+use std::{cell::RefCell, rc::Rc};
+use stdweb::js;
+use yew::ComponentLink;
+
+/// Sets the functions on `window.liveHandlebars`:
+/// - `loadScenario(scenario)` loads a scenario, given as an object or as
+///   JSON or YAML text, like a dropped file. Its errors are notified.
+/// - `setInput(path, value)` edits the value at a path of the inputs data,
+///   written with dots or as a JSON Pointer. It throws if the path is
+///   invalid, and the value is validated like a typed one.
+/// - `getRenderedOutput()` returns the output of the last render of the
+///   template shown, or `null` if it wasn't rendered yet.
+pub fn expose(link: &ComponentLink<App>, rendered: &Rc<RefCell<Option<Rendered>>>) {
+    let load_scenario = {
+        let link = link.clone();
+        move |source: String| link.send_message(Msg::FetchedScenario(source, None))
+    };
+    let edit = StateStore::callback(|(path, value)| StoreRequest::EditedInput(path, value));
+    let set_input = move |path: String, value: String| -> Option<String> {
+        let path: Path = match path.parse() {
+            Ok(path) => path,
+            Err(e) => return Some(format!("{}", e)),
+        };
+        match serde_json::from_str(&value) {
+            Ok(value) => {
+                edit.emit((path, value));
+                None
+            }
+            Err(e) => Some(format!("Invalid value: {}", e)),
+        }
+    };
+    let get_rendered_output = {
+        let rendered = rendered.clone();
+        move || -> Option<String> {
+            let rendered = rendered.borrow();
+            rendered.as_ref().map(|rendered| rendered.output.clone())
+        }
+    };
+    js! { @(no_return)
+        var load_scenario = @{load_scenario};
+        var set_input = @{set_input};
+        var get_rendered_output = @{get_rendered_output};
+        window.liveHandlebars = {
+            loadScenario: function(scenario) {
+                load_scenario(typeof scenario === "string" ? scenario : JSON.stringify(scenario));
+            },
+            setInput: function(path, value) {
+                var error = set_input(String(path), JSON.stringify(value === undefined ? null : value));
+                if (error) {
+                    throw new Error(error);
+                }
+            },
+            getRenderedOutput: function() {
+                return get_rendered_output();
+            },
+        };
+    }
+}
@@ -1,13 +1,23 @@
 #![recursion_limit = "1024"]
 
+#[macro_use]
+mod i18n;
+
 mod agents;
 pub mod app;
+mod clipboard;
 mod components;
-mod inputs;
-mod json_path;
+mod js_api;
 mod prelude;
-mod scenario;
-mod template_engine;
+mod route;
+mod session_store;
+mod settings;
+mod share;
+mod state;
 mod views;
 
-pub use json_path::{InputsData, Path};
+// The modules of the core, as if they were part of this crate
+use live_handlebars_core::{crdt, inputs, scenario, template_engine, webhook};
+
+pub use live_handlebars_core::{AppError, ArrayMerge, InputsData, PatchOp, Path};
+pub use views::{register_input_view, CustomInputView};
@@ -5,4 +5,4 @@ pub use serde::{Deserialize, Serialize};
 pub use serde_json::Value as JsonValue;
 pub use yew::prelude::*;
 
-pub use crate::components::NeqAssign;
+pub use crate::{components::NeqAssign, i18n::tr};
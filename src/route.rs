@@ -0,0 +1,59 @@
+//! The pages of the app, as paths of the URL, so that they can be linked to
+//! and the back button of the browser goes back to the previous one. They
+//! are changed through the `RouteAgent` of yew-router, eg. by a
+//! `RouterAnchor<Route>`.
+
+use yew_router::Switch;
+
+/// The variants are matched in order, and the home page matches any path.
+#[derive(Switch, Debug, Clone, PartialEq)]
+pub enum Route {
+    /// An example scenario by name, at `/scenario/<name>`, see
+    /// `find_example`.
+    #[to = "/scenario/{name}"]
+    Scenario(String),
+    /// The settings opened from the navbar, at `/settings`.
+    #[to = "/settings"]
+    Settings,
+    /// The example scenarios, at `/gallery`.
+    #[to = "/gallery"]
+    Gallery,
+    /// The scenario of the active tab, at `/`.
+    #[to = "/"]
+    Home,
+}
+
+impl From<yew_router::route::Route> for Route {
+    /// Unknown paths are the home page.
+    fn from(route: yew_router::route::Route) -> Self {
+        Self::switch(route).unwrap_or(Route::Home)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_paths() {
+        for route in [
+            Route::Home,
+            Route::Scenario("nginx".to_owned()),
+            Route::Settings,
+            Route::Gallery,
+        ]
+        .iter()
+        {
+            let path = yew_router::route::Route::from(route.clone());
+            assert_eq!(&Route::from(path), route);
+        }
+        let parse = |path| Route::from(yew_router::route::Route::new_no_state(path));
+        assert_eq!(
+            parse("/scenario/nginx"),
+            Route::Scenario("nginx".to_owned())
+        );
+        assert_eq!(parse("/gallery/"), Route::Gallery);
+        assert_eq!(parse("/unknown/page"), Route::Home);
+        assert_eq!(parse("/?scenario=nginx"), Route::Home);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{prelude::*, AppError};
+use lazy_static::lazy_static;
+
+mod backend;
+mod crypto;
+mod indexed_db;
+mod migrate;
+pub use backend::{Backend, LocalStorage};
+pub use crypto::{Crypto, DecryptionError, LockedError, UnlockError};
+pub use indexed_db::IndexedDb;
+pub use migrate::NewerSessionError;
+
+lazy_static! {
+    /// The state saved after every change. It is kept at the key used before
+    /// the sessions could be named, so that it is still restored.
+    static ref AUTOSAVE_KEY: String = format!("totorigolo.{}.state", env!("CARGO_PKG_NAME"));
+    /// The names of the saved sessions, in the order they were saved.
+    static ref INDEX_KEY: String = format!("totorigolo.{}.sessions", env!("CARGO_PKG_NAME"));
+}
+
+fn session_key(name: &str) -> String {
+    format!("totorigolo.{}.session.{}", env!("CARGO_PKG_NAME"), name)
+}
+
+/// Sessions saved in the browser: the autosaved one, and the ones saved
+/// under a name by the user.
+///
+/// They are saved in the local storage, unless they exceed its quota, eg.
+/// because of big files or data sets, in which case they are saved in
+/// IndexedDB.
+///
+/// They can be encrypted with a passphrase, see `Crypto`. The names of the
+/// saved sessions are never encrypted.
+pub struct SessionStore {
+    local: LocalStorage,
+    indexed_db: IndexedDb,
+    crypto: Crypto,
+    /// Receives the errors of the asynchronous saves.
+    on_error: Callback<String>,
+    /// Sessions bigger than this, in bytes, aren't autosaved.
+    max_autosave_size: Option<usize>,
+    /// Whether the sessions are encrypted when saved. The encrypted ones are
+    /// always decrypted when restored, if the passphrase was entered.
+    encrypt: bool,
+}
+
+impl SessionStore {
+    pub fn new(encrypt: bool, on_error: Callback<String>) -> Self {
+        Self {
+            local: LocalStorage,
+            indexed_db: IndexedDb::new(on_error.clone()),
+            crypto: Crypto::new(),
+            on_error,
+            max_autosave_size: None,
+            encrypt,
+        }
+    }
+
+    /// Stops autosaving the sessions bigger than `max_kb` kilobytes, eg.
+    /// because of big data sets. There is no limit if `max_kb` is 0.
+    pub fn set_max_autosave_kb(&mut self, max_kb: u64) {
+        self.max_autosave_size = Some(max_kb as usize * 1024).filter(|size| *size > 0);
+    }
+
+    pub fn set_encryption(&mut self, encrypt: bool) {
+        self.encrypt = encrypt;
+    }
+
+    /// Enters the passphrase encrypting the sessions, see `Crypto::unlock`.
+    pub fn unlock(&self, passphrase: &str, callback: Callback<Result<(), UnlockError>>) {
+        self.crypto.unlock(passphrase, callback);
+    }
+
+    /// See `Crypto::forget`.
+    pub fn forget_passphrase(&self) {
+        self.crypto.forget();
+    }
+
+    pub fn autosave(&mut self, state: &impl Serialize) -> Result<()> {
+        if let Some(max_size) = self.max_autosave_size {
+            let size = serde_json::to_string(state)?.len();
+            if size > max_size {
+                debug!("Not autosaving {} bytes, above the limit.", size);
+                return Ok(());
+            }
+        }
+        self.store(&AUTOSAVE_KEY, state)
+    }
+
+    pub fn restore_autosave(&self, callback: Callback<Result<JsonValue>>) {
+        self.restore(&AUTOSAVE_KEY, callback);
+    }
+
+    pub fn remove_autosave(&mut self) {
+        self.remove(&AUTOSAVE_KEY);
+    }
+
+    /// Returns the names of the saved sessions.
+    pub fn names(&self) -> Vec<String> {
+        self.local
+            .get(&INDEX_KEY)
+            .and_then(|names| serde_json::from_str(&names).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves a session, replacing the one with the same name if any.
+    pub fn save(&mut self, name: &str, state: &impl Serialize) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("A session needs a name.");
+        }
+
+        self.store(&session_key(name), state)?;
+        let mut names = self.names();
+        if !names.iter().any(|saved| saved == name) {
+            names.push(name.to_owned());
+            self.store_names(&names)?;
+        }
+        Ok(())
+    }
+
+    pub fn load(&self, name: &str, callback: Callback<Result<JsonValue>>) {
+        let name_inner = name.to_owned();
+        let callback = Callback::from(move |state: Result<JsonValue>| {
+            callback.emit(
+                state.with_context(|| format!("Failed to load the session '{}'.", name_inner)),
+            )
+        });
+        self.restore(&session_key(name), callback);
+    }
+
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<()> {
+        let new_name = new_name.trim();
+        let mut names = self.names();
+        if new_name.is_empty() {
+            bail!("A session needs a name.");
+        }
+        if names.iter().any(|saved| saved == new_name) {
+            bail!("A session is already saved as '{}'.", new_name);
+        }
+        let position = names
+            .iter()
+            .position(|saved| saved == name)
+            .ok_or_else(|| anyhow!("No session is saved as '{}'.", name))?;
+
+        // The session is in one of them
+        self.local
+            .rename(&session_key(name), &session_key(new_name));
+        self.indexed_db
+            .rename(&session_key(name), &session_key(new_name));
+        names[position] = new_name.to_owned();
+        self.store_names(&names)
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<()> {
+        self.remove(&session_key(name));
+        let mut names = self.names();
+        names.retain(|saved| saved != name);
+        self.store_names(&names)
+    }
+
+    fn store_names(&mut self, names: &[String]) -> Result<()> {
+        self.local.store(&INDEX_KEY, &serde_json::to_string(names)?)
+    }
+
+    /// Saves in the local storage, or in IndexedDB if it doesn't fit.
+    ///
+    /// The encrypted sessions are saved asynchronously, so their errors are
+    /// sent to `on_error`. They can't be saved until the passphrase is
+    /// entered, see `LockedError`.
+    fn store(&mut self, key: &str, state: &impl Serialize) -> Result<()> {
+        let json = serde_json::to_string(&migrate::Envelope::new(state))?;
+        if !self.encrypt {
+            return write(self.local, self.indexed_db.clone(), key, &json);
+        }
+        if !self.crypto.is_unlocked() {
+            return Err(LockedError.into());
+        }
+
+        let (local, indexed_db, key) = (self.local, self.indexed_db.clone(), key.to_owned());
+        let on_error = self.on_error.clone();
+        let on_encrypted = Callback::from(move |encrypted: Result<String, String>| {
+            let result = encrypted
+                .map_err(|e| anyhow!(e))
+                .and_then(|encrypted| write(local, indexed_db.clone(), &key, &encrypted));
+            if let Err(e) = result {
+                on_error.emit(format!("{:?}", e));
+            }
+        });
+        self.crypto.encrypt(&json, on_encrypted);
+        Ok(())
+    }
+
+    fn restore(&self, key: &str, callback: Callback<Result<JsonValue>>) {
+        let crypto = self.crypto;
+        let parse = Callback::from(move |saved: Result<Option<String>>| {
+            let callback = callback.clone();
+            let json = match saved {
+                Ok(Some(json)) => json,
+                Ok(None) => return callback.emit(Err(anyhow!("Nothing is saved."))),
+                Err(e) => return callback.emit(Err(e)),
+            };
+            let parse = move |json: String| {
+                serde_json::from_str(&json)
+                    .map_err(|e| AppError::Storage(format!("Invalid saved session: {}", e)).into())
+                    .and_then(migrate::migrate)
+            };
+            if Crypto::is_encrypted(&json) {
+                let on_decrypted = Callback::from(move |decrypted: Result<String, String>| {
+                    callback.emit(match decrypted {
+                        Ok(json) => parse(json),
+                        Err(e) => Err(DecryptionError(e).into()),
+                    })
+                });
+                crypto.decrypt(&json, on_decrypted);
+            } else {
+                callback.emit(parse(json));
+            }
+        });
+
+        let indexed_db = self.indexed_db.clone();
+        let key_inner = key.to_owned();
+        let from_local = Callback::from(move |local: Result<Option<String>, String>| {
+            if let Ok(Some(json)) = local {
+                parse.emit(Ok(Some(json)));
+                return;
+            }
+
+            let parse = parse.clone();
+            let from_indexed_db = Callback::from(move |result: Result<Option<String>, String>| {
+                parse.emit(result.map_err(|e| anyhow!("Failed to read IndexedDB: {}", e)))
+            });
+            indexed_db.restore(&key_inner, from_indexed_db);
+        });
+        self.local.restore(key, from_local);
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.local.remove(key);
+        self.indexed_db.remove(key);
+    }
+}
+
+/// Writes in the local storage, or in IndexedDB if it doesn't fit.
+fn write(mut local: LocalStorage, mut indexed_db: IndexedDb, key: &str, json: &str) -> Result<()> {
+    match local.store(key, json) {
+        Ok(()) => {
+            // Removes the previous version, if it was too big
+            indexed_db.remove(key);
+            Ok(())
+        }
+        Err(e) => {
+            debug!(
+                "Saving '{}' in IndexedDB, the local storage failed: {}",
+                key, e
+            );
+            local.remove(key);
+            indexed_db.store(key, json)
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::prelude::*;
+use stdweb::js;
+
+/// A key-value store of the browser, in which the sessions are saved as
+/// JSON.
+pub trait Backend {
+    /// Fails when the value doesn't fit, eg. because of the quota.
+    fn store(&mut self, key: &str, value: &str) -> Result<()>;
+    /// Sends the value at the key, or `None` if there is none.
+    fn restore(&self, key: &str, callback: Callback<Result<Option<String>, String>>);
+    /// Moves the value at the key, if any, to another key.
+    fn rename(&mut self, key: &str, new_key: &str);
+    fn remove(&mut self, key: &str);
+}
+
+/// The local storage, which is synchronous but only holds a few megabytes.
+///
+/// Unlike `StorageService`, which panics when the quota is exceeded, it
+/// reports the errors.
+#[derive(Debug, Clone, Copy)]
+pub struct LocalStorage;
+
+impl LocalStorage {
+    pub fn get(&self, key: &str) -> Option<String> {
+        js! { return localStorage.getItem(@{key}); }.into_string()
+    }
+}
+
+impl Backend for LocalStorage {
+    fn store(&mut self, key: &str, value: &str) -> Result<()> {
+        let error = js! {
+            try {
+                localStorage.setItem(@{key}, @{value});
+                return null;
+            } catch (error) {
+                return String(error);
+            }
+        }
+        .into_string();
+        match error {
+            Some(error) => Err(anyhow!(error)),
+            None => Ok(()),
+        }
+    }
+
+    fn restore(&self, key: &str, callback: Callback<Result<Option<String>, String>>) {
+        callback.emit(Ok(self.get(key)));
+    }
+
+    fn rename(&mut self, key: &str, new_key: &str) {
+        js! { @(no_return)
+            var value = localStorage.getItem(@{key});
+            if (value !== null) {
+                localStorage.setItem(@{new_key}, value);
+                localStorage.removeItem(@{key});
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        js! { @(no_return) localStorage.removeItem(@{key}); }
+    }
+}
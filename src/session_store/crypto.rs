@@ -0,0 +1,210 @@
+use crate::prelude::*;
+use stdweb::js;
+
+/// Random bytes mixed with the passphrase, created with the first one.
+const SALT_KEY: &str = concat!("totorigolo.", env!("CARGO_PKG_NAME"), ".salt");
+/// A known text encrypted with the key, to detect wrong passphrases before
+/// saving anything with them.
+const CHECK_KEY: &str = concat!("totorigolo.", env!("CARGO_PKG_NAME"), ".key_check");
+
+/// An encrypted session which couldn't be decrypted, eg. because the
+/// passphrase wasn't entered.
+#[derive(Debug)]
+pub struct DecryptionError(pub String);
+
+impl fmt::Display for DecryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed to decrypt the session: {}", self.0)
+    }
+}
+
+impl std::error::Error for DecryptionError {}
+
+/// The sessions couldn't be saved since they are encrypted, and the
+/// passphrase wasn't entered.
+#[derive(Debug)]
+pub struct LockedError;
+
+impl fmt::Display for LockedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(tr(
+            "The sessions are encrypted, and their passphrase wasn't entered."
+        ))
+    }
+}
+
+impl std::error::Error for LockedError {}
+
+/// Why the passphrase wasn't entered, see `Crypto::unlock`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum UnlockError {
+    /// It isn't the passphrase of the sessions encrypted before, which can
+    /// be forgotten with `Crypto::forget`.
+    WrongPassphrase,
+    /// Eg. the browser can't encrypt.
+    Failed(String),
+}
+
+impl fmt::Display for UnlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnlockError::WrongPassphrase => f.write_str(tr("Wrong passphrase.")),
+            UnlockError::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+/// Encrypts the saved sessions with AES-GCM, using a key derived from a
+/// passphrase of the user with PBKDF2. The key is only kept in memory.
+#[derive(Debug, Clone, Copy)]
+pub struct Crypto;
+
+impl Crypto {
+    pub fn new() -> Self {
+        js! { @(no_return)
+            if (window.sessionsCrypto) {
+                return;
+            }
+            var toBase64 = function(bytes) {
+                return btoa(String.fromCharCode.apply(null, new Uint8Array(bytes)));
+            };
+            var fromBase64 = function(text) {
+                return Uint8Array.from(atob(text), function(c) { return c.charCodeAt(0); });
+            };
+            window.sessionsCrypto = {
+                key: null,
+                encrypt: function(key, text) {
+                    var iv = crypto.getRandomValues(new Uint8Array(12));
+                    var data = new TextEncoder().encode(text);
+                    return crypto.subtle.encrypt({ name: "AES-GCM", iv: iv }, key, data)
+                        .then(function(encrypted) {
+                            return JSON.stringify({
+                                encrypted: { iv: toBase64(iv), data: toBase64(encrypted) }
+                            });
+                        });
+                },
+                decrypt: function(key, text) {
+                    var encrypted = JSON.parse(text).encrypted;
+                    var iv = fromBase64(encrypted.iv);
+                    return crypto.subtle.decrypt({ name: "AES-GCM", iv: iv }, key, fromBase64(encrypted.data))
+                        .then(function(data) { return new TextDecoder().decode(data); });
+                },
+                deriveKey: function(passphrase) {
+                    var salt = localStorage.getItem(@{SALT_KEY});
+                    if (salt === null) {
+                        salt = toBase64(crypto.getRandomValues(new Uint8Array(16)));
+                        localStorage.setItem(@{SALT_KEY}, salt);
+                    }
+                    var material = new TextEncoder().encode(passphrase);
+                    return crypto.subtle.importKey("raw", material, "PBKDF2", false, ["deriveKey"])
+                        .then(function(material) {
+                            var params = { name: "PBKDF2", salt: fromBase64(salt), iterations: 200000, hash: "SHA-256" };
+                            var algorithm = { name: "AES-GCM", length: 256 };
+                            return crypto.subtle.deriveKey(params, material, algorithm, false, ["encrypt", "decrypt"]);
+                        });
+                }
+            };
+        }
+        Crypto
+    }
+
+    /// Whether the passphrase was entered, see `unlock`.
+    pub fn is_unlocked(self) -> bool {
+        matches!(
+            js! { return !!sessionsCrypto.key; },
+            stdweb::Value::Bool(true)
+        )
+    }
+
+    /// Whether a saved text was encrypted, see `encrypt`.
+    pub fn is_encrypted(text: &str) -> bool {
+        text.starts_with(r#"{"encrypted":"#)
+    }
+
+    /// Derives the key from the passphrase. Fails if it isn't the one used to
+    /// encrypt the previous sessions.
+    pub fn unlock(self, passphrase: &str, callback: Callback<Result<(), UnlockError>>) {
+        let on_success = {
+            let callback = callback.clone();
+            move || callback.emit(Ok(()))
+        };
+        let on_error = move |error: String, wrong_passphrase: bool| {
+            callback.emit(Err(if wrong_passphrase {
+                UnlockError::WrongPassphrase
+            } else {
+                UnlockError::Failed(error)
+            }))
+        };
+        js! { @(no_return)
+            var on_success = @{stdweb::Once(on_success)};
+            var on_error = @{stdweb::Once(on_error)};
+            var wrongPassphrase = {};
+            if (!window.crypto || !crypto.subtle) {
+                on_success.drop();
+                on_error("This browser can't encrypt, or the page isn't served over HTTPS.", false);
+                return;
+            }
+            sessionsCrypto.deriveKey(@{passphrase})
+                .then(function(key) {
+                    var check = localStorage.getItem(@{CHECK_KEY});
+                    if (check === null) {
+                        return sessionsCrypto.encrypt(key, "check").then(function(check) {
+                            localStorage.setItem(@{CHECK_KEY}, check);
+                            return key;
+                        });
+                    }
+                    return sessionsCrypto.decrypt(key, check).then(
+                        function() { return key; },
+                        function() { throw wrongPassphrase; }
+                    );
+                })
+                .then(
+                    function(key) { sessionsCrypto.key = key; on_error.drop(); on_success(); },
+                    function(error) {
+                        on_success.drop();
+                        on_error(String(error), error === wrongPassphrase);
+                    }
+                );
+        }
+    }
+
+    /// Forgets the previous passphrase, so that the next one entered with
+    /// `unlock` replaces it. The sessions encrypted with it can't be
+    /// decrypted anymore.
+    pub fn forget(self) {
+        js! { @(no_return)
+            localStorage.removeItem(@{CHECK_KEY});
+            localStorage.removeItem(@{SALT_KEY});
+            sessionsCrypto.key = null;
+        }
+    }
+
+    pub fn encrypt(self, text: &str, callback: Callback<Result<String, String>>) {
+        self.run("encrypt", text, callback);
+    }
+
+    pub fn decrypt(self, text: &str, callback: Callback<Result<String, String>>) {
+        self.run("decrypt", text, callback);
+    }
+
+    fn run(self, operation: &str, text: &str, callback: Callback<Result<String, String>>) {
+        let on_success = {
+            let callback = callback.clone();
+            move |text: String| callback.emit(Ok(text))
+        };
+        let on_error = move |error: String| callback.emit(Err(error));
+        js! { @(no_return)
+            var on_success = @{stdweb::Once(on_success)};
+            var on_error = @{stdweb::Once(on_error)};
+            if (!sessionsCrypto.key) {
+                on_success.drop();
+                on_error("The sessions are encrypted, and their passphrase wasn't entered.");
+                return;
+            }
+            sessionsCrypto[@{operation}](sessionsCrypto.key, @{text}).then(
+                function(text) { on_error.drop(); on_success(text); },
+                function(error) { on_success.drop(); on_error(String(error)); }
+            );
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use super::Backend;
+use crate::prelude::*;
+use stdweb::js;
+
+const DATABASE: &str = concat!("totorigolo.", env!("CARGO_PKG_NAME"));
+
+/// IndexedDB, which is asynchronous but can hold much bigger sessions than
+/// the local storage.
+///
+/// The errors of the writes are sent to `on_error`, or only logged for the
+/// renames and removals, since they are asynchronous.
+#[derive(Debug, Clone)]
+pub struct IndexedDb {
+    on_error: Callback<String>,
+}
+
+impl IndexedDb {
+    pub fn new(on_error: Callback<String>) -> Self {
+        // Opens the database once, and runs a request on its store
+        js! { @(no_return)
+            if (window.sessionsDb) {
+                return;
+            }
+            var opening = null;
+            window.sessionsDb = function(mode, request) {
+                opening = opening || new Promise(function(resolve, reject) {
+                    var open = indexedDB.open(@{DATABASE}, 1);
+                    open.onupgradeneeded = function() { open.result.createObjectStore("sessions"); };
+                    open.onsuccess = function() { resolve(open.result); };
+                    open.onerror = function() { reject(open.error); };
+                });
+                return opening.then(function(db) {
+                    return new Promise(function(resolve, reject) {
+                        var store = db.transaction("sessions", mode).objectStore("sessions");
+                        var pending = request(store);
+                        pending.onsuccess = function() { resolve(pending.result); };
+                        pending.onerror = function() { reject(pending.error); };
+                    });
+                });
+            };
+        }
+        Self { on_error }
+    }
+}
+
+impl Backend for IndexedDb {
+    fn store(&mut self, key: &str, value: &str) -> Result<()> {
+        let on_error = self.on_error.clone();
+        let on_error = move |error: String| on_error.emit(error);
+        js! { @(no_return)
+            var on_error = @{stdweb::Once(on_error)};
+            sessionsDb("readwrite", function(store) { return store.put(@{value}, @{key}); }).then(
+                function() { on_error.drop(); },
+                function(error) { on_error(String(error)); }
+            );
+        }
+        Ok(())
+    }
+
+    fn restore(&self, key: &str, callback: Callback<Result<Option<String>, String>>) {
+        let on_success = {
+            let callback = callback.clone();
+            move |value: Option<String>| callback.emit(Ok(value))
+        };
+        let on_error = move |error: String| callback.emit(Err(error));
+        js! { @(no_return)
+            var on_success = @{stdweb::Once(on_success)};
+            var on_error = @{stdweb::Once(on_error)};
+            sessionsDb("readonly", function(store) { return store.get(@{key}); }).then(
+                function(value) { on_error.drop(); on_success(value === undefined ? null : value); },
+                function(error) { on_success.drop(); on_error(String(error)); }
+            );
+        }
+    }
+
+    fn rename(&mut self, key: &str, new_key: &str) {
+        js! { @(no_return)
+            var key = @{key};
+            var new_key = @{new_key};
+            sessionsDb("readwrite", function(store) {
+                var get = store.get(key);
+                get.addEventListener("success", function() {
+                    if (get.result !== undefined) {
+                        store.put(get.result, new_key);
+                        store.delete(key);
+                    }
+                });
+                return get;
+            }).catch(function(error) { console.error("Failed to rename the session:", error); });
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        js! { @(no_return)
+            sessionsDb("readwrite", function(store) { return store.delete(@{key}); })
+                .catch(function(error) { console.error("Failed to delete the session:", error); });
+        }
+    }
+}
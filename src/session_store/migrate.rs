@@ -0,0 +1,110 @@
+//! Upgrades the sessions saved by a previous version of the app, so that
+//! they are still restored when the format of the state changes.
+//!
+//! The saved state is wrapped in an envelope giving its version, see
+//! `Envelope`. To change the format of the state, add a migration at the end
+//! of `MIGRATIONS`: it is given the state as JSON, in the format of the
+//! previous version. The scenarios have their own migrations, see
+//! `scenario::migrate`.
+
+use crate::prelude::*;
+use serde_json::json;
+
+/// Migrations from each version to the next one, starting from version 1.
+const MIGRATIONS: &[fn(&mut JsonValue)] = &[open_in_tabs];
+
+/// The version of the state saved by this version of the app.
+pub const CURRENT_VERSION: u64 = MIGRATIONS.len() as u64 + 1;
+
+/// `{"version": 2, "state": {...}}`
+#[derive(Serialize)]
+pub struct Envelope<'a, T> {
+    pub version: u64,
+    pub state: &'a T,
+}
+
+impl<'a, T> Envelope<'a, T> {
+    pub fn new(state: &'a T) -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            state,
+        }
+    }
+}
+
+/// A session saved by a version of the app newer than this one.
+#[derive(Debug)]
+pub struct NewerSessionError(pub u64);
+
+impl fmt::Display for NewerSessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The session was saved by a newer version of the app ({} > {}), reload the page to \
+             update it.",
+            self.0, CURRENT_VERSION
+        )
+    }
+}
+
+impl std::error::Error for NewerSessionError {}
+
+/// Takes the state out of its envelope, and upgrades it to the current
+/// version. The states saved without an envelope are of the first version.
+pub fn migrate(mut saved: JsonValue) -> Result<JsonValue> {
+    let enveloped = saved.get("version").is_some() && saved.get("state").is_some();
+    let (version, mut state) = if enveloped {
+        let version = saved["version"]
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| anyhow!("Invalid session version: {}", saved["version"]))?;
+        (version, saved["state"].take())
+    } else {
+        (1, saved)
+    };
+    if version > CURRENT_VERSION {
+        return Err(NewerSessionError(version).into());
+    }
+
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(&mut state);
+    }
+    Ok(state)
+}
+
+/// Version 2: the state holds the sessions opened in tabs, instead of a
+/// single one.
+fn open_in_tabs(state: &mut JsonValue) {
+    if state.get("sessions").is_none() {
+        *state = json!({ "sessions": [state.take()], "active": 0 });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_unversioned_state() {
+        let session = json!({ "Loaded": { "scenario": { "template": "" }, "step": 0 } });
+        assert_eq!(
+            migrate(session.clone()).unwrap(),
+            json!({ "sessions": [session], "active": 0 })
+        );
+        assert_eq!(
+            migrate(json!("Init")).unwrap(),
+            json!({ "sessions": ["Init"], "active": 0 })
+        );
+    }
+
+    #[test]
+    fn unwrap_current_state() {
+        let state = json!({ "sessions": ["Init"], "active": 0 });
+        let saved = serde_json::to_value(Envelope::new(&state)).unwrap();
+        assert_eq!(saved["version"], json!(CURRENT_VERSION));
+        assert_eq!(migrate(saved).unwrap(), state);
+
+        let newer = json!({ "version": CURRENT_VERSION + 1, "state": state });
+        assert!(migrate(newer).unwrap_err().is::<NewerSessionError>());
+    }
+}
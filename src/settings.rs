@@ -0,0 +1,116 @@
+use crate::{i18n::Language, prelude::*, webhook::Webhook};
+use lazy_static::lazy_static;
+use yew::{format::Json as YewJson, services::storage::StorageService};
+
+lazy_static! {
+    static ref SETTINGS_STORAGE_KEY: String =
+        format!("totorigolo.{}.settings", env!("CARGO_PKG_NAME"));
+}
+
+/// User preferences, persisted in local storage independently of the
+/// loaded scenario.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Fails the rendering on missing variables, even if the scenario
+    /// doesn't ask for it.
+    pub strict_mode: bool,
+    /// Delay without edits before re-rendering the template and saving to
+    /// local storage.
+    pub debounce_delay_ms: u64,
+    /// Only renders the template when asked, instead of after the edits,
+    /// to keep huge templates usable.
+    pub manual_render: bool,
+    /// Which columns are shown, to make room on small screens.
+    pub layout: Layout,
+    pub theme: Theme,
+    /// Language of the user interface, see `i18n`.
+    pub language: Language,
+    /// Whether the inputs data is shown below the output.
+    pub show_data: bool,
+    /// Interval between two downloads of a scenario loaded from a URL, to
+    /// apply its changes. Never reloaded automatically when 0.
+    pub reload_interval_s: u64,
+    /// The open tabs aren't saved in the browser when they take more
+    /// kilobytes than this, eg. because of big data sets. No limit when 0.
+    pub max_autosave_kb: u64,
+    /// Where the "Send" button posts the output, instead of the webhook of
+    /// the scenario.
+    pub webhook: Webhook,
+    /// WebSocket server with which the inputs data of the active tab is
+    /// kept in sync, see `SyncAgent`. Not synced when empty.
+    pub sync_url: String,
+    /// Where the changes of the inputs data of the active tab are posted,
+    /// see `ChangesWebhook`. Not posted when empty.
+    pub changes_webhook_url: String,
+    /// Restores the tabs open when the app was last closed, instead of
+    /// starting with an empty one.
+    pub restore_on_startup: bool,
+    /// Encrypts the saved sessions with a passphrase, asked when the app
+    /// starts, see `SessionStore`.
+    pub encrypt_sessions: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            strict_mode: false,
+            debounce_delay_ms: 300,
+            manual_render: false,
+            layout: Layout::Split,
+            theme: Theme::Light,
+            language: Language::English,
+            show_data: true,
+            reload_interval_s: 0,
+            max_autosave_kb: 0,
+            webhook: Webhook::default(),
+            sync_url: String::new(),
+            changes_webhook_url: String::new(),
+            restore_on_startup: true,
+            encrypt_sessions: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// The inputs next to the template and its output.
+    Split,
+    InputsOnly,
+    OutputOnly,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    Light,
+    Dark,
+    /// Follows the preference of the system, see `prefers-color-scheme`.
+    Auto,
+}
+
+impl Theme {
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::Auto => "auto",
+        }
+    }
+}
+
+impl Settings {
+    /// Restores the settings from local storage, falling back to the
+    /// default ones if they are absent or invalid.
+    pub fn restore(storage: &StorageService) -> Self {
+        match storage.restore(SETTINGS_STORAGE_KEY.as_ref()) {
+            YewJson(Ok(settings)) => settings,
+            _ => Self::default(),
+        }
+    }
+
+    pub fn store(&self, storage: &mut StorageService) {
+        storage.store(SETTINGS_STORAGE_KEY.as_ref(), YewJson(self));
+    }
+}
@@ -0,0 +1,39 @@
+//! Encodes a whole session in the fragment of a URL, so that it can be
+//! shared as a link without any server.
+
+use crate::prelude::*;
+
+/// Fragment of the share links, followed by the encoded session.
+pub const FRAGMENT_PREFIX: &str = "#session=";
+
+/// Compresses a session and encodes it for a URL.
+pub fn encode_session(json: &str) -> String {
+    let compressed = miniz_oxide::deflate::compress_to_vec(json.as_bytes(), 9);
+    base64::encode_config(&compressed, base64::URL_SAFE_NO_PAD)
+}
+
+/// Decodes a session encoded by `encode_session`.
+pub fn decode_session(encoded: &str) -> Result<String> {
+    let compressed =
+        base64::decode_config(encoded, base64::URL_SAFE_NO_PAD).context("Invalid base64.")?;
+    let json = miniz_oxide::inflate::decompress_to_vec(&compressed)
+        .map_err(|e| anyhow!("Failed to decompress the session: {:?}", e))?;
+    String::from_utf8(json).context("The session isn't UTF-8.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode_session() {
+        let json =
+            r#"{"Loaded":{"scenario":{"template":"{{a}} {{a}} {{a}}"},"inputs_data":{"a":"é"}}}"#;
+        let encoded = encode_session(json);
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(decode_session(&encoded).unwrap(), json);
+        assert!(decode_session("not a session!").is_err());
+    }
+}
@@ -0,0 +1,792 @@
+//! The sessions opened in tabs, and the edits of their inputs data. They
+//! are kept by the `StateStore`, which the components send their edits to.
+
+use crate::{
+    inputs::*,
+    prelude::*,
+    scenario::{self, initial_inputs_data, Scenario},
+    ArrayMerge, InputsData, PatchOp, Path,
+};
+use std::collections::{HashMap, HashSet};
+
+/// The sessions opened in tabs, each with its own scenario and inputs data.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct State {
+    /// Never empty, an empty tab being a `Session::Init`.
+    pub sessions: Vec<Session>,
+    /// Index of the session shown, see `State::active`.
+    pub active: usize,
+    /// Values rejected by the inputs of the active session, which aren't in
+    /// its inputs data.
+    #[serde(skip)]
+    pub invalid_inputs: HashMap<Path, InvalidInput>,
+    /// Units chosen in the quantity inputs, instead of their canonical one.
+    #[serde(skip)]
+    pub quantity_units: HashMap<Path, String>,
+    /// Required inputs which are still empty, see `InputInfo::required`.
+    #[serde(skip)]
+    pub missing_required: Vec<Path>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            sessions: vec![Session::Init],
+            active: 0,
+            invalid_inputs: HashMap::new(),
+            quantity_units: HashMap::new(),
+            missing_required: vec![],
+        }
+    }
+}
+
+// There are only a few sessions, so their size doesn't matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Session {
+    Init,
+    Loaded {
+        scenario: Scenario,
+        #[serde(default)]
+        inputs_data: InputsData,
+        /// Index of the current step, see `Scenario::steps`.
+        #[serde(default)]
+        step: usize,
+        /// Where the scenario was downloaded from, to reload it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source_url: Option<String>,
+        /// Name of the example the scenario was opened from, to show this
+        /// tab when going to its route.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        example: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        snapshots: Vec<Snapshot>,
+        /// Groups collapsed or expanded by the user, see
+        /// `GroupInput::collapsed`.
+        #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+        toggled_groups: HashSet<Path>,
+    },
+}
+
+/// The inputs data saved under a name, to restore it or compare with it
+/// later.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Snapshot {
+    pub name: String,
+    pub inputs_data: InputsData,
+}
+
+/// A value typed in an input which didn't validate.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InvalidInput {
+    pub value: String,
+    pub error: String,
+}
+
+impl State {
+    pub fn active(&self) -> &Session {
+        &self.sessions[self.active]
+    }
+
+    fn active_mut(&mut self) -> &mut Session {
+        &mut self.sessions[self.active]
+    }
+
+    /// The names shown in the tabs.
+    pub fn titles(&self) -> Vec<String> {
+        self.sessions
+            .iter()
+            .map(|session| match session {
+                Session::Init => tr("New tab").to_owned(),
+                Session::Loaded { scenario, .. } => scenario
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| tr("Untitled scenario").to_owned()),
+            })
+            .collect()
+    }
+
+    /// Forgets what the inputs of the previous active session showed.
+    fn show_active_session(&mut self) {
+        self.invalid_inputs.clear();
+        self.quantity_units.clear();
+        self.check_required_inputs();
+    }
+
+    fn check_required_inputs(&mut self) {
+        self.missing_required = match self.active() {
+            Session::Loaded {
+                scenario,
+                inputs_data,
+                ..
+            } => missing_required_paths(&scenario.inputs, &Path::default(), inputs_data),
+            Session::Init => vec![],
+        };
+    }
+
+    /// Replaces all the sessions, eg. by saved ones.
+    pub fn replace(&mut self, state: State) {
+        *self = state;
+        self.show_active_session();
+    }
+
+    /// Puts a session in the active tab if it is empty, or else in a new tab.
+    pub fn open(&mut self, session: Session) {
+        if let Session::Init = self.active() {
+            *self.active_mut() = session;
+        } else {
+            self.sessions.push(session);
+            self.active = self.sessions.len() - 1;
+        }
+        self.show_active_session();
+    }
+
+    /// Opens an empty tab, to load another scenario.
+    pub fn new_session(&mut self) {
+        self.sessions.push(Session::Init);
+        self.active = self.sessions.len() - 1;
+        self.show_active_session();
+    }
+
+    /// Returns whether another session is now active.
+    pub fn select_session(&mut self, index: usize) -> bool {
+        if index == self.active || index >= self.sessions.len() {
+            return false;
+        }
+        self.active = index;
+        self.show_active_session();
+        true
+    }
+
+    /// Returns whether the closed session was the active one, or `None` if
+    /// there is no session at this index.
+    pub fn close_session(&mut self, index: usize) -> Option<bool> {
+        if index >= self.sessions.len() {
+            return None;
+        }
+        let was_active = index == self.active;
+        self.sessions.remove(index);
+        if self.sessions.is_empty() {
+            self.sessions.push(Session::Init);
+        }
+        if index < self.active || self.active >= self.sessions.len() {
+            self.active -= 1;
+        }
+        if was_active {
+            self.show_active_session();
+        }
+        Some(was_active)
+    }
+
+    /// Empties the active tab.
+    pub fn unload(&mut self) {
+        *self.active_mut() = Session::Init;
+        self.show_active_session();
+    }
+
+    /// Validates and saves the value of an input, which is kept as invalid
+    /// if it doesn't validate.
+    pub fn edit(&mut self, path: Path, value: JsonValue) -> Result<()> {
+        let (scenario, inputs_data) = match self.active_mut() {
+            Session::Loaded {
+                scenario,
+                inputs_data,
+                ..
+            } => (scenario, inputs_data),
+            Session::Init => {
+                warn!("No scenario to edit the value of '{}' in.", path);
+                return Ok(());
+            }
+        };
+        if let Err(error) = validate_at(&scenario.inputs, &path, &value) {
+            let error = error.to_string();
+            match value {
+                // Kept as a draft in the field, until it is fixed
+                JsonValue::String(value) => self.mark_invalid(path, InvalidInput { value, error }),
+                // The previous value is kept, eg. the tags before the rejected one
+                value => {
+                    let value = value.to_string();
+                    self.invalid_inputs
+                        .insert(path, InvalidInput { value, error });
+                }
+            }
+            return Ok(());
+        }
+
+        inputs_data.insert_at(&path, value)?;
+        clear_hidden_inputs(&scenario.inputs, inputs_data);
+        clear_unavailable_selections(&scenario.inputs, inputs_data);
+        update_computed_inputs(&scenario.inputs, inputs_data);
+        self.invalid_inputs.remove(&path);
+        self.check_required_inputs();
+        Ok(())
+    }
+
+    /// Keeps a value which didn't validate out of the inputs data.
+    pub fn mark_invalid(&mut self, path: Path, invalid: InvalidInput) {
+        if let Session::Loaded { inputs_data, .. } = self.active_mut() {
+            if let Err(e) = inputs_data.remove_at(&path) {
+                debug!("Nothing to remove at '{}': {:?}", path, e);
+            }
+        }
+        self.invalid_inputs.insert(path, invalid);
+        self.check_required_inputs();
+    }
+
+    pub fn resize_at(&mut self, path: &Path, new_size: usize) {
+        if let Session::Loaded {
+            scenario,
+            inputs_data,
+            ..
+        } = self.active_mut()
+        {
+            let allowed_sizes =
+                input_at(&scenario.inputs, path).map_or(0..=usize::MAX, InputTypes::allowed_sizes);
+            if let Err(e) = inputs_data.resize_array_at(path, new_size, allowed_sizes) {
+                warn!("Failed to access array at '{}': {:?}", path, e);
+            }
+        }
+        self.check_required_inputs();
+    }
+
+    /// Returns the removed value, to put it back if asked, see `restore_at`.
+    pub fn remove_at(&mut self, path: &Path) -> Option<JsonValue> {
+        let removed = match self.active_mut() {
+            Session::Loaded { inputs_data, .. } => {
+                inputs_data.remove_at(path).unwrap_or_else(|e| {
+                    warn!("Failed to remove at '{}': {:?}", path, e);
+                    None
+                })
+            }
+            Session::Init => None,
+        };
+        self.check_required_inputs();
+        removed
+    }
+
+    /// Puts back a value removed from the session at this index.
+    pub fn restore_at(&mut self, session: usize, path: &Path, value: JsonValue) -> Result<()> {
+        match self.sessions.get_mut(session) {
+            Some(Session::Loaded { inputs_data, .. }) => inputs_data.restore_at(path, value)?,
+            _ => bail!(tr("The tab of the removed element was closed.")),
+        }
+        self.check_required_inputs();
+        Ok(())
+    }
+
+    /// Moves a list element to another index of the same list.
+    pub fn move_at(&mut self, path: &Path, new_index: usize) {
+        let mut moved_to = None;
+        if let Session::Loaded {
+            scenario,
+            inputs_data,
+            ..
+        } = self.active_mut()
+        {
+            match inputs_data.move_at(path, new_index) {
+                Ok(index) => moved_to = Some(index),
+                Err(e) => warn!("Failed to move '{}' to {}: {:?}", path, new_index, e),
+            }
+            update_computed_inputs(&scenario.inputs, inputs_data);
+        }
+        if let Some(index) = moved_to {
+            self.reindex_paths(|p| p.after_move(path, index));
+        }
+        self.check_required_inputs();
+    }
+
+    /// Swaps a list element with the one at another index of the same list.
+    pub fn swap_at(&mut self, path: &Path, other_index: usize) {
+        let mut swapped = false;
+        if let Session::Loaded {
+            scenario,
+            inputs_data,
+            ..
+        } = self.active_mut()
+        {
+            match inputs_data.swap_at(path, other_index) {
+                Ok(()) => swapped = true,
+                Err(e) => warn!("Failed to swap '{}' with {}: {:?}", path, other_index, e),
+            }
+            update_computed_inputs(&scenario.inputs, inputs_data);
+        }
+        if swapped {
+            self.reindex_paths(|p| p.after_swap(path, other_index));
+        }
+        self.check_required_inputs();
+    }
+
+    /// Makes the paths kept along the inputs data follow their elements,
+    /// once a list was reordered.
+    fn reindex_paths(&mut self, reindexed: impl Fn(&Path) -> Path) {
+        self.invalid_inputs = std::mem::take(&mut self.invalid_inputs)
+            .into_iter()
+            .map(|(path, invalid)| (reindexed(&path), invalid))
+            .collect();
+        self.quantity_units = std::mem::take(&mut self.quantity_units)
+            .into_iter()
+            .map(|(path, unit)| (reindexed(&path), unit))
+            .collect();
+        if let Session::Loaded { toggled_groups, .. } = self.active_mut() {
+            *toggled_groups = toggled_groups.iter().map(&reindexed).collect();
+        }
+    }
+
+    /// Inserts a copy of a list element after it.
+    pub fn duplicate_at(&mut self, path: &Path) {
+        if let Session::Loaded {
+            scenario,
+            inputs_data,
+            ..
+        } = self.active_mut()
+        {
+            if let Err(e) = inputs_data.duplicate_at(path) {
+                warn!("Failed to duplicate '{}': {:?}", path, e);
+            }
+            update_computed_inputs(&scenario.inputs, inputs_data);
+        }
+        self.check_required_inputs();
+    }
+
+    /// Puts back the default value of an input.
+    pub fn reset_at(&mut self, path: &Path) {
+        if let Session::Loaded {
+            scenario,
+            inputs_data,
+            ..
+        } = self.active_mut()
+        {
+            if let Err(e) = reset_at(&scenario.inputs, path, inputs_data) {
+                warn!("Failed to reset '{}': {:?}", path, e);
+            }
+            clear_hidden_inputs(&scenario.inputs, inputs_data);
+            clear_unavailable_selections(&scenario.inputs, inputs_data);
+            update_computed_inputs(&scenario.inputs, inputs_data);
+        }
+        self.invalid_inputs
+            .retain(|invalid, _| !invalid.starts_with(path));
+        self.check_required_inputs();
+    }
+
+    /// Merges JSON in a group, or adds its elements to a list, eg. pasted
+    /// from the clipboard. Returns the paths of the values it replaced.
+    pub fn merge_at(&mut self, path: &Path, value: JsonValue) -> Result<Vec<Path>> {
+        let (scenario, inputs_data) = match self.active_mut() {
+            Session::Loaded {
+                scenario,
+                inputs_data,
+                ..
+            } => (scenario, inputs_data),
+            Session::Init => return Ok(vec![]),
+        };
+        let arrays = match (input_at(&scenario.inputs, path), &value) {
+            (Some(InputTypes::Group(_)), JsonValue::Object(_)) => ArrayMerge::Replace,
+            (Some(InputTypes::List(_)), JsonValue::Array(_))
+            | (Some(InputTypes::Table(_)), JsonValue::Array(_)) => ArrayMerge::Concat,
+            (Some(InputTypes::Group(_)), _) => bail!("Expected a JSON object."),
+            _ => bail!("Expected a JSON array."),
+        };
+        let conflicts = inputs_data.merge_at(path, value, arrays)?;
+
+        clear_hidden_inputs(&scenario.inputs, inputs_data);
+        clear_unavailable_selections(&scenario.inputs, inputs_data);
+        update_computed_inputs(&scenario.inputs, inputs_data);
+        self.invalid_inputs
+            .retain(|invalid, _| !invalid.starts_with(path));
+        self.check_required_inputs();
+        Ok(conflicts)
+    }
+
+    /// Applies the changes made to the inputs data somewhere else, eg. by
+    /// another user, see `SyncAgent`. Nothing is changed if they don't
+    /// apply.
+    pub fn apply_patch(&mut self, patch: &[PatchOp]) -> Result<()> {
+        let (scenario, inputs_data) = match self.active_mut() {
+            Session::Loaded {
+                scenario,
+                inputs_data,
+                ..
+            } => (scenario, inputs_data),
+            Session::Init => bail!("No scenario is loaded."),
+        };
+        inputs_data.apply_patch(patch)?;
+
+        clear_hidden_inputs(&scenario.inputs, inputs_data);
+        clear_unavailable_selections(&scenario.inputs, inputs_data);
+        update_computed_inputs(&scenario.inputs, inputs_data);
+        // The values typed here are replaced by the received ones
+        let changed = patch.iter().flat_map(|op| match op {
+            PatchOp::Test { .. } => vec![],
+            PatchOp::Move { from, path } => vec![from, path],
+            op => vec![op.path()],
+        });
+        for path in changed {
+            self.invalid_inputs
+                .retain(|invalid, _| !invalid.starts_with(path));
+        }
+        self.check_required_inputs();
+        Ok(())
+    }
+
+    pub fn toggle_group(&mut self, path: Path) {
+        if let Session::Loaded { toggled_groups, .. } = self.active_mut() {
+            if !toggled_groups.remove(&path) {
+                toggled_groups.insert(path);
+            }
+        }
+    }
+
+    /// Collapses all the groups, or expands them.
+    pub fn set_groups_collapsed(&mut self, collapsed: bool) {
+        if let Session::Loaded {
+            scenario,
+            inputs_data,
+            toggled_groups,
+            ..
+        } = self.active_mut()
+        {
+            *toggled_groups = group_paths(&scenario.inputs, &Path::default(), inputs_data)
+                .into_iter()
+                .filter(|(_, initially)| *initially != collapsed)
+                .map(|(path, _)| path)
+                .collect();
+        }
+    }
+
+    /// The unit of a quantity input was changed, which doesn't change the
+    /// data.
+    pub fn select_unit(&mut self, path: Path, unit: String) {
+        // The value typed in the previous unit can't be converted
+        self.invalid_inputs.remove(&path);
+        self.quantity_units.insert(path, unit);
+    }
+
+    /// Why the output can't be copied, downloaded or sent yet, if it can't:
+    /// the values of the inputs must all be there and valid.
+    pub fn export_blocker(&self) -> Option<&'static str> {
+        if !self.missing_required.is_empty() {
+            Some(tr("Fill the required inputs first"))
+        } else if !self.invalid_inputs.is_empty() {
+            Some(tr("Fix the invalid inputs first"))
+        } else {
+            None
+        }
+    }
+
+    pub fn previous_step(&mut self) {
+        if let Session::Loaded { step, .. } = self.active_mut() {
+            *step = step.saturating_sub(1);
+        }
+    }
+
+    /// Goes to the next step, unless the inputs of the current one are
+    /// missing or invalid. Returns whether it went.
+    pub fn next_step(&mut self) -> bool {
+        let blocked = match self.active() {
+            Session::Loaded { scenario, step, .. } => {
+                let step_inputs = scenario.step_inputs(*step);
+                self.missing_required
+                    .iter()
+                    .chain(self.invalid_inputs.keys())
+                    .any(|path| {
+                        step_inputs
+                            .iter()
+                            .any(|input| path.starts_with(input.key()))
+                    })
+            }
+            Session::Init => return false,
+        };
+        if blocked {
+            return false;
+        }
+
+        if let Session::Loaded { scenario, step, .. } = self.active_mut() {
+            *step = (*step + 1).min(scenario.steps.len().saturating_sub(1));
+        }
+        true
+    }
+
+    /// Saves the inputs data under a name.
+    pub fn take_snapshot(&mut self, name: String) {
+        if let Session::Loaded {
+            inputs_data,
+            snapshots,
+            ..
+        } = self.active_mut()
+        {
+            snapshots.push(Snapshot {
+                name,
+                inputs_data: inputs_data.clone(),
+            });
+        }
+    }
+
+    /// Replaces the inputs data by a snapshot. Returns whether it exists.
+    pub fn restore_snapshot(&mut self, index: usize) -> bool {
+        match self.active_mut() {
+            Session::Loaded {
+                scenario,
+                inputs_data,
+                snapshots,
+                ..
+            } => match snapshots.get(index) {
+                Some(snapshot) => {
+                    *inputs_data = snapshot.inputs_data.clone();
+                    // The scenario may have been reloaded since
+                    clear_hidden_inputs(&scenario.inputs, inputs_data);
+                    clear_unavailable_selections(&scenario.inputs, inputs_data);
+                    update_computed_inputs(&scenario.inputs, inputs_data);
+                }
+                None => return false,
+            },
+            Session::Init => return false,
+        }
+        self.invalid_inputs.clear();
+        self.check_required_inputs();
+        true
+    }
+
+    pub fn delete_snapshot(&mut self, index: usize) {
+        if let Session::Loaded { snapshots, .. } = self.active_mut() {
+            if index < snapshots.len() {
+                snapshots.remove(index);
+            }
+        }
+    }
+
+    /// Puts back the default values of all the inputs, and goes back to the
+    /// first step.
+    pub fn reset_all(&mut self) -> Result<()> {
+        if let Session::Loaded {
+            scenario,
+            inputs_data,
+            step,
+            ..
+        } = self.active_mut()
+        {
+            *inputs_data = initial_inputs_data(scenario)?;
+            *step = 0;
+        }
+        self.invalid_inputs.clear();
+        self.quantity_units.clear();
+        self.check_required_inputs();
+        Ok(())
+    }
+
+    /// Replaces a template of the scenario of the active tab, eg. edited in
+    /// the template editor.
+    pub fn set_template(&mut self, name: &str, template: String) {
+        if let Session::Loaded { scenario, .. } = self.active_mut() {
+            scenario.template.set(name, template);
+        }
+    }
+
+    /// Replaces the scenario of the active tab by its new version, keeping
+    /// the inputs data which still fits it. Returns false if the scenario
+    /// didn't change, or if another tab was selected in the meantime.
+    pub fn reload(&mut self, scenario: Scenario, url: &str) -> Result<bool> {
+        let (current, inputs_data, step) = match self.active_mut() {
+            Session::Loaded {
+                scenario: current,
+                inputs_data,
+                step,
+                source_url,
+                ..
+            } if source_url.as_deref() == Some(url) => (current, inputs_data, step),
+            _ => return Ok(false),
+        };
+        if serde_json::to_value(&*current).ok() == serde_json::to_value(&scenario).ok() {
+            return Ok(false);
+        }
+
+        let root = Path::default();
+        let mut kept = inputs_data.clone();
+        for path in incompatible_paths(&current.inputs, &scenario.inputs, &root, inputs_data) {
+            if let Err(e) = kept.remove_at(&path) {
+                warn!("Failed to drop the value at '{}': {:?}", path, e);
+            }
+        }
+        let mut reloaded_data = initial_inputs_data(&scenario)?;
+        if let Some(kept) = kept.get_at(&root) {
+            if let Err(e) = reloaded_data.merge_at(&root, kept.clone(), ArrayMerge::Replace) {
+                warn!("Failed to keep the inputs data: {:?}", e);
+            }
+        }
+        clear_hidden_inputs(&scenario.inputs, &mut reloaded_data);
+        clear_unavailable_selections(&scenario.inputs, &mut reloaded_data);
+        update_computed_inputs(&scenario.inputs, &mut reloaded_data);
+
+        *step = (*step).min(scenario.steps.len().saturating_sub(1));
+        *current = scenario;
+        *inputs_data = reloaded_data;
+        self.show_active_session();
+        Ok(true)
+    }
+}
+
+/// Deserializes a state restored by the `SessionStore`, upgrading its
+/// scenarios.
+pub fn migrate_state(mut state: JsonValue) -> Result<State> {
+    let sessions = match state.get_mut("sessions").map(JsonValue::take) {
+        Some(JsonValue::Array(sessions)) => sessions
+            .into_iter()
+            .map(migrate_session)
+            .collect::<Result<Vec<_>>>()?,
+        _ => bail!("Expected an array of sessions."),
+    };
+    if sessions.is_empty() {
+        return Ok(State::default());
+    }
+
+    let active = state
+        .get("active")
+        .and_then(JsonValue::as_u64)
+        .map_or(0, |active| active as usize)
+        .min(sessions.len() - 1);
+    Ok(State {
+        sessions,
+        active,
+        ..State::default()
+    })
+}
+
+/// Deserializes a session saved by a previous version, upgrading its
+/// scenario.
+pub fn migrate_session(mut session: JsonValue) -> Result<Session> {
+    if let Some(scenario) = session.pointer_mut("/Loaded/scenario") {
+        *scenario = scenario::migrate(scenario.take())?;
+    }
+    Ok(serde_json::from_value(session)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn session(title: &str) -> Session {
+        let scenario = serde_json::from_value(json!({
+            "title": title,
+            "template": "",
+            "inputs": [
+                { "key": "name", "name": "Name", "type": "text", "required": true },
+                { "key": "code", "name": "Code", "type": "text",
+                    "validation": { "pattern": "\\d+" } },
+                {
+                    "key": "hosts", "name": "Hosts", "type": "list",
+                    "inputs": [{ "key": "ip", "name": "IP", "type": "text" }]
+                }
+            ]
+        }))
+        .unwrap();
+        Session::Loaded {
+            scenario,
+            inputs_data: InputsData::default(),
+            step: 0,
+            source_url: None,
+            example: None,
+            snapshots: vec![],
+            toggled_groups: HashSet::new(),
+        }
+    }
+
+    fn data(state: &State) -> &InputsData {
+        match state.active() {
+            Session::Loaded { inputs_data, .. } => inputs_data,
+            Session::Init => panic!("No session is loaded."),
+        }
+    }
+
+    #[test]
+    fn open_and_close_sessions() {
+        let mut state = State::default();
+        state.open(session("web"));
+        assert_eq!(state.titles(), ["web"]);
+        state.open(session("db"));
+        assert_eq!(state.titles(), ["web", "db"]);
+        assert_eq!(state.active, 1);
+
+        assert!(state.select_session(0));
+        assert!(!state.select_session(0));
+        assert_eq!(state.close_session(1), Some(false));
+        assert_eq!(state.close_session(1), None);
+        assert_eq!(state.close_session(0), Some(true));
+        assert!(matches!(state.active(), Session::Init));
+    }
+
+    #[test]
+    fn edit_inputs() {
+        let mut state = State::default();
+        state.open(session("web"));
+        assert_eq!(state.missing_required, [Path::from("name")]);
+
+        state.edit(Path::from("name"), json!("nginx")).unwrap();
+        assert!(state.missing_required.is_empty());
+        assert_eq!(state.export_blocker(), None);
+        state.edit(Path::from("code"), json!("abc")).unwrap();
+        assert_eq!(state.invalid_inputs[&Path::from("code")].value, "abc");
+        assert_eq!(state.export_blocker(), Some("Fix the invalid inputs first"));
+        state.edit(Path::from("code"), json!("12")).unwrap();
+        assert!(state.invalid_inputs.is_empty());
+        assert_eq!(
+            data(&state),
+            &InputsData::from(json!({"name": "nginx", "code": "12"}))
+        );
+
+        state.resize_at(&Path::from("hosts"), 2);
+        state
+            .edit(Path::from("hosts.1.ip"), json!("10.0.0.1"))
+            .unwrap();
+        let removed = state.remove_at(&Path::from("hosts.1")).unwrap();
+        assert_eq!(data(&state).get_at(&Path::from("hosts.1")), None);
+        state
+            .restore_at(0, &Path::from("hosts.1"), removed)
+            .unwrap();
+        assert_eq!(
+            data(&state).get_at(&Path::from("hosts.1.ip")),
+            Some(&json!("10.0.0.1"))
+        );
+    }
+
+    #[test]
+    fn apply_patches() {
+        let mut state = State::default();
+        state.open(session("web"));
+        state.edit(Path::from("code"), json!("abc")).unwrap();
+
+        let patch: Vec<PatchOp> = serde_json::from_value(json!([
+            {"op": "add", "path": "/name", "value": "nginx"},
+            {"op": "add", "path": "/code", "value": "80"},
+        ]))
+        .unwrap();
+        state.apply_patch(&patch).unwrap();
+        assert!(state.missing_required.is_empty());
+        assert!(state.invalid_inputs.is_empty());
+        assert_eq!(
+            data(&state),
+            &InputsData::from(json!({"name": "nginx", "code": "80"}))
+        );
+
+        let patch: Vec<PatchOp> =
+            serde_json::from_value(json!([{"op": "remove", "path": "/hosts/0"}])).unwrap();
+        assert!(state.apply_patch(&patch).is_err());
+    }
+
+    #[test]
+    fn reordered_lists_keep_their_invalid_values() {
+        let mut state = State::default();
+        state.open(session("web"));
+        state.resize_at(&Path::from("hosts"), 3);
+        let invalid = InvalidInput {
+            value: "10.0".to_owned(),
+            error: "Incomplete".to_owned(),
+        };
+        state.mark_invalid(Path::from("hosts.0.ip"), invalid.clone());
+
+        state.move_at(&Path::from("hosts.0"), 2);
+        assert_eq!(state.invalid_inputs[&Path::from("hosts.2.ip")], invalid);
+        state.swap_at(&Path::from("hosts.2"), 1);
+        assert_eq!(state.invalid_inputs[&Path::from("hosts.1.ip")], invalid);
+        assert_eq!(state.invalid_inputs.len(), 1);
+    }
+}
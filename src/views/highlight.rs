@@ -0,0 +1,159 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use yew::{
+    virtual_dom::{VList, VNode, VTag, VText},
+    Html,
+};
+
+lazy_static! {
+    /// The tokens of each language, as named groups whose names are the kinds
+    /// of the tokens, see `tokens`.
+    static ref LANGUAGES: Vec<(&'static [&'static str], Regex)> = vec![
+        (&["json"], Regex::new(&[
+            r#"(?P<key>"(?:[^"\\\n]|\\.)*")\s*:"#,
+            STRING,
+            NUMBER,
+            r"\b(?P<literal>true|false|null)\b",
+        ].join("|")).unwrap()),
+        (&["yaml", "yml"], Regex::new(&[
+            r"(?:^|\s)(?P<comment>#.*)",
+            r"(?m)^[ \t]*(?:- )*(?P<key>[\w.\-/]+)[ \t]*:(?:\s|$)",
+            STRING,
+            NUMBER,
+            r"\b(?P<literal>true|false|null|yes|no)\b",
+        ].join("|")).unwrap()),
+        (&["nginx"], Regex::new(&[
+            r"(?P<comment>#.*)",
+            r"(?m)^[ \t]*(?P<keyword>[a-z_]+)\b",
+            STRING,
+            r"(?P<variable>\$\w+)",
+            r"\b(?P<number>\d+[kKmMsdhw]?)\b",
+        ].join("|")).unwrap()),
+        (&["html", "xml", "svg"], Regex::new(&[
+            r"(?P<comment><!--(?s:.)*?-->)",
+            r"(?P<tag></?[\w:\-]+|/?>)",
+            r"(?P<key>[\w:\-]+)=",
+            STRING,
+        ].join("|")).unwrap()),
+        (&["toml", "ini", "conf"], Regex::new(&[
+            r"(?m)^[ \t]*(?P<comment>[#;].*)",
+            r"(?m)^[ \t]*(?P<keyword>\[.*\])",
+            r"(?m)^[ \t]*(?P<key>[\w.\-]+)[ \t]*=",
+            STRING,
+            NUMBER,
+            r"\b(?P<literal>true|false)\b",
+        ].join("|")).unwrap()),
+        (&["sh", "bash", "shell"], Regex::new(&[
+            r"(?:^|\s)(?P<comment>#.*)",
+            STRING,
+            r"(?P<variable>\$\{[^}]*\}|\$\w+)",
+            r"\b(?P<keyword>if|then|else|elif|fi|for|in|do|done|case|esac|while|function|export|local|return)\b",
+        ].join("|")).unwrap()),
+    ];
+}
+
+const STRING: &str = r#"(?P<string>"(?:[^"\\\n]|\\.)*"|'[^'\n]*')"#;
+const NUMBER: &str = r"(?P<number>-?\b\d+(?:\.\d+)?(?:[eE][+-]?\d+)?\b)";
+const KINDS: &[&str] = &[
+    "comment", "key", "string", "number", "literal", "keyword", "variable", "tag",
+];
+
+/// Whether `language` can be highlighted, eg. `yaml` or `nginx`.
+pub fn is_known_language(language: &str) -> bool {
+    find_language(language).is_some()
+}
+
+fn find_language(language: &str) -> Option<&'static Regex> {
+    let language = language.to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(names, _)| names.contains(&language.as_str()))
+        .map(|(_, regex)| regex)
+}
+
+/// Splits a text into its tokens, with their kinds if they are highlighted.
+fn tokens<'a>(language: &Regex, text: &'a str) -> Vec<(Option<&'static str>, &'a str)> {
+    let mut tokens = vec![];
+    let mut end = 0;
+    for captures in language.captures_iter(text) {
+        let token = KINDS
+            .iter()
+            .find_map(|kind| captures.name(kind).map(|token| (*kind, token)));
+        if let Some((kind, token)) = token {
+            if token.start() > end {
+                tokens.push((None, &text[end..token.start()]));
+            }
+            tokens.push((Some(kind), token.as_str()));
+            end = token.end();
+        }
+    }
+    if end < text.len() {
+        tokens.push((None, &text[end..]));
+    }
+    tokens
+}
+
+/// Renders a text with its tokens in `<span class="hl-...">`, or as is if the
+/// language is unknown.
+pub fn highlight(text: &str, language: Option<&str>) -> Html {
+    let language = match language.and_then(find_language) {
+        Some(language) => language,
+        None => return VText::new(text.to_owned()).into(),
+    };
+
+    let mut list = VList::new();
+    for (kind, token) in tokens(language, text) {
+        let text: VNode = VText::new(token.to_owned()).into();
+        match kind {
+            Some(kind) => {
+                let mut span = VTag::new("span");
+                span.add_class(&format!("hl-{}", kind));
+                span.add_child(text);
+                list.add_child(span.into());
+            }
+            None => list.add_child(text),
+        }
+    }
+    list.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize() {
+        let yaml = find_language("YAML").unwrap();
+        assert_eq!(
+            tokens(yaml, "name: \"web\" # The name\nports:\n  - 80\n"),
+            [
+                (Some("key"), "name"),
+                (None, ": "),
+                (Some("string"), "\"web\""),
+                (None, " "),
+                (Some("comment"), "# The name"),
+                (None, "\n"),
+                (Some("key"), "ports"),
+                (None, ":\n  - "),
+                (Some("number"), "80"),
+                (None, "\n"),
+            ]
+        );
+
+        let nginx = find_language("nginx").unwrap();
+        assert_eq!(
+            tokens(nginx, "listen 80;\nroot $root;"),
+            [
+                (Some("keyword"), "listen"),
+                (None, " "),
+                (Some("number"), "80"),
+                (None, ";\n"),
+                (Some("keyword"), "root"),
+                (None, " "),
+                (Some("variable"), "$root"),
+                (None, ";"),
+            ]
+        );
+        assert!(!is_known_language("cobol"));
+    }
+}
@@ -1,27 +1,126 @@
-use crate::{app, for_all_inputtypes_variants, inputs::*, prelude::*, InputsData, Path};
+use super::markdown::{is_safe_url, render_markdown};
+use crate::{
+    agents::{StateStore, StoreRequest},
+    components::RemoteSelect,
+    inputs::*,
+    prelude::*,
+    state::InvalidInput,
+    InputsData, Path,
+};
+use live_handlebars_core::for_all_inputtypes_variants;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+use stdweb::{
+    js,
+    unstable::TryInto,
+    web::{
+        event::{IDragEvent, IEvent},
+        html_element::InputElement,
+    },
+};
 
-type AppComponentLink = yew::ComponentLink<app::App>;
+/// Type of the data dragged to reorder the elements of a list.
+const LIST_ITEM_MIME_TYPE: &str = "application/x-live-handlebars-list-item";
+
+thread_local! {
+    static CUSTOM_VIEWS: RefCell<HashMap<String, Rc<dyn CustomInputView>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// What the inputs need to be rendered.
+#[derive(Clone, Copy)]
+pub struct InputsContext<'a> {
+    pub data: &'a InputsData,
+    /// Values which were rejected, kept out of `data` until they are fixed.
+    pub invalid: &'a HashMap<Path, InvalidInput>,
+    /// Groups which were collapsed or expanded, from their initial state.
+    pub toggled_groups: &'a HashSet<Path>,
+    /// Units chosen in the quantity inputs, see `QuantityInput`.
+    pub quantity_units: &'a HashMap<Path, String>,
+    /// Required inputs which are still empty.
+    pub missing_required: &'a [Path],
+    /// Only the inputs matching it are shown, in lowercase, see
+    /// `InputTypes::matches_filter`.
+    pub filter: &'a str,
+    /// The peers editing each input, see `SyncAgent`.
+    pub presence: &'a HashMap<Path, Vec<String>>,
+}
+
+impl<'a> InputsContext<'a> {
+    /// Returns the error to show under the input at `key`, if any.
+    fn error(&self, key: &Path) -> Option<&str> {
+        match self.invalid.get(key) {
+            Some(invalid) => Some(&invalid.error),
+            None if self.missing_required.contains(key) => Some(tr("This value is required.")),
+            None => None,
+        }
+    }
+
+    /// Returns the context of the inputs nested in `input`, which are all
+    /// shown when its own name matches the filter.
+    fn nested(&self, input: &impl Input) -> InputsContext<'a> {
+        if name_or_key_matches(input, self.filter) {
+            InputsContext {
+                filter: "",
+                ..*self
+            }
+        } else {
+            *self
+        }
+    }
+}
 
 pub trait RenderableInput {
-    fn render(&self, key_base: &Path, inputs_data: &InputsData, link: &AppComponentLink) -> Html;
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html;
 }
 
 impl RenderableInput for InputTypes {
-    fn render(&self, key_base: &Path, inputs_data: &InputsData, link: &AppComponentLink) -> Html {
-        for_all_inputtypes_variants! { self, i => i.render(key_base, inputs_data, link) }
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        if !self.is_visible(ctx.data, key_base) || !self.matches_filter(ctx.filter) {
+            return html! {};
+        }
+        for_all_inputtypes_variants! { self, i => i.render(key_base, ctx) }
     }
 }
 
 impl RenderableInput for TextInput {
-    fn render(&self, key_base: &Path, inputs_data: &InputsData, link: &AppComponentLink) -> Html {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
         let key = key_base + self.key();
 
         let key_inner = key.clone();
-        let on_input = link.callback(move |input_data: InputData| {
-            app::Msg::EditedInput(key_inner.clone(), JsonValue::String(input_data.value))
+        let input = self.clone();
+        let on_input = StateStore::callback(move |input_data: InputData| {
+            let value = input.normalize(&input_data.value);
+            match input.validate(&value) {
+                Ok(()) => StoreRequest::EditedInput(key_inner.clone(), JsonValue::String(value)),
+                Err(error) => {
+                    StoreRequest::InvalidInput(key_inner.clone(), InvalidInput { value, error })
+                }
+            }
+        });
+        // The rejected characters don't change the value, so the view doesn't
+        // erase them: this does it instead.
+        let input = self.clone();
+        let on_keyup = StateStore::batch_callback(move |event: KeyUpEvent| {
+            if let Some(element) = event
+                .target()
+                .and_then(|t| TryInto::<InputElement>::try_into(t).ok())
+            {
+                let value = input.normalize(&element.raw_value());
+                if value != element.raw_value() {
+                    element.set_raw_value(&value);
+                }
+            }
+            vec![]
         });
 
-        let value = if let Some(value) = inputs_data.get_at(&key) {
+        let invalid = ctx.invalid.get(&key);
+        let value = if let Some(invalid) = invalid {
+            invalid.value.clone()
+        } else if let Some(value) = ctx.data.get_at(&key) {
             match value {
                 JsonValue::Null => "".to_owned(),
                 JsonValue::Bool(true) => "true".to_owned(),
@@ -36,59 +135,175 @@ impl RenderableInput for TextInput {
 
         html! {
             <div class="field">
-                <label class="label">{ self.name() }</label>
+                { render_label(self, &key, ctx) }
                 <div class="control">
                     <input
-                        class="input"
+                        class=if ctx.error(&key).is_some() { "input is-danger" } else { "input" }
                         type="text"
-                        placeholder={ self.name() }
+                        id=input_id(&key)
+                        aria-describedby=described_by(self, &key, ctx)
+                        aria-invalid=ctx.error(&key).is_some().to_string()
+                        aria-required=self.required().to_string()
+                        placeholder=self.placeholder().unwrap_or_else(|| self.name())
+                        value=value
+                        oninput=&on_input
+                        onkeyup=on_keyup
+                        onblur=StateStore::callback(|_| StoreRequest::Flush)
+                        />
+                </div>
+                { render_error(&key, ctx) }
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for SecretInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+
+        let key_inner = key.clone();
+        let on_input = StateStore::callback(move |input_data: InputData| {
+            StoreRequest::EditedInput(key_inner.clone(), JsonValue::String(input_data.value))
+        });
+
+        let value = ctx
+            .data
+            .get_str_at(&key)
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+            .to_owned();
+
+        html! {
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <div class="control has-icons-left">
+                    <input
+                        class="input"
+                        type="password"
+                        id=input_id(&key)
+                        aria-describedby=described_by(self, &key, ctx)
+                        aria-required=self.required().to_string()
+                        autocomplete="off"
+                        placeholder=self.placeholder().unwrap_or_else(|| self.name())
                         value=value
                         oninput=&on_input
+                        onblur=StateStore::callback(|_| StoreRequest::Flush)
                         />
+                    <span class="icon is-small is-left">
+                        <i class="fas fa-lock"></i>
+                    </span>
+                </div>
+                { render_error(&key, ctx) }
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for ComputedInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+        let value = match ctx.data.get_at(&key) {
+            Some(JsonValue::String(s)) => s.clone(),
+            Some(JsonValue::Null) | None => "".to_owned(),
+            Some(value) => value.to_string(),
+        };
+
+        html! {
+            <div class="field">
+                <label class="label" for=input_id(&key)>{ render_name(self, &key) }</label>
+                <div class="control has-icons-left">
+                    <input
+                        class="input is-static"
+                        type="text"
+                        id=input_id(&key)
+                        aria-describedby=described_by(self, &key, ctx)
+                        readonly=true
+                        value=value
+                        />
+                    <span class="icon is-small is-left">
+                        <i class="fas fa-calculator"></i>
+                    </span>
                 </div>
-                { render_description(self.description()) }
             </div>
         }
     }
 }
 
 impl RenderableInput for GroupInput {
-    fn render(&self, key_base: &Path, inputs_data: &InputsData, link: &AppComponentLink) -> Html {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
         let key = key_base + self.key();
+        // The groups are expanded to show the inputs matching the filter
+        let collapsed =
+            ctx.filter.is_empty() && self.collapsed != ctx.toggled_groups.contains(&key);
+        let nested = ctx.nested(self);
+
+        let key_inner = key.clone();
+        let on_toggle =
+            StateStore::callback(move |_: ClickEvent| StoreRequest::ToggleGroup(key_inner.clone()));
+
+        let children = if collapsed {
+            html! {}
+        } else {
+            html! {
+                <>
+                    <div
+                        class="input-group-children"
+                        id=input_id(&key)
+                        role="group"
+                        aria-labelledby=label_id(&key)>
+                        { for self.inputs.iter().map(|input| input.render(&key, &nested)) }
+                    </div>
+                </>
+            }
+        };
+
         html! {
             <div class="field input-group">
-                <p class="label">{ self.name() }</p>
-                { render_description(self.description()) }
-                <div class="input-group-children">
-                    { for self
-                        .inputs
-                        .iter()
-                        .map(|input| input.render(&key, &inputs_data, &link))
-                    }
-                </div>
+                <button
+                    type="button"
+                    class="label input-group-toggle"
+                    id=label_id(&key)
+                    aria-expanded=(!collapsed).to_string()
+                    aria-controls=input_id(&key)
+                    onclick=on_toggle>
+                    <span class="icon" aria-hidden="true">
+                        <i class=if collapsed { "fas fa-angle-right" } else { "fas fa-angle-down" }></i>
+                    </span>
+                    { self.name() }
+                </button>
+                { render_help(self, &key) }
+                { render_reset(self, &key, ctx) }
+                { render_paste(&key) }
+                { children }
             </div>
         }
     }
 }
 
 impl RenderableInput for NumberInput {
-    fn render(&self, key_base: &Path, inputs_data: &InputsData, link: &AppComponentLink) -> Html {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
         let key = key_base + self.key();
         let key_callback = key.clone();
-        let on_input = link.callback(move |input_data: InputData| {
-            let number = match &input_data.value {
-                s if s.is_empty() => JsonValue::Null,
-                s => match s.parse::<JsonNumber>() {
-                    Ok(n) => JsonValue::Number(n),
-                    Err(_) => JsonValue::String(input_data.value),
-                },
-            };
-            app::Msg::EditedInput(key_callback.clone(), number)
+        let input = self.clone();
+        let on_input = StateStore::callback(move |input_data: InputData| {
+            match input.parse(&input_data.value) {
+                Ok(number) => StoreRequest::EditedInput(key_callback.clone(), number),
+                Err(error) => StoreRequest::InvalidInput(
+                    key_callback.clone(),
+                    InvalidInput {
+                        value: input_data.value,
+                        error,
+                    },
+                ),
+            }
         });
 
-        let value = match inputs_data.get_at(&key) {
-            Some(JsonValue::Number(n)) => format!("{}", n),
-            Some(JsonValue::String(s)) => s.clone(),
+        let invalid = ctx.invalid.get(&key);
+        let value = match (invalid, ctx.data.get_at(&key)) {
+            (Some(invalid), _) => invalid.value.clone(),
+            (None, Some(JsonValue::Number(n))) => format!("{}", n),
+            (None, Some(JsonValue::String(s))) => s.clone(),
             _ => "".to_string(),
         };
 
@@ -102,64 +317,470 @@ impl RenderableInput for NumberInput {
             .as_ref()
             .map(ToString::to_string)
             .unwrap_or_default();
-        let step = self
-            .step
-            .as_ref()
-            .map(ToString::to_string)
-            .unwrap_or_default();
+        let step = match (&self.step, self.mode) {
+            (Some(step), _) => step.to_string(),
+            (None, NumberMode::Integer) => "1".to_owned(),
+            (None, NumberMode::Float) => "any".to_owned(),
+        };
 
         html! {
             <div class="field">
-                <label class="label">{ self.name() }</label>
+                { render_label(self, &key, ctx) }
                 <div class="control">
                     <input
-                        class="input"
+                        class=if ctx.error(&key).is_some() { "input is-danger" } else { "input" }
                         type="number"
-                        placeholder={ self.name() }
+                        id=input_id(&key)
+                        aria-describedby=described_by(self, &key, ctx)
+                        aria-invalid=ctx.error(&key).is_some().to_string()
+                        aria-required=self.required().to_string()
+                        placeholder=self.placeholder().unwrap_or_else(|| self.name())
                         value={ value }
                         oninput=&on_input
+                        onblur=StateStore::callback(|_| StoreRequest::Flush)
                         min=min
                         max=max
                         step=step
                         />
                 </div>
-                { render_description(self.description()) }
+                { render_error(&key, ctx) }
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for QuantityInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+        let unit = ctx
+            .quantity_units
+            .get(&key)
+            .map_or(self.canonical_unit.as_str(), String::as_str);
+
+        let key_inner = key.clone();
+        let input = self.clone();
+        let unit_inner = unit.to_owned();
+        let on_input = StateStore::callback(move |input_data: InputData| {
+            match input.parse(&input_data.value, &unit_inner) {
+                Ok(value) => StoreRequest::EditedInput(key_inner.clone(), value),
+                Err(error) => StoreRequest::InvalidInput(
+                    key_inner.clone(),
+                    InvalidInput {
+                        value: input_data.value,
+                        error,
+                    },
+                ),
+            }
+        });
+
+        let key_inner = key.clone();
+        let on_unit_change = StateStore::batch_callback(move |change: ChangeData| match change {
+            ChangeData::Select(select) => select
+                .value()
+                .map(|unit| StoreRequest::SelectUnit(key_inner.clone(), unit))
+                .into_iter()
+                .collect(),
+            _ => vec![],
+        });
+
+        let value = match (ctx.invalid.get(&key), ctx.data.get_at(&key)) {
+            (Some(invalid), _) => invalid.value.clone(),
+            (None, Some(value)) => self.display(value, unit).unwrap_or_default(),
+            (None, None) => String::new(),
+        };
+
+        html! {
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <div class="field has-addons">
+                    <div class="control is-expanded">
+                        <input
+                            class=if ctx.error(&key).is_some() { "input is-danger" } else { "input" }
+                            type="number"
+                            step="any"
+                            id=input_id(&key)
+                            aria-describedby=described_by(self, &key, ctx)
+                            aria-invalid=ctx.error(&key).is_some().to_string()
+                            aria-required=self.required().to_string()
+                            placeholder=self.placeholder().unwrap_or_else(|| self.name())
+                            value=value
+                            oninput=on_input
+                            onblur=StateStore::callback(|_| StoreRequest::Flush)
+                            />
+                    </div>
+                    <div class="control">
+                        <div class="select">
+                            <select onchange=on_unit_change aria-label=tr("Unit")>
+                                { for self.unit_names().into_iter().map(|name| html! {
+                                    <option value=name selected=(name == unit)>{ name }</option>
+                                }) }
+                            </select>
+                        </div>
+                    </div>
+                </div>
+                { render_error(&key, ctx) }
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for SliderInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+        let key_callback = key.clone();
+        let on_input = StateStore::callback(move |input_data: InputData| {
+            let number = input_data
+                .value
+                .parse::<JsonNumber>()
+                .map(JsonValue::Number)
+                .unwrap_or(JsonValue::Null);
+            StoreRequest::EditedInput(key_callback.clone(), number)
+        });
+
+        let value = match ctx.data.get_at(&key) {
+            Some(JsonValue::Number(n)) => Some(n.to_string()),
+            _ => None,
+        };
+        let step = self
+            .step
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "any".to_owned());
+
+        html! {
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <div class="control slider-control">
+                    <input
+                        class="slider"
+                        type="range"
+                        id=input_id(&key)
+                        aria-describedby=described_by(self, &key, ctx)
+                        value=value.clone().unwrap_or_default()
+                        oninput=&on_input
+                        onblur=StateStore::callback(|_| StoreRequest::Flush)
+                        min=self.min.to_string()
+                        max=self.max.to_string()
+                        step=step
+                        />
+                    <output class="slider-value" for=input_id(&key)>{ value.unwrap_or_else(|| "-".to_owned()) }</output>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for SelectInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+
+        let key_inner = key.clone();
+        let on_change = StateStore::callback(move |change: ChangeData| {
+            let value = match change {
+                ChangeData::Select(select) => select.value().filter(|value| !value.is_empty()),
+                _ => None,
+            };
+            StoreRequest::EditedInput(
+                key_inner.clone(),
+                value.map(JsonValue::String).unwrap_or(JsonValue::Null),
+            )
+        });
+
+        let selected = ctx.data.get_str_at(&key).ok().flatten();
+        let render_option = |option: &SelectOption| {
+            html! {
+                <option value=&option.value selected=(selected == Some(option.value.as_str()))>
+                    { option.label() }
+                </option>
+            }
+        };
+
+        html! {
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <div class="control">
+                    <div class="select">
+                        <select
+                            onchange=on_change
+                            id=input_id(&key)
+                            aria-describedby=described_by(self, &key, ctx)
+                            aria-invalid=ctx.error(&key).is_some().to_string()
+                            aria-required=self.required().to_string()>
+                            <option value="" selected=selected.is_none()>{ tr("Choose...") }</option>
+                            { for self
+                                .available_options(ctx.data, key_base)
+                                .into_iter()
+                                .map(render_option) }
+                        </select>
+                    </div>
+                </div>
+                { render_error(&key, ctx) }
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for RemoteSelectInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+        let value = ctx
+            .data
+            .get_str_at(&key)
+            .ok()
+            .flatten()
+            .map(ToOwned::to_owned);
+        let key_inner = key.clone();
+        let on_select =
+            StateStore::callback(move |value| StoreRequest::EditedInput(key_inner.clone(), value));
+
+        html! {
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <RemoteSelect
+                    id=input_id(&key)
+                    url=self.url.clone()
+                    value=value
+                    placeholder=self.placeholder().map(ToOwned::to_owned)
+                    on_select=on_select
+                    />
+                { render_error(&key, ctx) }
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for MultiSelectInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+
+        let selected: Vec<&str> = ctx
+            .data
+            .get_at(&key)
+            .and_then(JsonValue::as_array)
+            .map(|values| values.iter().filter_map(JsonValue::as_str).collect())
+            .unwrap_or_default();
+        let render_option = |option: &SelectOption| {
+            let checked = selected.contains(&option.value.as_str());
+            // Keep the selection in the order of the options
+            let new_selection: JsonValue = self
+                .options
+                .iter()
+                .filter(|o| (o.value == option.value) != selected.contains(&o.value.as_str()))
+                .map(|o| JsonValue::String(o.value.clone()))
+                .collect();
+            let key_inner = key.clone();
+            let on_click = StateStore::callback(move |_: ClickEvent| {
+                StoreRequest::EditedInput(key_inner.clone(), new_selection.clone())
+            });
+
+            html! {
+                <label class="checkbox">
+                    <input type="checkbox" checked=checked onclick=on_click />
+                    { " " }{ option.label() }
+                </label>
+            }
+        };
+
+        html! {
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <div
+                    class="control multi-select"
+                    id=input_id(&key)
+                    role="group"
+                    aria-labelledby=label_id(&key)
+                    aria-describedby=described_by(self, &key, ctx)>
+                    { for self.options.iter().map(render_option) }
+                </div>
+                { render_error(&key, ctx) }
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for RadioInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+        let name = format!("input_radio_{}", key);
+
+        let selected = ctx.data.get_str_at(&key).ok().flatten();
+        let render_option = |option: &SelectOption| {
+            let key_inner = key.clone();
+            let value = option.value.clone();
+            let on_click = StateStore::callback(move |_: ClickEvent| {
+                StoreRequest::EditedInput(key_inner.clone(), JsonValue::String(value.clone()))
+            });
+
+            html! {
+                <label class="radio">
+                    <input
+                        type="radio"
+                        name=&name
+                        checked=(selected == Some(option.value.as_str()))
+                        onclick=on_click
+                        />
+                    { " " }{ option.label() }
+                </label>
+            }
+        };
+
+        html! {
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <div
+                    class="control"
+                    id=input_id(&key)
+                    role="radiogroup"
+                    aria-labelledby=label_id(&key)
+                    aria-describedby=described_by(self, &key, ctx)>
+                    { for self.options.iter().map(render_option) }
+                </div>
+                { render_error(&key, ctx) }
             </div>
         }
     }
 }
 
 impl RenderableInput for ListInput {
-    fn render(&self, key_base: &Path, inputs_data: &InputsData, link: &AppComponentLink) -> Html {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
         let key = key_base + self.key();
 
-        let list_data = inputs_data.get_at(&key);
+        let list_data = ctx.data.get_at(&key);
         let len = list_data
             .map(JsonValue::as_array)
             .flatten()
             .map(Vec::len)
             .unwrap_or(0);
 
-        let on_resize = |key: Path, new_size| {
-            link.callback(move |_: ClickEvent| {
-                app::Msg::ListInputSizeChanged(key.clone(), new_size)
+        // The focus goes to the added element, or to the add button when the
+        // focused one is removed
+        let add_button_id = format!("{}-add", input_id(&key));
+        let on_resize = |key: Path, new_size, focused: String| {
+            StateStore::callback(move |_: ClickEvent| {
+                focus_later(focused.clone());
+                StoreRequest::ListInputSizeChanged(key.clone(), new_size)
             })
         };
-        let on_grow = on_resize(key.clone(), len + 1);
-        let on_shrink = on_resize(key.clone(), len.saturating_sub(1));
+        let on_grow = on_resize(key.clone(), len + 1, input_id(&(&key + Path::from(len))));
+        let on_shrink = on_resize(key.clone(), len.saturating_sub(1), add_button_id.clone());
+        let allowed_sizes = self.allowed_sizes();
+        let can_grow = len < *allowed_sizes.end();
+        let can_shrink = len > *allowed_sizes.start();
 
-        let render_list_elem = |key_base: Path| {
+        let nested = ctx.nested(self);
+        let render_list_elem = |(index, key_base): (usize, Path)| {
             let key_base_inner = key_base.clone();
-            let on_delete =
-                link.callback(move |_: ClickEvent| app::Msg::RemoveAt(key_base_inner.clone()));
+            let add_button_id = add_button_id.clone();
+            let on_delete = StateStore::callback(move |_: ClickEvent| {
+                focus_later(add_button_id.clone());
+                StoreRequest::RemoveAt(key_base_inner.clone())
+            });
+
+            let key_base_inner = key_base.clone();
+            let duplicate_id = input_id(&(&key + Path::from(index + 1)));
+            let on_duplicate = StateStore::callback(move |_: ClickEvent| {
+                focus_later(duplicate_id.clone());
+                StoreRequest::DuplicateAt(key_base_inner.clone())
+            });
+
+            // The arrow keys swap the element with its neighbour, as dragging
+            // it does
+            let key_base_inner = key_base.clone();
+            let key_inner = key.clone();
+            let on_handle_keydown = StateStore::batch_callback(move |event: KeyDownEvent| {
+                let new_index = match event.key().as_str() {
+                    "ArrowUp" if index > 0 => index - 1,
+                    "ArrowDown" if index + 1 < len => index + 1,
+                    _ => return vec![],
+                };
+                event.prevent_default();
+                focus_later(handle_id(&(&key_inner + Path::from(new_index))));
+                vec![StoreRequest::SwapAt(key_base_inner.clone(), new_index)]
+            });
+
+            let key_base_inner = key_base.clone();
+            let on_drag_start = StateStore::batch_callback(move |event: DragStartEvent| {
+                if let Some(data_transfer) = event.data_transfer() {
+                    data_transfer.set_data(LIST_ITEM_MIME_TYPE, &key_base_inner.to_pointer());
+                }
+                vec![]
+            });
+            let on_drag_over = StateStore::batch_callback(|event: DragOverEvent| {
+                let is_list_item = match event.data_transfer() {
+                    Some(data_transfer) => data_transfer
+                        .types()
+                        .iter()
+                        .any(|t| t == LIST_ITEM_MIME_TYPE),
+                    None => false,
+                };
+                if is_list_item {
+                    // Allows the drop
+                    event.prevent_default();
+                }
+                vec![]
+            });
+            let key_inner = key.clone();
+            let on_drop = StateStore::batch_callback(move |event: DragDropEvent| {
+                let dragged = event
+                    .data_transfer()
+                    .map(|data_transfer| data_transfer.get_data(LIST_ITEM_MIME_TYPE))
+                    .and_then(|pointer| Path::from_pointer(&pointer).ok());
+                let dragged = match dragged {
+                    Some(dragged) => dragged,
+                    None => return vec![],
+                };
+                // Only reorder the elements of this list
+                if !(0..len).any(|i| &key_inner + Path::from(i) == dragged) {
+                    return vec![];
+                }
+                event.prevent_default();
+                vec![StoreRequest::MoveAt(dragged, index)]
+            });
 
             html! {
-                <div class="input-group-children">
-                    <a class="delete" onclick=on_delete></a>
+                <div
+                    class="input-group-children"
+                    id=input_id(&key_base)
+                    role="group"
+                    aria-label=format!("{} {}", self.name(), index + 1)
+                    ondragover=on_drag_over
+                    ondrop=on_drop>
+                    { if can_shrink {
+                        html! {
+                            <button type="button" class="delete" aria-label=tr("Remove") onclick=on_delete></button>
+                        }
+                    } else {
+                        html! {}
+                    } }
+                    { if can_grow {
+                        html! {
+                            <button
+                                type="button"
+                                class="icon duplicate"
+                                title=tr("Duplicate")
+                                aria-label=tr("Duplicate")
+                                onclick=on_duplicate>
+                                <i class="fas fa-clone"></i>
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    } }
+                    <button
+                        type="button"
+                        class="icon drag-handle"
+                        id=handle_id(&key_base)
+                        draggable="true"
+                        ondragstart=on_drag_start
+                        onkeydown=on_handle_keydown
+                        title=tr("Drag to reorder")
+                        aria-label=tr("Drag to reorder, or use the up and down arrow keys")>
+                        <i class="fas fa-grip-vertical"></i>
+                    </button>
                     { for self
                         .inputs
                         .iter()
-                        .map(|input| input.render(&key_base, &inputs_data, &link))
+                        .map(|input| input.render(&key_base, &nested))
                     }
                 </div>
             }
@@ -167,20 +788,35 @@ impl RenderableInput for ListInput {
 
         html! {
             <div class="field input-group">
-                <p class="label">{ self.name() }</p>
-                { render_description(self.description()) }
+                <p class="label" id=label_id(&key)>
+                    { render_name(self, &key) }
+                    { render_reset(self, &key, ctx) }
+                    { render_paste(&key) }
+                </p>
+                { render_error(&key, ctx) }
 
                 { for (0..len)
-                    .map(|i| &key + Path::from(i))
+                    .map(|i| (i, &key + Path::from(i)))
                     .map(render_list_elem) }
 
                 <div class="buttons has-addons">
-                    <button class="button is-small" onclick=on_grow>
+                    <button
+                        class="button is-small"
+                        id=add_button_id
+                        title=tr("Add an element")
+                        aria-label=tr("Add an element")
+                        onclick=on_grow
+                        disabled=!can_grow>
                         <span class="icon is-small">
                             <i class="fas fa-plus"></i>
                         </span>
                     </button>
-                    <button class="button is-small" onclick=on_shrink disabled=(len == 0)>
+                    <button
+                        class="button is-small"
+                        title=tr("Remove the last element")
+                        aria-label=tr("Remove the last element")
+                        onclick=on_shrink
+                        disabled=!can_shrink>
                         <span class="icon is-small">
                             <i class="fas fa-minus"></i>
                         </span>
@@ -191,18 +827,201 @@ impl RenderableInput for ListInput {
     }
 }
 
+impl RenderableInput for TableInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+
+        let len = ctx
+            .data
+            .get_at(&key)
+            .and_then(JsonValue::as_array)
+            .map_or(0, Vec::len);
+        let allowed_sizes = self.allowed_sizes();
+        let can_grow = len < *allowed_sizes.end();
+        let can_shrink = len > *allowed_sizes.start();
+
+        let key_inner = key.clone();
+        let added_id = input_id(&(&key + Path::from(len)));
+        let on_add = StateStore::callback(move |_: ClickEvent| {
+            focus_later(added_id.clone());
+            StoreRequest::ListInputSizeChanged(key_inner.clone(), len + 1)
+        });
+        let add_button_id = format!("{}-add", input_id(&key));
+
+        // Filtering the columns would misalign the cells and the headers
+        let columns = InputsContext { filter: "", ..*ctx };
+        let render_row = |(index, row_key): (usize, Path)| {
+            let swap_with = |other_index| {
+                let row_key = row_key.clone();
+                StateStore::callback(move |_: ClickEvent| {
+                    StoreRequest::SwapAt(row_key.clone(), other_index)
+                })
+            };
+            let row_key_inner = row_key.clone();
+            let add_button_id = add_button_id.clone();
+            let on_remove = StateStore::callback(move |_: ClickEvent| {
+                focus_later(add_button_id.clone());
+                StoreRequest::RemoveAt(row_key_inner.clone())
+            });
+
+            html! {
+                <tr id=input_id(&row_key)>
+                    { for self.columns.iter().map(|column| html! {
+                        <td>{ column.render(&row_key, &columns) }</td>
+                    }) }
+                    <td class="input-table-actions">
+                        <div class="buttons has-addons">
+                            <button
+                                class="button is-small"
+                                title=tr("Move up")
+                                onclick=swap_with(index.saturating_sub(1))
+                                disabled=(index == 0)>
+                                <span class="icon is-small"><i class="fas fa-arrow-up"></i></span>
+                            </button>
+                            <button
+                                class="button is-small"
+                                title=tr("Move down")
+                                onclick=swap_with(index + 1)
+                                disabled=(index + 1 == len)>
+                                <span class="icon is-small"><i class="fas fa-arrow-down"></i></span>
+                            </button>
+                            <button
+                                class="button is-small"
+                                title=tr("Remove")
+                                onclick=on_remove
+                                disabled=!can_shrink>
+                                <span class="icon is-small"><i class="fas fa-times"></i></span>
+                            </button>
+                        </div>
+                    </td>
+                </tr>
+            }
+        };
+
+        html! {
+            <div class="field input-group">
+                <p class="label" id=label_id(&key)>
+                    { render_name(self, &key) }
+                    { render_reset(self, &key, ctx) }
+                    { render_paste(&key) }
+                </p>
+                <div class="table-container">
+                    <table class="table is-narrow is-fullwidth input-table" aria-labelledby=label_id(&key)>
+                        <thead>
+                            <tr>
+                                { for self.columns.iter().map(|column| html! {
+                                    <th>{ render_name(column, &(&key + column.key())) }</th>
+                                }) }
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for (0..len)
+                                .map(|i| (i, &key + Path::from(i)))
+                                .map(render_row) }
+                        </tbody>
+                    </table>
+                </div>
+                { render_error(&key, ctx) }
+                <button class="button is-small" id=add_button_id onclick=on_add disabled=!can_grow>
+                    <span class="icon is-small">
+                        <i class="fas fa-plus"></i>
+                    </span>
+                    <span>{ tr("Add a row") }</span>
+                </button>
+            </div>
+        }
+    }
+}
+
+impl RenderableInput for TagsInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+
+        let tags: Vec<String> = ctx
+            .data
+            .get_at(&key)
+            .and_then(JsonValue::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let key_inner = key.clone();
+        let tags_inner = tags.clone();
+        let on_keypress = StateStore::batch_callback(move |event: KeyPressEvent| {
+            if event.key() != "Enter" {
+                return vec![];
+            }
+            let input: InputElement = match event.target().and_then(|t| t.try_into().ok()) {
+                Some(input) => input,
+                None => return vec![],
+            };
+            let tag = input.raw_value().trim().to_owned();
+            input.set_raw_value("");
+            if tag.is_empty() || tags_inner.contains(&tag) {
+                return vec![];
+            }
+
+            let mut tags = tags_inner.clone();
+            tags.push(tag);
+            vec![StoreRequest::EditedInput(key_inner.clone(), tags.into())]
+        });
+
+        let render_tag = |(i, tag): (usize, &String)| {
+            let mut remaining = tags.clone();
+            remaining.remove(i);
+            let key_inner = key.clone();
+            let on_delete = StateStore::callback(move |_: ClickEvent| {
+                StoreRequest::EditedInput(key_inner.clone(), remaining.clone().into())
+            });
+
+            html! {
+                <span class="tag is-info">
+                    { tag }
+                    <button class="delete is-small" aria-label=tr("Remove") onclick=on_delete></button>
+                </span>
+            }
+        };
+
+        html! {
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <div class="tags">
+                    { for tags.iter().enumerate().map(render_tag) }
+                </div>
+                <div class="control">
+                    <input
+                        class="input"
+                        type="text"
+                        id=input_id(&key)
+                        aria-describedby=described_by(self, &key, ctx)
+                        placeholder=self.placeholder().unwrap_or_else(|| tr("Type and press Enter to add"))
+                        onkeypress=on_keypress
+                        />
+                </div>
+                { render_error(&key, ctx) }
+            </div>
+        }
+    }
+}
+
 impl RenderableInput for BooleanInput {
-    fn render(&self, key_base: &Path, inputs_data: &InputsData, link: &AppComponentLink) -> Html {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
         let key = key_base + self.key();
 
         let key_inner = key.clone();
         let on_click = |b| {
-            link.callback(move |_: ClickEvent| {
-                app::Msg::EditedInput(key_inner.clone(), JsonValue::Bool(b))
+            StateStore::callback(move |_: ClickEvent| {
+                StoreRequest::EditedInput(key_inner.clone(), JsonValue::Bool(b))
             })
         };
 
-        let checked = match inputs_data.get_at(&key) {
+        let checked = match ctx.data.get_at(&key) {
             Some(JsonValue::Null) => false,
             Some(JsonValue::Bool(b)) => *b,
             Some(JsonValue::Number(n)) => n.as_f64() != Some(0.0) && n.as_f64().is_some(),
@@ -211,24 +1030,248 @@ impl RenderableInput for BooleanInput {
             None => false,
         };
         //let color_class = if checked { "is-success" } else { "is-danger" };
-        let id = format!("input_boolean_{}", key);
+        let id = input_id(&key);
 
         html! {
             <div class="field">
-                <input id=id name=id type="checkbox" class="switch" checked=checked onclick=on_click(!checked) />
-                <label for=id class="label">{ self.name() }</label>
-                { render_description(self.description()) }
+                <input
+                    id=&id
+                    name=&id
+                    type="checkbox"
+                    class="switch"
+                    checked=checked
+                    aria-describedby=described_by(self, &key, ctx)
+                    onclick=on_click(!checked)
+                    />
+                <label for=id id=label_id(&key) class="label">{ self.name() }</label>
+                { render_help(self, &key) }
+                { render_reset(self, &key, ctx) }
             </div>
         }
     }
 }
 
-fn render_description<T: AsRef<str>>(description: Option<T>) -> Html {
-    if let Some(text) = description {
+/// Renders the field of the inputs of a type added with
+/// `register_input_type`, under their label.
+pub trait CustomInputView {
+    /// `on_change` puts a new value in the inputs data, once validated by
+    /// the plugin of the input.
+    fn render(
+        &self,
+        input: &CustomInput,
+        value: Option<&JsonValue>,
+        on_change: Callback<JsonValue>,
+    ) -> Html;
+}
+
+/// Sets the view of the inputs of a type registered with
+/// `register_input_type`, replacing the previous one, if any.
+pub fn register_input_view(type_name: &str, view: impl CustomInputView + 'static) {
+    CUSTOM_VIEWS.with(|views| {
+        views
+            .borrow_mut()
+            .insert(type_name.to_owned(), Rc::new(view))
+    });
+}
+
+impl RenderableInput for CustomInput {
+    fn render(&self, key_base: &Path, ctx: &InputsContext) -> Html {
+        let key = key_base + self.key();
+
+        let view = CUSTOM_VIEWS.with(|views| views.borrow().get(&self.type_name).cloned());
+        let field = match view {
+            Some(view) => {
+                let key_inner = key.clone();
+                let on_change = StateStore::callback(move |value| {
+                    StoreRequest::EditedInput(key_inner.clone(), value)
+                });
+                view.render(self, ctx.data.get_at(&key), on_change)
+            }
+            None => html! {
+                <p class="help is-warning">
+                    { tr!("No view is registered for the inputs of type '{}'.", self.type_name) }
+                </p>
+            },
+        };
+
         html! {
-            <p class="help">{ text.as_ref() }</p>
+            <div class="field">
+                { render_label(self, &key, ctx) }
+                <div class="control" id=input_id(&key)>{ field }</div>
+                { render_error(&key, ctx) }
+            </div>
         }
+    }
+}
+
+/// Returns the id of the field of the input at `key`, which its label and
+/// the focus refer to.
+fn input_id(key: &Path) -> String {
+    format!("input-{}", key)
+}
+
+fn label_id(key: &Path) -> String {
+    format!("input-{}-label", key)
+}
+
+fn help_id(key: &Path) -> String {
+    format!("input-{}-help", key)
+}
+
+fn error_id(key: &Path) -> String {
+    format!("input-{}-error", key)
+}
+
+/// Returns the id of the handle moving the list element at `key`.
+fn handle_id(key: &Path) -> String {
+    format!("input-{}-handle", key)
+}
+
+/// Returns the ids of the help and of the error of the input at `key`, for
+/// `aria-describedby`.
+fn described_by(input: &impl Input, key: &Path, ctx: &InputsContext) -> String {
+    let mut ids = vec![];
+    if input.description().is_some() || input.help_url().is_some() {
+        ids.push(help_id(key));
+    }
+    if ctx.error(key).is_some() {
+        ids.push(error_id(key));
+    }
+    ids.join(" ")
+}
+
+/// Focuses the element `id` once it is rendered, or its first field if it
+/// contains some, eg. when a list element is added.
+fn focus_later(id: String) {
+    js! { @(no_return)
+        var id = @{id};
+        setTimeout(function() {
+            var element = document.getElementById(id);
+            if (element) {
+                (element.querySelector("input, select, textarea") || element).focus();
+            }
+        }, 0);
+    }
+}
+
+/// Renders the label of the field of an input, with its reset button.
+fn render_label(input: &impl Input, key: &Path, ctx: &InputsContext) -> Html {
+    html! {
+        <label class="label" for=input_id(key) id=label_id(key)>
+            { render_name(input, key) }
+            { render_reset(input, key, ctx) }
+            { render_presence(key, ctx) }
+        </label>
+    }
+}
+
+fn render_error(key: &Path, ctx: &InputsContext) -> Html {
+    match ctx.error(key) {
+        Some(error) => html! {
+            <p class="help is-danger" id=error_id(key) role="alert">{ error }</p>
+        },
+        None => html! {},
+    }
+}
+
+/// Renders the name of an input, with a mark if it is required, and its help.
+fn render_name(input: &impl Input, key: &Path) -> Html {
+    let required = if input.required() {
+        html! { <span class="has-text-danger" title=tr("Required") aria-hidden="true">{ " *" }</span> }
     } else {
         html! {}
+    };
+
+    html! {
+        <>
+            { input.name() }
+            { required }
+            { render_help(input, key) }
+        </>
+    }
+}
+
+/// Renders a button putting back the default value of the input, when it
+/// was changed.
+fn render_reset(input: &impl Input, key: &Path, ctx: &InputsContext) -> Html {
+    let is_default =
+        !ctx.invalid.contains_key(key) && ctx.data.get_at(key) == input.default_value();
+    if is_default {
+        return html! {};
+    }
+
+    let key = key.clone();
+    let on_reset = StateStore::callback(move |_: ClickEvent| StoreRequest::ResetAt(key.clone()));
+    html! {
+        <button
+            type="button"
+            class="icon input-reset"
+            title=tr("Reset to the default value")
+            aria-label=tr("Reset to the default value")
+            onclick=on_reset>
+            <i class="fas fa-undo"></i>
+        </button>
+    }
+}
+
+/// Renders the peers editing the input, when syncing with a server.
+fn render_presence(key: &Path, ctx: &InputsContext) -> Html {
+    let render_peer = |peer: &String| {
+        html! {
+            <span class="tag is-warning is-light input-presence" title=tr!("{} is editing it", peer)>
+                <span class="icon is-small"><i class="fas fa-user-edit"></i></span>
+                <span>{ peer }</span>
+            </span>
+        }
+    };
+    match ctx.presence.get(key) {
+        Some(peers) => html! { { for peers.iter().map(render_peer) } },
+        None => html! {},
+    }
+}
+
+/// Renders a button merging JSON from the clipboard in a group or a list.
+fn render_paste(key: &Path) -> Html {
+    let key = key.clone();
+    let on_paste =
+        StateStore::callback(move |_: ClickEvent| StoreRequest::PasteJsonAt(key.clone()));
+    html! {
+        <button
+            type="button"
+            class="icon input-paste"
+            title=tr("Paste JSON from the clipboard")
+            aria-label=tr("Paste JSON from the clipboard")
+            onclick=on_paste>
+            <i class="fas fa-paste"></i>
+        </button>
+    }
+}
+
+/// Renders a help icon which shows the description of the input, and a
+/// link to its documentation, when hovered.
+fn render_help(input: &impl Input, key: &Path) -> Html {
+    if input.description().is_none() && input.help_url().is_none() {
+        return html! {};
+    }
+
+    let link = match input.help_url().filter(|url| is_safe_url(url)) {
+        Some(url) => html! {
+            <a href=url target="_blank" rel="noopener noreferrer">
+                { tr("More information") }
+            </a>
+        },
+        None => html! {},
+    };
+
+    html! {
+        <span class="input-help" tabindex="0" aria-label=tr("Help")>
+            <span class="icon has-text-info" aria-hidden="true">
+                <i class="fas fa-question-circle"></i>
+            </span>
+            <div class="input-help-popover box" id=help_id(key) role="tooltip">
+                { input.description().map_or_else(|| html! {}, render_markdown) }
+                { link }
+            </div>
+        </span>
     }
 }
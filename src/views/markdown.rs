@@ -0,0 +1,111 @@
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag};
+use yew::{
+    virtual_dom::{VNode, VTag, VText},
+    Html,
+};
+
+/// Renders a Markdown text as virtual DOM nodes.
+///
+/// Raw HTML is displayed as text, so that a scenario can't inject markup in
+/// the page.
+pub fn render_markdown(source: &str) -> Html {
+    let mut stack: Vec<VTag> = vec![VTag::new("div")];
+    for event in Parser::new(source) {
+        match event {
+            Event::Start(tag) => stack.push(open_tag(tag)),
+            Event::End(_) => {
+                if stack.len() > 1 {
+                    let tag = stack.pop().unwrap();
+                    push_child(&mut stack, tag.into());
+                }
+            }
+            Event::Text(text) | Event::Html(text) => {
+                push_child(&mut stack, VText::new(text.into_string()).into())
+            }
+            Event::Code(code) => {
+                let mut tag = VTag::new("code");
+                tag.add_child(VText::new(code.into_string()).into());
+                push_child(&mut stack, tag.into());
+            }
+            Event::SoftBreak => push_child(&mut stack, VText::new(" ".to_owned()).into()),
+            Event::HardBreak => push_child(&mut stack, VTag::new("br").into()),
+            Event::Rule => push_child(&mut stack, VTag::new("hr").into()),
+            Event::FootnoteReference(_) | Event::TaskListMarker(_) => {}
+        }
+    }
+
+    let mut root = stack.swap_remove(0);
+    root.add_class("content");
+    root.into()
+}
+
+fn push_child(stack: &mut [VTag], child: VNode) {
+    if let Some(parent) = stack.last_mut() {
+        parent.add_child(child);
+    }
+}
+
+fn open_tag(tag: Tag) -> VTag {
+    match tag {
+        Tag::Paragraph => VTag::new("p"),
+        Tag::Heading(level) => VTag::new(format!("h{}", level.min(6))),
+        Tag::BlockQuote => VTag::new("blockquote"),
+        Tag::CodeBlock(kind) => {
+            let mut pre = VTag::new("pre");
+            if let CodeBlockKind::Fenced(lang) = kind {
+                if !lang.is_empty() {
+                    pre.add_attribute("data-lang", &lang);
+                }
+            }
+            pre
+        }
+        Tag::List(Some(start)) => {
+            let mut list = VTag::new("ol");
+            if start != 1 {
+                list.add_attribute("start", &start);
+            }
+            list
+        }
+        Tag::List(None) => VTag::new("ul"),
+        Tag::Item => VTag::new("li"),
+        Tag::Emphasis => VTag::new("em"),
+        Tag::Strong => VTag::new("strong"),
+        Tag::Strikethrough => VTag::new("del"),
+        Tag::Link(_, url, title) => {
+            let mut link = VTag::new("a");
+            if is_safe_url(&url) {
+                link.add_attribute("href", &url);
+            }
+            link.add_attribute("target", &"_blank");
+            link.add_attribute("rel", &"noopener noreferrer");
+            if !title.is_empty() {
+                link.add_attribute("title", &title);
+            }
+            link
+        }
+        Tag::Image(_, _, title) => {
+            // Images would be too big for a tooltip: only keep their alt text.
+            let mut span = VTag::new("span");
+            if !title.is_empty() {
+                span.add_attribute("title", &title);
+            }
+            span
+        }
+        Tag::FootnoteDefinition(_)
+        | Tag::Table(_)
+        | Tag::TableHead
+        | Tag::TableRow
+        | Tag::TableCell => VTag::new("span"),
+    }
+}
+
+/// Rejects the URLs which could run code, like `javascript:` ones.
+pub fn is_safe_url(url: &str) -> bool {
+    match url.find(':') {
+        Some(colon) if !url[..colon].contains('/') => {
+            let scheme = url[..colon].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
@@ -1,3 +1,13 @@
+mod highlight;
 mod inputs;
+mod markdown;
+mod output_diff;
+mod output_search;
+mod snapshots;
 
-pub use inputs::RenderableInput;
+pub use highlight::{highlight, is_known_language};
+pub use inputs::{register_input_view, CustomInputView, InputsContext, RenderableInput};
+pub use markdown::render_markdown;
+pub use output_diff::render_output_diff;
+pub use output_search::{find_matches, render_matches, render_search_box, scroll_to_current_match};
+pub use snapshots::render_snapshots;
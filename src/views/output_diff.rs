@@ -0,0 +1,113 @@
+use crate::i18n::tr;
+use yew::{html, Html};
+
+/// A line of the diff between two outputs.
+#[derive(Debug, PartialEq)]
+enum Line<'a> {
+    Same(&'a str),
+    Added(&'a str),
+    Removed(&'a str),
+}
+
+/// Diffs the lines of two texts with their longest common subsequence,
+/// after skipping their common first and last lines.
+fn diff_lines<'a>(before: &'a str, after: &'a str) -> Vec<Line<'a>> {
+    let before: Vec<_> = before.lines().collect();
+    let after: Vec<_> = after.lines().collect();
+    let prefix = before
+        .iter()
+        .zip(&after)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old, new) = (
+        &before[prefix..before.len() - suffix],
+        &after[prefix..after.len() - suffix],
+    );
+
+    // lengths[i][j]: length of the common subsequence of old[i..] and new[j..]
+    let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<_> = before[..prefix]
+        .iter()
+        .map(|line| Line::Same(line))
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lengths[i + 1][j] >= lengths[i][j + 1]) {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(
+        before[before.len() - suffix..]
+            .iter()
+            .map(|line| Line::Same(line)),
+    );
+    lines
+}
+
+/// Renders the lines added to and removed from the pinned output.
+pub fn render_output_diff(pinned: &str, output: &str) -> Html {
+    let lines = diff_lines(pinned, output);
+    if lines.iter().all(|line| matches!(line, Line::Same(_))) {
+        return html! { <p class="has-text-grey">{ tr("The output didn't change since it was pinned.") }</p> };
+    }
+
+    let render_line = |line: &Line| match line {
+        Line::Same(line) => html! { <span class="diff-line">{ "  " }{ line }</span> },
+        Line::Added(line) => html! { <span class="diff-line diff-added">{ "+ " }{ line }</span> },
+        Line::Removed(line) => {
+            html! { <span class="diff-line diff-removed">{ "- " }{ line }</span> }
+        }
+    };
+    html! {
+        <pre>{ for lines.iter().map(render_line) }</pre>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_outputs() {
+        let before = "server {\n  listen 80;\n  root /var/www;\n}\n";
+        let after = "server {\n  listen 443;\n  root /var/www;\n  index index.html;\n}\n";
+        assert_eq!(
+            diff_lines(before, after),
+            [
+                Line::Same("server {"),
+                Line::Removed("  listen 80;"),
+                Line::Added("  listen 443;"),
+                Line::Same("  root /var/www;"),
+                Line::Added("  index index.html;"),
+                Line::Same("}"),
+            ]
+        );
+        assert!(diff_lines(before, before)
+            .iter()
+            .all(|line| matches!(line, Line::Same(_))));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{app::Msg, prelude::*};
+use std::ops::Range;
+use stdweb::{js, web::event::IKeyboardEvent};
+use yew::virtual_dom::{VList, VTag, VText};
+
+type AppComponentLink = yew::ComponentLink<crate::app::App>;
+
+/// Finds the occurrences of `query` in `text`, ignoring the ASCII case.
+pub fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return vec![];
+    }
+    // Lowercasing the ASCII letters keeps the byte offsets
+    let text = text.to_ascii_lowercase();
+    let query = query.to_ascii_lowercase();
+    text.match_indices(&query)
+        .map(|(start, _)| start..start + query.len())
+        .collect()
+}
+
+/// Renders a text with its matches in `<mark>`, the current one being
+/// scrolled to, see `scroll_to_current_match`.
+pub fn render_matches(text: &str, matches: &[Range<usize>], current: usize) -> Html {
+    let mut list = VList::new();
+    let mut end = 0;
+    for (i, range) in matches.iter().enumerate() {
+        list.add_child(VText::new(text[end..range.start].to_owned()).into());
+        let mut mark = VTag::new("mark");
+        if i == current {
+            mark.add_class("search-current");
+        }
+        mark.add_child(VText::new(text[range.clone()].to_owned()).into());
+        list.add_child(mark.into());
+        end = range.end;
+    }
+    list.add_child(VText::new(text[end..].to_owned()).into());
+    list.into()
+}
+
+/// Scrolls to the current match once the output is rendered.
+pub fn scroll_to_current_match() {
+    js! { @(no_return)
+        setTimeout(function() {
+            var current = document.querySelector(".search-current");
+            if (current) {
+                current.scrollIntoView({ block: "center" });
+            }
+        }, 0);
+    }
+}
+
+/// Renders the find box of the output, with the position of the current
+/// match among the `count` ones.
+pub fn render_search_box(
+    query: &str,
+    current: usize,
+    count: usize,
+    link: &AppComponentLink,
+) -> Html {
+    let position = match count {
+        _ if query.is_empty() => String::new(),
+        0 => tr("No match").to_owned(),
+        count => tr!("{} of {}", current + 1, count),
+    };
+    // Enter goes to the next match, and Shift+Enter to the previous one
+    let on_keypress = link.batch_callback(|event: KeyPressEvent| match event.key().as_str() {
+        "Enter" if event.shift_key() => vec![Msg::PreviousMatch],
+        "Enter" => vec![Msg::NextMatch],
+        _ => vec![],
+    });
+    html! {
+        <div class="field has-addons output-search">
+            <div class="control has-icons-left">
+                <input
+                    class="input is-small"
+                    type="search"
+                    placeholder=tr("Find in the output")
+                    value=query
+                    oninput=link.callback(|input: InputData| Msg::SearchOutput(input.value))
+                    onkeypress=on_keypress
+                    />
+                <span class="icon is-small is-left"><i class="fas fa-search"></i></span>
+            </div>
+            <div class="control">
+                <span class="button is-small is-static">{ position }</span>
+            </div>
+            <div class="control">
+                <button
+                    class="button is-small"
+                    title=tr("Previous match")
+                    disabled=count == 0
+                    onclick=link.callback(|_| Msg::PreviousMatch)>
+                    <span class="icon is-small"><i class="fas fa-chevron-up"></i></span>
+                </button>
+            </div>
+            <div class="control">
+                <button
+                    class="button is-small"
+                    title=tr("Next match")
+                    disabled=count == 0
+                    onclick=link.callback(|_| Msg::NextMatch)>
+                    <span class="icon is-small"><i class="fas fa-chevron-down"></i></span>
+                </button>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_in_output() {
+        assert_eq!(
+            find_matches("Listen 80;\nlisten 443;", "LISTEN"),
+            [0..6, 11..17]
+        );
+        assert!(find_matches("listen", "").is_empty());
+        assert_eq!(find_matches("café café", "é"), [3..5, 9..11]);
+    }
+}
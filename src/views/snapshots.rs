@@ -0,0 +1,104 @@
+use crate::{app::Msg, prelude::*, state::Snapshot, InputsData};
+
+type AppComponentLink = yew::ComponentLink<crate::app::App>;
+
+/// Renders the snapshots of the inputs data, and the differences with the
+/// compared one if any.
+pub fn render_snapshots(
+    snapshots: &[Snapshot],
+    inputs_data: &InputsData,
+    compared: Option<usize>,
+    link: &AppComponentLink,
+) -> Html {
+    let render_row = |(i, snapshot): (usize, &Snapshot)| {
+        let is_compared = compared == Some(i);
+        html! {
+            <tr class=if is_compared { "is-selected" } else { "" }>
+                <td>{ &snapshot.name }</td>
+                <td>
+                    <div class="buttons are-small is-right">
+                        <button class="button" onclick=link.callback(move |_| Msg::RestoreSnapshot(i))>
+                            { tr("Restore") }
+                        </button>
+                        <button
+                            class="button"
+                            onclick=link.callback(move |_| Msg::CompareSnapshot(if is_compared { None } else { Some(i) }))>
+                            { if is_compared { tr("Hide the differences") } else { tr("Compare") } }
+                        </button>
+                        <button class="button is-danger is-outlined" onclick=link.callback(move |_| Msg::DeleteSnapshot(i))>
+                            { tr("Delete") }
+                        </button>
+                    </div>
+                </td>
+            </tr>
+        }
+    };
+    let list = if snapshots.is_empty() {
+        html! {
+            <p class="has-text-grey">
+                { tr("Save the current inputs to restore them, or to compare them, later.") }
+            </p>
+        }
+    } else {
+        html! {
+            <table class="table is-fullwidth">
+                <tbody>
+                    { for snapshots.iter().enumerate().map(render_row) }
+                </tbody>
+            </table>
+        }
+    };
+    let differences = match compared.and_then(|i| snapshots.get(i)) {
+        Some(snapshot) => render_differences(snapshot, inputs_data),
+        None => html! {},
+    };
+
+    html! {
+        <div class="box">
+            <div class="level">
+                <div class="level-left">
+                    <h1 class="title">{ tr("Snapshots") }</h1>
+                </div>
+                <div class="level-right">
+                    <button class="button is-small" onclick=link.callback(|_| Msg::TakeSnapshot)>
+                        { tr("Take a snapshot") }
+                    </button>
+                </div>
+            </div>
+            { list }
+            { differences }
+        </div>
+    }
+}
+
+fn render_differences(snapshot: &Snapshot, inputs_data: &InputsData) -> Html {
+    let changes = snapshot.inputs_data.diff(inputs_data);
+    if changes.is_empty() {
+        return html! { <p>{ tr!("The inputs didn't change since '{}'.", snapshot.name) }</p> };
+    }
+
+    let render_value = |value: Option<&JsonValue>| match value {
+        Some(value) => html! { <code>{ value }</code> },
+        None => html! { <span class="has-text-grey">{ "empty" }</span> },
+    };
+    html! {
+        <table class="table is-fullwidth is-narrow">
+            <thead>
+                <tr>
+                    <th>{ tr("Input") }</th>
+                    <th>{ tr!("In '{}'", snapshot.name) }</th>
+                    <th>{ tr("Now") }</th>
+                </tr>
+            </thead>
+            <tbody>
+                { for changes.into_iter().map(|(path, before, after)| html! {
+                    <tr>
+                        <td>{ path }</td>
+                        <td>{ render_value(before) }</td>
+                        <td>{ render_value(after) }</td>
+                    </tr>
+                }) }
+            </tbody>
+        </table>
+    }
+}